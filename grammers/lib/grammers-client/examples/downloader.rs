@@ -117,7 +117,7 @@ async fn async_main() -> Result<()> {
     println!("Downloaded {counter} messages");
 
     if sign_out {
-        // TODO revisit examples and get rid of "handle references" (also, this panics)
+        // TODO revisit examples and get rid of "handle references"
         drop(client.sign_out_disconnect().await);
     }
 
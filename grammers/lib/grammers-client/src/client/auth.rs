@@ -11,6 +11,7 @@ use crate::types::{LoginToken, PasswordToken, TermsOfService, User};
 use crate::utils;
 use grammers_crypto::two_factor_auth::{calculate_2fa, check_p_and_g};
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_mtsender::{RpcError, RpcErrorKind, TelegramError};
 use grammers_tl_types as tl;
 use std::fmt;
 
@@ -24,6 +25,9 @@ pub enum SignInError {
     PasswordRequired(PasswordToken),
     InvalidCode,
     InvalidPassword,
+    /// Telegram sent password parameters that are missing, use an unknown algorithm or fail the
+    /// safety checks even after asking for them again, so the password cannot be checked.
+    InvalidPasswordParameters,
     Other(InvocationError),
 }
 
@@ -33,13 +37,11 @@ impl fmt::Display for SignInError {
         match self {
             SignUpRequired {
                 terms_of_service: tos,
-            } => write!(
-                f,
-                "sign in error: sign up with official client required: {tos:?}"
-            ),
+            } => write!(f, "sign in error: sign up required: {tos:?}"),
             PasswordRequired(_password) => write!(f, "2fa password required"),
             InvalidCode => write!(f, "sign in error: invalid code"),
             InvalidPassword => write!(f, "invalid password"),
+            InvalidPasswordParameters => write!(f, "sign in error: invalid password parameters"),
             Other(e) => write!(f, "sign in error: {e}"),
         }
     }
@@ -155,8 +157,12 @@ impl Client {
 
         let result = match self.invoke(&request).await {
             Ok(x) => x,
-            Err(InvocationError::Rpc(err)) if err.code == 303 => {
-                let dc_id = err.value.unwrap() as i32;
+            Err(InvocationError::Rpc(RpcError {
+                code: 303,
+                value: Some(dc_id),
+                ..
+            })) => {
+                let dc_id = dc_id as i32;
                 let (sender, request_tx) = connect_sender(dc_id, &self.0.config).await?;
                 {
                     *self.0.conn.sender.lock().await = sender;
@@ -173,9 +179,10 @@ impl Client {
             tl::enums::auth::Authorization::Authorization(x) => {
                 self.complete_login(x).await.map_err(Into::into)
             }
-            tl::enums::auth::Authorization::SignUpRequired(_) => {
-                panic!("API returned SignUpRequired even though we're logging in as a bot");
-            }
+            // Bots are created through @BotFather and never need to sign up.
+            tl::enums::auth::Authorization::SignUpRequired(_) => Err(
+                AuthorizationError::UnexpectedResponse("auth.authorizationSignUpRequired"),
+            ),
         }
     }
 
@@ -187,6 +194,9 @@ impl Client {
     /// via the console's standard input), you will need to [`Client::sign_in`] to complete the
     /// process.
     ///
+    /// Some phone numbers only receive the code after buying a subscription from an official
+    /// application, in which case [`AuthorizationError::PaymentRequired`] is returned.
+    ///
     /// # Examples
     ///
     /// ```
@@ -224,20 +234,20 @@ impl Client {
 
         use tl::enums::auth::SentCode as SC;
 
-        let sent_code: tl::types::auth::SentCode = match self.invoke(&request).await {
-            Ok(x) => match x {
-                SC::Code(code) => code,
-                SC::Success(_) => panic!("should not have logged in yet"),
-                SC::PaymentRequired(_) => todo!(),
-            },
-            Err(InvocationError::Rpc(err)) if err.code == 303 => {
+        let sent_code = match self.invoke(&request).await {
+            Ok(sent_code) => sent_code,
+            Err(InvocationError::Rpc(RpcError {
+                code: 303,
+                value: Some(dc_id),
+                ..
+            })) => {
                 // Since we are not logged in (we're literally requesting for
                 // the code to login now), there's no need to export the current
                 // authorization and re-import it at a different datacenter.
                 //
                 // Just connect and generate a new authorization key with it
                 // before trying again.
-                let dc_id = err.value.unwrap() as i32;
+                let dc_id = dc_id as i32;
                 let (sender, request_tx) = connect_sender(dc_id, &self.0.config).await?;
                 {
                     *self.0.conn.sender.lock().await = sender;
@@ -245,19 +255,25 @@ impl Client {
                     let mut state = self.0.state.write().unwrap();
                     state.dc_id = dc_id;
                 }
-                match self.invoke(&request).await? {
-                    SC::Code(code) => code,
-                    SC::Success(_) => panic!("should not have logged in yet"),
-                    SC::PaymentRequired(_) => todo!(),
-                }
+                self.invoke(&request).await?
             }
             Err(e) => return Err(e.into()),
         };
 
-        Ok(LoginToken {
-            phone: phone.to_string(),
-            phone_code_hash: sent_code.phone_code_hash,
-        })
+        match sent_code {
+            SC::Code(code) => Ok(LoginToken {
+                phone: phone.to_string(),
+                phone_code_hash: code.phone_code_hash,
+            }),
+            SC::PaymentRequired(payment) => Err(AuthorizationError::PaymentRequired {
+                store_product: payment.store_product,
+            }),
+            // Only sent when logging in again with the tokens of a previous login, which are
+            // never included in the request.
+            SC::Success(_) => Err(AuthorizationError::UnexpectedResponse(
+                "auth.sentCodeSuccess",
+            )),
+        }
     }
 
     /// Signs in to the user account.
@@ -319,18 +335,105 @@ impl Client {
                     terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
                 })
             }
-            Err(err) if err.is("SESSION_PASSWORD_NEEDED") => {
+            Err(err) if err.kind() == Some(RpcErrorKind::PasswordRequired) => {
                 let password_token = self.get_password_information().await;
                 match password_token {
                     Ok(token) => Err(SignInError::PasswordRequired(token)),
                     Err(e) => Err(SignInError::Other(e)),
                 }
             }
-            Err(err) if err.is("PHONE_CODE_*") => Err(SignInError::InvalidCode),
+            Err(err) if err.kind() == Some(RpcErrorKind::PhoneCode) => {
+                Err(SignInError::InvalidCode)
+            }
             Err(error) => Err(SignInError::Other(error)),
         }
     }
 
+    /// Signs up a new user account for the phone number the login code was sent to.
+    ///
+    /// This is only needed when [`Client::sign_in`] fails with [`SignInError::SignUpRequired`],
+    /// because no account uses the phone number yet. The same `token` used to sign in must be
+    /// provided again, as it proves the phone number was verified. `last_name` may be empty.
+    ///
+    /// When the error included the [`TermsOfService`], they must be shown to the user, and only
+    /// passed here once the user agrees to them. They are accepted after the account is created.
+    ///
+    /// Telegram may not allow creating accounts from unofficial applications, in which case
+    /// this fails with [`SignInError::Other`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use grammers_client::SignInError;
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// fn ask_code_to_user() -> String {
+    ///     unimplemented!()
+    /// }
+    /// fn user_agrees_to(terms: &str) -> bool {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let token = client.request_login_code(PHONE).await?;
+    /// let code = ask_code_to_user();
+    ///
+    /// let user = match client.sign_in(&token, &code).await {
+    ///     Ok(user) => user,
+    ///     Err(SignInError::SignUpRequired { terms_of_service }) => {
+    ///         if let Some(tos) = &terms_of_service {
+    ///             if !user_agrees_to(tos.text()) {
+    ///                 return Ok(());
+    ///             }
+    ///         }
+    ///         client
+    ///             .sign_up(&token, "Alice", "", terms_of_service)
+    ///             .await?
+    ///     }
+    ///     Err(err) => return Err(err.into()),
+    /// };
+    /// println!("Signed up as {}!", user.full_name());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sign_up(
+        &self,
+        token: &LoginToken,
+        first_name: &str,
+        last_name: &str,
+        terms_of_service: Option<TermsOfService>,
+    ) -> Result<User, SignInError> {
+        let authorization = self
+            .invoke(&tl::functions::auth::SignUp {
+                no_joined_notifications: false,
+                phone_number: token.phone.clone(),
+                phone_code_hash: token.phone_code_hash.clone(),
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+            })
+            .await;
+        let user = match authorization {
+            Ok(tl::enums::auth::Authorization::Authorization(x)) => {
+                self.complete_login(x).await.map_err(SignInError::Other)?
+            }
+            Ok(tl::enums::auth::Authorization::SignUpRequired(x)) => {
+                return Err(SignInError::SignUpRequired {
+                    terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
+                });
+            }
+            Err(err) if err.kind() == Some(RpcErrorKind::PhoneCode) => {
+                return Err(SignInError::InvalidCode);
+            }
+            Err(error) => return Err(SignInError::Other(error)),
+        };
+
+        if let Some(tos) = terms_of_service {
+            self.invoke(&tl::functions::help::AcceptTermsOfService { id: tos.raw.id })
+                .await
+                .map_err(SignInError::Other)?;
+        }
+        Ok(user)
+    }
+
     /// Extract information needed for the two-factor authentication
     /// It's called automatically when we get SESSION_PASSWORD_NEEDED error during sign in.
     async fn get_password_information(&self) -> Result<PasswordToken, InvocationError> {
@@ -385,33 +488,37 @@ impl Client {
         password: impl AsRef<[u8]>,
     ) -> Result<User, SignInError> {
         let mut password_info = password_token.password;
-        let current_algo = password_info.current_algo.unwrap();
-        let mut params = utils::extract_password_parameters(&current_algo);
+        let mut params = password_info
+            .current_algo
+            .as_ref()
+            .and_then(utils::extract_password_parameters);
 
         // Telegram sent us incorrect parameters, trying to get them again
-        if !check_p_and_g(params.2, params.3) {
+        if !params.is_some_and(|params| check_p_and_g(params.2, params.3)) {
             password_info = self
                 .get_password_information()
                 .await
                 .map_err(SignInError::Other)?
                 .password;
-            params =
-                utils::extract_password_parameters(password_info.current_algo.as_ref().unwrap());
-            if !check_p_and_g(params.2, params.3) {
-                panic!("Failed to get correct password information from Telegram")
-            }
+            params = password_info
+                .current_algo
+                .as_ref()
+                .and_then(utils::extract_password_parameters);
         }
 
-        let (salt1, salt2, p, g) = params;
-
-        let g_b = password_info.srp_b.unwrap();
+        let (salt1, salt2, p, g) = params
+            .filter(|params| check_p_and_g(params.2, params.3))
+            .ok_or(SignInError::InvalidPasswordParameters)?;
+        let (Some(g_b), Some(srp_id)) = (password_info.srp_b, password_info.srp_id) else {
+            return Err(SignInError::InvalidPasswordParameters);
+        };
         let a: Vec<u8> = password_info.secure_random;
 
         let (m1, g_a) = calculate_2fa(salt1, salt2, p, g, g_b, a, password);
 
         let check_password = tl::functions::auth::CheckPassword {
             password: tl::enums::InputCheckPasswordSrp::Srp(tl::types::InputCheckPasswordSrp {
-                srp_id: password_info.srp_id.unwrap(),
+                srp_id,
                 a: g_a.to_vec(),
                 m1: m1.to_vec(),
             }),
@@ -421,8 +528,14 @@ impl Client {
             Ok(tl::enums::auth::Authorization::Authorization(x)) => {
                 self.complete_login(x).await.map_err(SignInError::Other)
             }
-            Ok(tl::enums::auth::Authorization::SignUpRequired(_x)) => panic!("Unexpected result"),
-            Err(err) if err.is("PASSWORD_HASH_INVALID") => Err(SignInError::InvalidPassword),
+            Ok(tl::enums::auth::Authorization::SignUpRequired(x)) => {
+                Err(SignInError::SignUpRequired {
+                    terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
+                })
+            }
+            Err(err) if err.telegram_error() == Some(TelegramError::PasswordHashInvalid) => {
+                Err(SignInError::InvalidPassword)
+            }
             Err(error) => Err(SignInError::Other(error)),
         }
     }
@@ -462,11 +575,11 @@ impl Client {
         &self.0.config.session
     }
 
-    /// Calls [`Client::sign_out`] and disconnects.
+    /// Calls [`Client::sign_out`] and disconnects by dropping the client.
     ///
-    /// The client will be disconnected even if signing out fails.
-    pub async fn sign_out_disconnect(&self) -> Result<(), InvocationError> {
-        let _res = self.invoke(&tl::functions::auth::LogOut {}).await;
-        panic!("disconnect now only works via dropping");
+    /// The client will be disconnected even if signing out fails. Since the connection is shared
+    /// by every clone of the client, it is only closed once all the other clones are dropped too.
+    pub async fn sign_out_disconnect(self) -> Result<(), InvocationError> {
+        self.sign_out().await.map(drop)
     }
 }
@@ -10,11 +10,12 @@
 
 use super::Client;
 use crate::types::{
-    AdminRightsBuilder, BannedRightsBuilder, Chat, ChatMap, IterBuffer, Message, Participant,
-    Photo, User, chats::AdminRightsBuilderInner, chats::BannedRightsBuilderInner,
+    AdminRightsBuilder, BannedRightsBuilder, Chat, ChatMap, FullChannel, FullUser, IterBuffer,
+    Message, Participant, Photo, User, chats::AdminRightsBuilderInner,
+    chats::BannedRightsBuilderInner,
 };
-use grammers_mtsender::RpcError;
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_mtsender::{RpcError, TelegramError};
 use grammers_session::{PackedChat, PackedType};
 use grammers_tl_types as tl;
 use std::collections::VecDeque;
@@ -24,6 +25,7 @@ use std::time::Duration;
 
 const MAX_PARTICIPANT_LIMIT: usize = 200;
 const MAX_PHOTO_LIMIT: usize = 100;
+const MAX_GET_USERS_LIMIT: usize = 100;
 const KICK_BAN_DURATION: i32 = 60; // in seconds, in case the second request fails
 
 pub enum ParticipantIter {
@@ -379,7 +381,9 @@ impl Client {
             .await
         {
             Ok(tl::enums::contacts::ResolvedPeer::Peer(p)) => p,
-            Err(err) if err.is("USERNAME_NOT_OCCUPIED") => return Ok(None),
+            Err(err) if err.telegram_error() == Some(TelegramError::UsernameNotOccupied) => {
+                return Ok(None);
+            }
             Err(err) => return Err(err),
         };
 
@@ -431,6 +435,110 @@ impl Client {
         Ok(User::from_raw(res.pop().unwrap()))
     }
 
+    /// Fetch the users with the given identifiers, in as few requests as possible.
+    ///
+    /// Only users whose access hash is known can be fetched, which is the case for those that
+    /// were returned by previous requests, such as the owners of unique gifts. Unknown users are
+    /// skipped, so the result may have fewer users than `ids`, and in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// for user in client.get_users(&[777000, 93372553]).await? {
+    ///     println!("{} is @{}", user.id(), user.username().unwrap_or("-"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_users(&self, ids: &[i64]) -> Result<Vec<User>, InvocationError> {
+        let input_users = {
+            let state = self.0.state.read().unwrap();
+            ids.iter()
+                .filter_map(|&id| state.chat_hashes.get(id))
+                .filter_map(|packed| packed.try_to_input_user())
+                .collect::<Vec<_>>()
+        };
+
+        let mut users = Vec::with_capacity(input_users.len());
+        for chunk in input_users.chunks(MAX_GET_USERS_LIMIT) {
+            let res = self
+                .invoke(&tl::functions::users::GetUsers { id: chunk.to_vec() })
+                .await?;
+            users.extend(
+                res.into_iter()
+                    .filter(|user| !matches!(user, tl::enums::User::Empty(_)))
+                    .map(User::from_raw),
+            );
+        }
+        Ok(users)
+    }
+
+    /// Fetch full information about a user, such as their bio or business hours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(user: grammers_client::types::User, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let full = client.get_full_user(&user).await?;
+    /// println!("{} says: {}", user.full_name(), full.about().unwrap_or("nothing"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `user` isn't a user.
+    pub async fn get_full_user<C: Into<PackedChat>>(
+        &self,
+        user: C,
+    ) -> Result<FullUser, InvocationError> {
+        let user = user.into();
+        let id = match user.try_to_input_user() {
+            Some(id) => id,
+            None => panic!("User parameter not user!"),
+        };
+
+        let tl::enums::users::UserFull::Full(full) = self
+            .invoke(&tl::functions::users::GetFullUser { id })
+            .await?;
+
+        Ok(FullUser::from_raw(full))
+    }
+
+    /// Fetch full information about a broadcast channel or megagroup, such as its
+    /// description and participant counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let full = client.get_full_channel(&chat).await?;
+    /// println!("{} has {:?} members", chat.name().unwrap_or(""), full.participants_count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `channel` isn't a channel, or Telegram's response doesn't describe it.
+    pub async fn get_full_channel<C: Into<PackedChat>>(
+        &self,
+        channel: C,
+    ) -> Result<FullChannel, InvocationError> {
+        let channel = channel.into();
+        let input_channel = match channel.try_to_input_channel() {
+            Some(input_channel) => input_channel,
+            None => panic!("Channel parameter not channel!"),
+        };
+
+        let tl::enums::messages::ChatFull::Full(full) = self
+            .invoke(&tl::functions::channels::GetFullChannel {
+                channel: input_channel,
+            })
+            .await?;
+
+        Ok(FullChannel::from_raw(full).expect("fetching a channel should return the channel"))
+    }
+
     /// Iterate over the participants of a chat.
     ///
     /// The participants are returned in no particular order.
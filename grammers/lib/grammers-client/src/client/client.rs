@@ -12,9 +12,12 @@ use grammers_tl_types as tl;
 use sender::Enqueuer;
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
-use std::sync::atomic::AtomicU32;
-use std::sync::{Arc, RwLock};
-use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, AtomicUsize};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::{Mutex as AsyncMutex, OnceCell};
 use web_time::Instant;
 
 use super::net;
@@ -61,11 +64,21 @@ pub struct InitParams {
     /// field can be used to override said address, and is most commonly used to connect to one
     /// of Telegram's test servers instead.
     pub server_addr: Option<ServerAddr>,
+    /// Addresses to use for specific datacenters instead of the built-in ones, keyed by the
+    /// datacenter ID. Unlike `server_addr`, this also applies to the connections made to other
+    /// datacenters (for example, to download media), and is most useful on networks where the
+    /// default Telegram addresses are blocked or resolve to the wrong place.
+    ///
+    /// Datacenters without an entry keep using the default addresses. `server_addr` still takes
+    /// precedence over this for the connection to the home datacenter.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub dc_addresses: HashMap<i32, SocketAddr>,
     /// The threshold below which the library should automatically sleep on flood-wait and slow
     /// mode wait errors (inclusive). For instance, if an
     /// `RpcError { name: "FLOOD_WAIT", value: Some(17) }` (flood, must wait 17 seconds) occurs
     /// and `flood_sleep_threshold` is 20 (seconds), the library will `sleep` automatically for
-    /// 17 seconds. If the error was for 21s, it would propagate the error instead.
+    /// 17 seconds. If the error was for 21s, it would propagate the error instead, and
+    /// [`InvocationError::flood_wait_seconds`] would tell how long to wait before retrying.
     ///
     /// By default, the library will sleep on flood-waits below or equal to one minute (60
     /// seconds), but this can be disabled by passing `0` (since all flood errors would be
@@ -73,7 +86,32 @@ pub struct InitParams {
     ///
     /// On flood, the library will retry *once*. If the flood error occurs a second time after
     /// sleeping, the error will be returned.
+    ///
+    /// [`InvocationError::flood_wait_seconds`]: grammers_mtsender::InvocationError::flood_wait_seconds
     pub flood_sleep_threshold: u32,
+    /// How long to wait for the response to a request before giving up on it.
+    ///
+    /// A connection can stop answering without being closed, in which case requests would
+    /// otherwise wait forever. Once the timeout passes, the request fails with an IO error of
+    /// kind [`std::io::ErrorKind::TimedOut`], which is treated as a lost connection. Time spent
+    /// sleeping on flood-waits does not count towards it.
+    ///
+    /// [`Client::invoke_with_timeout`] can be used to pick a different timeout for a single
+    /// request.
+    ///
+    /// By default, there is no timeout.
+    pub request_timeout: Option<Duration>,
+    /// How many connections to the home datacenter [`Client::invoke_spread`] takes turns
+    /// between, the main connection included.
+    ///
+    /// Telegram limits how fast the requests sent over a single connection are served, which
+    /// caps long runs of independent requests (such as fetching thousands of gifts). The extra
+    /// connections reuse the session's authorization key, each with an MTProto session of its
+    /// own, and are only opened once a request is sent over them. Updates are only received
+    /// over the main connection.
+    ///
+    /// By default, only the main connection is used.
+    pub connections: usize,
     /// How many updates may be buffered by the client at any given time.
     ///
     /// Telegram passively sends updates to the client through the open connection, so they must
@@ -94,9 +132,20 @@ pub struct InitParams {
     ///
     /// When the limit is `Some`, a buffer to hold that many updates will be pre-allocated.
     pub update_queue_limit: Option<usize>,
+    /// Every how often keepalive pings should be sent while the connection is otherwise idle.
+    ///
+    /// Telegram closes connections that stay silent for too long, so pings keep them open in
+    /// between requests. Pings only go out while the client is stepped (by a request,
+    /// [`Client::step`] or [`Client::next_update`]), so keep stepping it while idle. Larger values reduce idle traffic. If the process sleeps for longer
+    /// than this (e.g. the machine was suspended), the connection is assumed to be closed and
+    /// will be re-established according to the `reconnection_policy` before it is used again.
+    ///
+    /// By default, pings are sent every minute.
+    pub ping_delay: Duration,
     /// URL of the proxy to use. Requires the `proxy` feature to be enabled.
     ///
-    /// The scheme must be `socks5`. Username and password are optional.
+    /// The scheme must be `socks5` or `http` (a proxy that supports `CONNECT`). Username and
+    /// password are optional.
     ///
     /// Both a host and port must be provided. If a domain is used for the host, domain, its address will be looked up,
     /// and the first IP address found will be used. If a different IP address should be used, consider resolving the
@@ -124,8 +173,14 @@ pub(crate) struct ClientInner {
     pub(crate) config: Config,
     pub(crate) conn: Connection,
     pub(crate) state: RwLock<ClientState>,
-    // Stores per-datacenter downloader instances
-    pub(crate) downloader_map: AsyncRwLock<HashMap<i32, Arc<Connection>>>,
+    // Stores per-datacenter downloader instances. Each datacenter gets its own cell so that
+    // connecting to one of them does not block requests to the others.
+    pub(crate) downloader_map: Mutex<HashMap<i32, Arc<OnceCell<Arc<Connection>>>>>,
+    // Extra connections to the home datacenter used by `Client::invoke_spread`, one per
+    // `InitParams::connections` beyond the first. Each is opened on first use.
+    pub(crate) spread_conns: Vec<OnceCell<Arc<Connection>>>,
+    // Which connection `Client::invoke_spread` sends the next request over.
+    pub(crate) spread_turn: AtomicUsize,
 }
 
 pub(crate) struct ClientState {
@@ -154,10 +209,21 @@ pub(crate) struct Connection {
 /// On drop, all state is synchronized to the session. The [`Session`] must be explicitly saved
 /// to disk with [`Session::save_to_file`] for persistence
 ///
+/// A `Client` is a cheap handle: cloning it only bumps a reference count, and every clone shares
+/// the same connections and state. It is also `Send` and `Sync`, so clones can be moved into
+/// tasks running on any thread. Requests made concurrently from several clones are sent over the
+/// same connection without waiting for each other's responses.
+///
 /// [`Session`]: grammers_session::Session
 #[derive(Clone)]
 pub struct Client(pub(crate) Arc<ClientInner>);
 
+// Applications share clients between tasks, so losing `Send + Sync` would be a breaking change.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Client>();
+};
+
 impl Default for InitParams {
     fn default() -> Self {
         let info = os_info::get();
@@ -185,8 +251,13 @@ impl Default for InitParams {
             lang_code,
             catch_up: false,
             server_addr: None,
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            dc_addresses: HashMap::new(),
             flood_sleep_threshold: 60,
+            request_timeout: None,
+            connections: 1,
             update_queue_limit: Some(100),
+            ping_delay: grammers_mtsender::PING_DELAY,
             #[cfg(feature = "proxy")]
             proxy_url: None,
             reconnection_policy: &grammers_mtsender::NoReconnect,
@@ -215,3 +286,38 @@ impl PartialEq for Client {
         self.0.id == other.0.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[test]
+    fn client_is_cheap_to_share() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<Client>();
+    }
+
+    // Never called, but must compile: the futures returned by the client have to be `Send`
+    // so that they can be spawned on a multi-threaded runtime.
+    #[allow(dead_code)]
+    fn client_futures_are_send(client: Client) {
+        assert_send(client.invoke(&tl::functions::Ping { ping_id: 0 }));
+        assert_send(
+            client.invoke_with_timeout(&tl::functions::Ping { ping_id: 0 }, Duration::ZERO),
+        );
+        assert_send(client.invoke_in_dc(&tl::functions::Ping { ping_id: 0 }, 2));
+        assert_send(client.invoke_spread(&tl::functions::Ping { ping_id: 0 }));
+        assert_send(client.get_star_gifts(0));
+        assert_send(client.get_unique_star_gift(""));
+        assert_send(client.get_unique_star_gifts(&[]));
+
+        let mut gifts = client.iter_saved_star_gifts(grammers_session::PackedChat {
+            ty: grammers_session::PackedType::User,
+            id: 0,
+            access_hash: None,
+        });
+        assert_send(gifts.next());
+    }
+}
@@ -171,11 +171,6 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
-
-    pub async fn get_unique_star_gift(&self, slug: String) -> Result<tl::enums::payments::UniqueStarGift, InvocationError> {
-        self.invoke(&tl::functions::payments::GetUniqueStarGift { slug })
-        .await
-    }
     pub async fn delete_dialog<C: Into<PackedChat>>(&self, chat: C) -> Result<(), InvocationError> {
         let chat = chat.into();
         if let Some(channel) = chat.try_to_input_channel() {
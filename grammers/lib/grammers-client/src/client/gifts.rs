@@ -0,0 +1,547 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods related to star gifts.
+
+use crate::Client;
+use crate::types::{
+    ChatMap, IterBuffer, SavedStarGift, SavedStarGiftId, StarGiftCatalog, StarGiftUpgrade,
+    StarGiftUpgradePreview, UniqueGift,
+};
+use futures_util::future::join_all;
+pub use grammers_mtsender::InvocationError;
+use grammers_session::{PackedChat, UpdatesLike};
+use grammers_tl_types as tl;
+use std::collections::{HashMap, HashSet};
+
+const MAX_SAVED_GIFTS_LIMIT: usize = 100;
+
+pub type SavedStarGiftIter = IterBuffer<tl::functions::payments::GetSavedStarGifts, SavedStarGift>;
+
+impl SavedStarGiftIter {
+    fn new(client: &Client, chat: PackedChat) -> Self {
+        Self::from_request(
+            client,
+            MAX_SAVED_GIFTS_LIMIT,
+            tl::functions::payments::GetSavedStarGifts {
+                exclude_unsaved: false,
+                exclude_saved: false,
+                exclude_unlimited: false,
+                exclude_limited: false,
+                exclude_unique: false,
+                sort_by_value: false,
+                peer: chat.to_input_peer(),
+                offset: String::new(),
+                limit: 0,
+            },
+        )
+    }
+
+    /// Only return unique collectible gifts, skipping those that were never upgraded.
+    pub fn unique_only(mut self) -> Self {
+        self.request.exclude_unlimited = true;
+        self.request.exclude_limited = true;
+        self
+    }
+
+    /// Determines how many gifts there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.request.limit = 1;
+        let tl::enums::payments::SavedStarGifts::Gifts(gifts) =
+            self.client.invoke(&self.request).await?;
+        let total = gifts.count as usize;
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next `SavedStarGift` from the internal buffer, filling the buffer previously
+    /// if it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no gifts left.
+    pub async fn next(&mut self) -> Result<Option<SavedStarGift>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_SAVED_GIFTS_LIMIT);
+        let tl::enums::payments::SavedStarGifts::Gifts(gifts) =
+            self.client.invoke(&self.request).await?;
+
+        self.total = Some(gifts.count as usize);
+        match gifts.next_offset {
+            Some(offset) if !offset.is_empty() => self.request.offset = offset,
+            _ => self.last_chunk = true,
+        }
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&gifts.users, &gifts.chats);
+        }
+
+        let chats = ChatMap::new(gifts.users, gifts.chats);
+        self.buffer.extend(
+            gifts
+                .gifts
+                .into_iter()
+                .map(|gift| SavedStarGift::from_raw(gift, &chats)),
+        );
+
+        Ok(self.pop_item())
+    }
+}
+
+const MAX_RESALE_GIFTS_LIMIT: usize = 100;
+
+pub type ResaleStarGiftIter = IterBuffer<tl::functions::payments::GetResaleStarGifts, UniqueGift>;
+
+impl ResaleStarGiftIter {
+    fn new(client: &Client, gift_id: i64) -> Self {
+        Self::from_request(
+            client,
+            MAX_RESALE_GIFTS_LIMIT,
+            tl::functions::payments::GetResaleStarGifts {
+                sor_by_price: false,
+                sort_by_num: false,
+                attributes_hash: None,
+                gift_id,
+                attributes: None,
+                offset: String::new(),
+                limit: 0,
+            },
+        )
+    }
+
+    /// Return the cheapest gifts first, instead of the most recently listed.
+    pub fn sort_by_price(mut self) -> Self {
+        self.request.sor_by_price = true;
+        self.request.sort_by_num = false;
+        self
+    }
+
+    /// Return the gifts with the lowest number first, instead of the most recently listed.
+    pub fn sort_by_num(mut self) -> Self {
+        self.request.sor_by_price = false;
+        self.request.sort_by_num = true;
+        self
+    }
+
+    /// Determines how many gifts of the collection are listed for resale in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.request.limit = 1;
+        let tl::enums::payments::ResaleStarGifts::Gifts(gifts) =
+            self.client.invoke(&self.request).await?;
+        let total = gifts.count as usize;
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next listed `UniqueGift` from the internal buffer, filling the buffer
+    /// previously if it's empty. Its [`UniqueGift::resale_price`] is the price it's listed for.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no gifts left.
+    pub async fn next(&mut self) -> Result<Option<UniqueGift>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_RESALE_GIFTS_LIMIT);
+        let tl::enums::payments::ResaleStarGifts::Gifts(gifts) =
+            self.client.invoke(&self.request).await?;
+
+        self.total = Some(gifts.count as usize);
+        match gifts.next_offset {
+            Some(offset) if !offset.is_empty() => self.request.offset = offset,
+            _ => self.last_chunk = true,
+        }
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&gifts.users, &gifts.chats);
+        }
+
+        self.buffer.extend(
+            gifts
+                .gifts
+                .into_iter()
+                .filter_map(UniqueGift::from_star_gift),
+        );
+
+        Ok(self.pop_item())
+    }
+}
+
+/// Method implementations related to star gifts.
+impl Client {
+    /// Fetch the unique gift with the given slug, such as `PlushPepe-1`.
+    ///
+    /// The users Telegram sent along with the gift (such as its owner) are kept in
+    /// [`UniqueGift::users`], so the owner can be resolved without further requests.
+    ///
+    /// The request goes through [`Client::invoke_spread`], so fetching many gifts at once
+    /// makes use of every one of the [`InitParams::connections`].
+    ///
+    /// [`InitParams::connections`]: crate::InitParams::connections
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let gift = client.get_unique_star_gift("PlushPepe-1").await?;
+    /// if let Some(owner) = gift.owner_user() {
+    ///     println!("{} is owned by {}", gift.slug(), owner.full_name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_unique_star_gift(&self, slug: &str) -> Result<UniqueGift, InvocationError> {
+        let tl::enums::payments::UniqueStarGift::Gift(gift) = self
+            .invoke_spread(&tl::functions::payments::GetUniqueStarGift {
+                slug: slug.to_string(),
+            })
+            .await?;
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&gift.users, &[]);
+        }
+
+        match gift.gift {
+            tl::enums::StarGift::Unique(raw) => Ok(UniqueGift::with_users(raw, gift.users)),
+            // A slug always refers to a unique gift.
+            tl::enums::StarGift::Gift(_) => Err(tl::deserialize::Error::UnexpectedConstructor {
+                id: <tl::types::StarGift as tl::Identifiable>::CONSTRUCTOR_ID,
+            }
+            .into()),
+        }
+    }
+
+    /// Fetch several unique gifts by their slugs at once.
+    ///
+    /// The requests are all sent without waiting for the previous responses, so that they
+    /// share the same round-trips over the connection instead of waiting for one another.
+    /// Each slug gets its own result, as some gifts may fail (for example, if the slug does
+    /// not exist) while the rest succeed. Repeated slugs are only fetched once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let slugs = ["PlushPepe-1".to_string(), "PlushPepe-2".to_string()];
+    /// for (slug, gift) in client.get_unique_star_gifts(&slugs).await {
+    ///     match gift {
+    ///         Ok(gift) => println!("{slug} is {} #{}", gift.title(), gift.num()),
+    ///         Err(e) => println!("failed to fetch {slug}: {e}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_unique_star_gifts(
+        &self,
+        slugs: &[String],
+    ) -> HashMap<String, Result<UniqueGift, InvocationError>> {
+        let slugs: HashSet<&String> = slugs.iter().collect();
+        join_all(
+            slugs
+                .into_iter()
+                .map(|slug| async move { (slug.clone(), self.get_unique_star_gift(slug).await) }),
+        )
+        .await
+        .into_iter()
+        .collect()
+    }
+
+    /// Fetch the catalog of every star gift type that can currently be bought or was sold out,
+    /// including limited gifts that can be upgraded into unique collectibles.
+    ///
+    /// `hash` should be the [`StarGiftCatalog::hash`] of a previously fetched catalog, or `0`
+    /// to always fetch it. If the catalog has not changed since then, `None` is returned and
+    /// the previous catalog can be used as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let catalog = client.get_star_gifts(0).await?.expect("hash 0 always returns the catalog");
+    /// for gift in catalog.gifts() {
+    ///     println!("{:?} costs {} stars", gift.title, gift.stars);
+    /// }
+    ///
+    /// // Later on, only download the catalog again if it changed.
+    /// if let Some(catalog) = client.get_star_gifts(catalog.hash()).await? {
+    ///     println!("the catalog now has {} gifts", catalog.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_star_gifts(
+        &self,
+        hash: i32,
+    ) -> Result<Option<StarGiftCatalog>, InvocationError> {
+        match self
+            .invoke(&tl::functions::payments::GetStarGifts { hash })
+            .await?
+        {
+            tl::enums::payments::StarGifts::Gifts(catalog) => {
+                Ok(Some(StarGiftCatalog::from_raw(catalog)))
+            }
+            tl::enums::payments::StarGifts::NotModified => Ok(None),
+        }
+    }
+
+    /// Fetch every model, backdrop and pattern the gift type with the given `gift_id` can get
+    /// when upgraded, along with how rare each of them is.
+    ///
+    /// The `gift_id` is that of a gift type from [`Client::get_star_gifts`]. Only gift types
+    /// that can be upgraded have a preview.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let catalog = client.get_star_gifts(0).await?.expect("hash 0 always returns the catalog");
+    /// if let Some(gift) = catalog.upgradable().next() {
+    ///     let preview = client.get_star_gift_upgrade_preview(gift.id).await?;
+    ///     for model in preview.models() {
+    ///         println!("{}: {}‰", model.name, model.rarity_permille);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_star_gift_upgrade_preview(
+        &self,
+        gift_id: i64,
+    ) -> Result<StarGiftUpgradePreview, InvocationError> {
+        self.invoke(&tl::functions::payments::GetStarGiftUpgradePreview { gift_id })
+            .await
+            .map(StarGiftUpgradePreview::from_raw)
+    }
+
+    /// Show the received gift on the profile of its owner.
+    ///
+    /// The gift must be owned by the logged-in user or by a channel they manage. Gifts that
+    /// are already shown are left as-is. Unique gifts can be referred to by their slug.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.save_star_gift("PlushPepe-1234").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn save_star_gift<G: Into<SavedStarGiftId>>(
+        &self,
+        gift: G,
+    ) -> Result<(), InvocationError> {
+        self.set_star_gift_saved(gift.into(), true).await
+    }
+
+    /// Hide the received gift from the profile of its owner.
+    ///
+    /// Hidden gifts are still owned and can be shown again with [`Client::save_star_gift`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.unsave_star_gift("PlushPepe-1234").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn unsave_star_gift<G: Into<SavedStarGiftId>>(
+        &self,
+        gift: G,
+    ) -> Result<(), InvocationError> {
+        self.set_star_gift_saved(gift.into(), false).await
+    }
+
+    async fn set_star_gift_saved(
+        &self,
+        gift: SavedStarGiftId,
+        saved: bool,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::payments::SaveStarGift {
+            unsave: !saved,
+            stargift: gift.to_raw(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Upgrade a received gift into a unique collectible gift, returning the new gift.
+    ///
+    /// If the sender already paid for the upgrade (see [`SavedStarGift::prepaid_upgrade_stars`]),
+    /// `prepaid` should be `true` and the upgrade is free. Otherwise the upgrade is paid from
+    /// the stars balance of the logged-in account, at the price Telegram asks for the gift type.
+    ///
+    /// With `keep_original_details`, the sender, recipient, date and message of the original
+    /// gift stay visible on the unique gift. This costs extra.
+    ///
+    /// If Telegram asks to confirm the payment first, [`StarGiftUpgrade::VerificationNeeded`] is
+    /// returned and the gift is not upgraded until the payment is confirmed at its URL.
+    ///
+    /// The updates Telegram sends back are processed like any other, so the service message
+    /// about the upgrade also shows up in [`Client::next_update`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::{SavedStarGiftId, StarGiftUpgrade};
+    ///
+    /// let me = client.get_me().await?;
+    /// let mut gifts = client.iter_saved_star_gifts(&me);
+    /// while let Some(gift) = gifts.next().await? {
+    ///     if let (true, Some(msg_id)) = (gift.can_upgrade(), gift.msg_id()) {
+    ///         let prepaid = gift.prepaid_upgrade_stars().is_some();
+    ///         match client
+    ///             .upgrade_star_gift(SavedStarGiftId::User { msg_id }, false, prepaid)
+    ///             .await?
+    ///         {
+    ///             StarGiftUpgrade::Upgraded(Some(unique)) => {
+    ///                 println!("upgraded into {}", unique.slug())
+    ///             }
+    ///             StarGiftUpgrade::Upgraded(None) => println!("upgraded"),
+    ///             StarGiftUpgrade::VerificationNeeded { url } => println!("confirm at {url}"),
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upgrade_star_gift<G: Into<SavedStarGiftId>>(
+        &self,
+        gift: G,
+        keep_original_details: bool,
+        prepaid: bool,
+    ) -> Result<StarGiftUpgrade, InvocationError> {
+        let stargift = gift.into().to_raw();
+        let updates = if prepaid {
+            self.invoke(&tl::functions::payments::UpgradeStarGift {
+                keep_original_details,
+                stargift,
+            })
+            .await?
+        } else {
+            let invoice: tl::enums::InputInvoice = tl::types::InputInvoiceStarGiftUpgrade {
+                keep_original_details,
+                stargift,
+            }
+            .into();
+            let form_id = match self
+                .invoke(&tl::functions::payments::GetPaymentForm {
+                    invoice: invoice.clone(),
+                    theme_params: None,
+                })
+                .await?
+            {
+                tl::enums::payments::PaymentForm::Form(form) => form.form_id,
+                tl::enums::payments::PaymentForm::Stars(form) => form.form_id,
+                tl::enums::payments::PaymentForm::StarGift(form) => form.form_id,
+            };
+            match self
+                .invoke(&tl::functions::payments::SendStarsForm { form_id, invoice })
+                .await?
+            {
+                tl::enums::payments::PaymentResult::Result(result) => result.updates,
+                tl::enums::payments::PaymentResult::PaymentVerificationNeeded(verification) => {
+                    return Ok(StarGiftUpgrade::VerificationNeeded {
+                        url: verification.url,
+                    });
+                }
+            }
+        };
+
+        self.process_socket_updates(vec![UpdatesLike::Updates(updates.clone())]);
+        Ok(StarGiftUpgrade::Upgraded(unique_gift_from_updates(updates)))
+    }
+
+    /// Iterate over the unique gifts of a collection that are listed for resale.
+    ///
+    /// The `gift_id` is that of the gift type from [`Client::get_star_gifts`] the collection
+    /// was upgraded from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(gift_id: i64, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut listings = client.iter_resale_star_gifts(gift_id).sort_by_price();
+    ///
+    /// if let Some(cheapest) = listings.next().await? {
+    ///     println!("{} costs {:?} stars", cheapest.slug(), cheapest.resale_price());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_resale_star_gifts(&self, gift_id: i64) -> ResaleStarGiftIter {
+        ResaleStarGiftIter::new(self, gift_id)
+    }
+
+    /// Iterate over the star gifts shown on the profile of the given user or channel.
+    ///
+    /// Gifts the owner chose to hide are only returned when iterating over your own profile
+    /// (or that of a channel you manage).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut gifts = client.iter_saved_star_gifts(&chat).unique_only();
+    ///
+    /// while let Some(gift) = gifts.next().await? {
+    ///     if let Some(unique) = gift.unique() {
+    ///         println!("{} owns {}", chat.name().unwrap_or_default(), unique.slug);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_saved_star_gifts<C: Into<PackedChat>>(&self, chat: C) -> SavedStarGiftIter {
+        SavedStarGiftIter::new(self, chat.into())
+    }
+}
+
+// Upgrading a gift sends a service message with the new unique gift.
+fn unique_gift_from_updates(updates: tl::enums::Updates) -> Option<UniqueGift> {
+    let updates = match updates {
+        tl::enums::Updates::Updates(updates) => updates.updates,
+        tl::enums::Updates::Combined(updates) => updates.updates,
+        _ => return None,
+    };
+    updates.into_iter().find_map(|update| {
+        let message = match update {
+            tl::enums::Update::NewMessage(update) => update.message,
+            tl::enums::Update::NewChannelMessage(update) => update.message,
+            _ => return None,
+        };
+        match message {
+            tl::enums::Message::Service(tl::types::MessageService {
+                action: tl::enums::MessageAction::StarGiftUnique(action),
+                ..
+            }) => UniqueGift::from_star_gift(action.gift),
+            _ => None,
+        }
+    })
+}
@@ -12,8 +12,10 @@ pub mod chats;
 pub mod client;
 pub mod dialogs;
 pub mod files;
+pub mod gifts;
 pub mod messages;
 pub mod net;
+pub mod stars;
 pub mod updates;
 
 pub use auth::SignInError;
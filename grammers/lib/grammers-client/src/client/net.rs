@@ -8,21 +8,27 @@
 use super::client::{ClientState, Connection};
 use super::{Client, ClientInner, Config};
 use crate::utils;
+use futures_util::future::{Either, select};
 use grammers_mtproto::mtp;
 use grammers_mtproto::transport;
 use grammers_mtsender::{
-    self as sender, AuthorizationError, InvocationError, RpcError, Sender, ServerAddr, utils::sleep,
+    self as sender, AuthorizationError, InvocationError, ReadError, RpcError, Sender, ServerAddr,
+    utils::{sleep, sleep_until},
 };
 use grammers_session::{ChatHashCache, MessageBoxes, UpdatesLike};
 use grammers_tl_types::{self as tl, Deserializable};
 use log::{debug, info};
 use sender::Enqueuer;
 use std::collections::{HashMap, VecDeque};
+use std::io;
 use std::net::Ipv4Addr;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, RwLock};
+use std::pin::pin;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 use tokio::sync::oneshot::error::TryRecvError;
-use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
+use tokio::sync::{Mutex as AsyncMutex, OnceCell};
+use web_time::Instant;
 
 /// Socket addresses to Telegram datacenters, where the index into this array
 /// represents the data center ID.
@@ -77,7 +83,12 @@ pub(crate) async fn connect_sender(
     } else {
         #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
         let addr = {
-            let tcp_addr = DC_ADDRESSES[dc_id as usize].into();
+            let tcp_addr = config
+                .params
+                .dc_addresses
+                .get(&dc_id)
+                .copied()
+                .unwrap_or_else(|| DC_ADDRESSES[dc_id as usize].into());
 
             #[cfg(not(feature = "proxy"))]
             let addr = ServerAddr::Tcp { address: tcp_addr };
@@ -145,6 +156,7 @@ pub(crate) async fn connect_sender(
         }
         (sender, tx)
     };
+    sender.set_ping_delay(config.params.ping_delay);
 
     // TODO handle -404 (we had a previously-valid authkey, but server no longer knows about it)
     // TODO all up-to-date server addresses should be stored in the session for future initial connections
@@ -235,6 +247,10 @@ impl Client {
         // Don't bother getting pristine update state if we're not logged in.
         let should_get_state = message_box.is_empty() && config.session.signed_in();
 
+        let spread_conns = (1..config.params.connections)
+            .map(|_| OnceCell::new())
+            .collect();
+
         // TODO Sender doesn't have a way to handle backpressure yet
         let client = Self(Arc::new(ClientInner {
             id: utils::generate_random_id(),
@@ -247,7 +263,9 @@ impl Client {
                 last_update_limit_warn: None,
                 updates,
             }),
-            downloader_map: AsyncRwLock::new(HashMap::new()),
+            downloader_map: Mutex::new(HashMap::new()),
+            spread_conns,
+            spread_turn: AtomicUsize::new(0),
         }));
 
         if should_get_state {
@@ -300,6 +318,53 @@ impl Client {
             .invoke(
                 request,
                 self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                |updates| self.process_socket_updates(updates),
+            )
+            .await
+    }
+
+    /// Invoke a raw API call, giving up if Telegram has not responded after `timeout`.
+    ///
+    /// This is the same as [`Client::invoke`], but overrides [`InitParams::request_timeout`]
+    /// for this call only. Time spent sleeping on flood-waits (see
+    /// [`InitParams::flood_sleep_threshold`]) does not count towards the timeout.
+    ///
+    /// A request that times out fails with an IO error of kind [`std::io::ErrorKind::TimedOut`],
+    /// for which [`InvocationError::is_connection_lost`] returns `true`: a connection that
+    /// stopped answering should be treated as lost. The response, if it ever arrives, is
+    /// discarded.
+    ///
+    /// [`InitParams::request_timeout`]: crate::InitParams::request_timeout
+    /// [`InitParams::flood_sleep_threshold`]: crate::InitParams::flood_sleep_threshold
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_tl_types as tl;
+    /// use std::time::Duration;
+    ///
+    /// let request = tl::functions::Ping { ping_id: 0 };
+    /// match client.invoke_with_timeout(&request, Duration::from_secs(10)).await {
+    ///     Ok(pong) => println!("{pong:?}"),
+    ///     Err(e) if e.is_connection_lost() => println!("Telegram did not answer in time"),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn invoke_with_timeout<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+        timeout: Duration,
+    ) -> Result<R::Return, InvocationError> {
+        self.0
+            .conn
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                Some(timeout),
                 |updates| self.process_socket_updates(updates),
             )
             .await
@@ -321,7 +386,6 @@ impl Client {
     }
 
     async fn connect_sender(&self, dc_id: i32) -> Result<Arc<Connection>, InvocationError> {
-        let mut mutex = self.0.downloader_map.write().await;
         debug!("Connecting new datacenter {}", dc_id);
         match connect_sender(dc_id, &self.0.config).await {
             Ok((new_sender, new_tx)) => {
@@ -336,24 +400,40 @@ impl Client {
                     bytes: authorization.bytes,
                 };
                 new_downloader
-                    .invoke(&request, self.0.config.params.flood_sleep_threshold, drop)
+                    .invoke(
+                        &request,
+                        self.0.config.params.flood_sleep_threshold,
+                        self.0.config.params.request_timeout,
+                        drop,
+                    )
                     .await?;
 
-                mutex.insert(dc_id, new_downloader.clone());
-                Ok(new_downloader.clone())
+                Ok(new_downloader)
             }
             Err(AuthorizationError::Invoke(e)) => Err(e),
-            Err(AuthorizationError::Gen(e)) => {
-                panic!("authorization key generation failed: {e}")
-            }
+            Err(e) => Err(InvocationError::Read(ReadError::Io(io::Error::other(
+                e.to_string(),
+            )))),
         }
     }
 
-    async fn get_downloader(&self, dc_id: i32) -> Result<Option<Arc<Connection>>, InvocationError> {
-        return Ok({
-            let guard = self.0.downloader_map.read().await;
-            guard.get(&dc_id).cloned()
-        });
+    /// Get the connection to the given datacenter, connecting to it first if needed.
+    ///
+    /// The map is only locked to look up the datacenter's cell, so connecting to one datacenter
+    /// does not hold up requests to the others. Concurrent callers for the same datacenter wait
+    /// for a single connection attempt instead of each opening their own.
+    async fn get_downloader(&self, dc_id: i32) -> Result<Arc<Connection>, InvocationError> {
+        let cell = self
+            .0
+            .downloader_map
+            .lock()
+            .unwrap()
+            .entry(dc_id)
+            .or_default()
+            .clone();
+        cell.get_or_try_init(|| self.connect_sender(dc_id))
+            .await
+            .cloned()
     }
 
     pub async fn invoke_in_dc<R: tl::RemoteCall>(
@@ -361,15 +441,89 @@ impl Client {
         request: &R,
         dc_id: i32,
     ) -> Result<R::Return, InvocationError> {
-        let downloader = match self.get_downloader(dc_id).await? {
-            None => self.connect_sender(dc_id).await?,
-            Some(fd) => fd,
+        self.get_downloader(dc_id)
+            .await?
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                drop,
+            )
+            .await
+    }
+
+    /// Invoke a raw API call over one of the [`InitParams::connections`] to the home
+    /// datacenter, taking turns between them.
+    ///
+    /// This is meant for many independent requests in flight at the same time: spread over
+    /// several connections, they are not held back by the limits of a single one. With the
+    /// default of one connection, this is the same as [`Client::invoke`].
+    ///
+    /// A connection is opened the first time a request is sent over it. Updates that arrive
+    /// over the extra connections are dropped.
+    ///
+    /// [`InitParams::connections`]: crate::InitParams::connections
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use futures_util::future::join_all;
+    /// use grammers_tl_types as tl;
+    ///
+    /// let requests: Vec<_> = ["PlushPepe-1", "PlushPepe-2"]
+    ///     .into_iter()
+    ///     .map(|slug| tl::functions::payments::GetUniqueStarGift { slug: slug.to_string() })
+    ///     .collect();
+    /// for gift in join_all(requests.iter().map(|request| client.invoke_spread(request))).await {
+    ///     dbg!(gift?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn invoke_spread<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+    ) -> Result<R::Return, InvocationError> {
+        let conns = &self.0.spread_conns;
+        let turn = self.0.spread_turn.fetch_add(1, Ordering::Relaxed) % (conns.len() + 1);
+        // Turn 0 is the main connection, which also processes updates.
+        let Some(cell) = turn.checked_sub(1).map(|i| &conns[i]) else {
+            return self.invoke(request).await;
         };
-        downloader
-            .invoke(request, self.0.config.params.flood_sleep_threshold, drop)
+        cell.get_or_try_init(|| self.connect_spread())
+            .await?
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                drop,
+            )
             .await
     }
 
+    /// How many connections [`Client::invoke_spread`] takes turns between, as set by
+    /// [`InitParams::connections`].
+    ///
+    /// [`InitParams::connections`]: crate::InitParams::connections
+    pub fn spread_connections(&self) -> usize {
+        self.0.spread_conns.len() + 1
+    }
+
+    /// Open another connection to the home datacenter. The session's authorization key is
+    /// already logged in, so unlike [`Client::connect_sender`] there is nothing to import.
+    async fn connect_spread(&self) -> Result<Arc<Connection>, InvocationError> {
+        let dc_id = self.0.state.read().unwrap().dc_id;
+        debug!("Connecting another sender to datacenter {}", dc_id);
+        match connect_sender(dc_id, &self.0.config).await {
+            Ok((sender, request_tx)) => Ok(Arc::new(Connection::new(sender, request_tx))),
+            Err(AuthorizationError::Invoke(e)) => Err(e),
+            Err(e) => Err(InvocationError::Read(ReadError::Io(io::Error::other(
+                e.to_string(),
+            )))),
+        }
+    }
+
     /// Perform a single network step.
     ///
     /// Most commonly, you will want to use the higher-level abstraction [`Client::next_update`]
@@ -425,11 +579,13 @@ impl Connection {
         &self,
         request: &R,
         flood_sleep_threshold: u32,
+        timeout: Option<Duration>,
         on_updates: F,
     ) -> Result<R::Return, InvocationError> {
         let mut slept_flood = false;
 
         let mut rx = { self.request_tx.read().unwrap().enqueue(request) };
+        let mut deadline = timeout.map(|timeout| Instant::now() + timeout);
         loop {
             match rx.try_recv() {
                 Ok(response) => match response {
@@ -450,12 +606,31 @@ impl Connection {
                         sleep(delay).await;
                         slept_flood = true;
                         rx = self.request_tx.read().unwrap().enqueue(request);
+                        deadline = timeout.map(|timeout| Instant::now() + timeout);
                         continue;
                     }
                     Err(e) => break Err(e),
                 },
                 Err(TryRecvError::Empty) => {
-                    on_updates(self.step().await?);
+                    let (Some(deadline), Some(timeout)) = (deadline, timeout) else {
+                        on_updates(self.step().await?);
+                        continue;
+                    };
+                    let sleep = pin!(async { sleep_until(deadline).await });
+                    let step = pin!(async { self.step().await });
+                    match select(sleep, step).await {
+                        Either::Left(_) => {
+                            break Err(InvocationError::Read(ReadError::Io(io::Error::new(
+                                io::ErrorKind::TimedOut,
+                                format!(
+                                    "no response to {} after {:?}",
+                                    std::any::type_name::<R>(),
+                                    timeout
+                                ),
+                            ))));
+                        }
+                        Either::Right((updates, _)) => on_updates(updates?),
+                    }
                 }
                 Err(TryRecvError::Closed) => {
                     panic!("request channel dropped before receiving a result")
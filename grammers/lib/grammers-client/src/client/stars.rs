@@ -0,0 +1,150 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods related to Telegram Stars.
+
+use crate::Client;
+use crate::types::{ChatMap, IterBuffer, StarsStatus, StarsTransaction};
+pub use grammers_mtsender::InvocationError;
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+const MAX_TRANSACTIONS_LIMIT: usize = 50;
+
+pub type StarsTransactionIter =
+    IterBuffer<tl::functions::payments::GetStarsTransactions, StarsTransaction>;
+
+impl StarsTransactionIter {
+    fn new(client: &Client, peer: tl::enums::InputPeer) -> Self {
+        Self::from_request(
+            client,
+            MAX_TRANSACTIONS_LIMIT,
+            tl::functions::payments::GetStarsTransactions {
+                inbound: false,
+                outbound: false,
+                ascending: false,
+                ton: false,
+                subscription_id: None,
+                peer,
+                offset: String::new(),
+                limit: 0,
+            },
+        )
+    }
+
+    /// Only return transactions where stars were received.
+    pub fn inbound_only(mut self) -> Self {
+        self.request.inbound = true;
+        self.request.outbound = false;
+        self
+    }
+
+    /// Only return transactions where stars were spent.
+    pub fn outbound_only(mut self) -> Self {
+        self.request.inbound = false;
+        self.request.outbound = true;
+        self
+    }
+
+    /// Return the oldest transactions first, instead of the newest.
+    pub fn oldest_first(mut self) -> Self {
+        self.request.ascending = true;
+        self
+    }
+
+    /// Return the next `StarsTransaction` from the internal buffer, filling the buffer previously
+    /// if it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no transactions left.
+    pub async fn next(&mut self) -> Result<Option<StarsTransaction>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_TRANSACTIONS_LIMIT);
+        let tl::enums::payments::StarsStatus::Status(status) =
+            self.client.invoke(&self.request).await?;
+
+        match status.next_offset {
+            Some(offset) if !offset.is_empty() => self.request.offset = offset,
+            _ => self.last_chunk = true,
+        }
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&status.users, &status.chats);
+        }
+
+        let chats = ChatMap::new(status.users, status.chats);
+        self.buffer.extend(
+            status
+                .history
+                .into_iter()
+                .flatten()
+                .map(|transaction| StarsTransaction::from_raw(transaction, &chats)),
+        );
+
+        Ok(self.pop_item())
+    }
+}
+
+/// Method implementations related to Telegram Stars.
+impl Client {
+    /// Fetch the stars balance of the logged-in account, along with its most recent
+    /// transactions.
+    ///
+    /// Useful to check whether the account can afford buying, transferring or upgrading
+    /// a gift before attempting to do so.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let status = client.get_star_balance().await?;
+    /// if status.balance().stars().unwrap_or(0) < 25 {
+    ///     println!("not enough stars to upgrade the gift");
+    /// }
+    /// for transaction in status.transactions() {
+    ///     println!("{}: {:?}", transaction.date(), transaction.amount());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_star_balance(&self) -> Result<StarsStatus, InvocationError> {
+        self.invoke(&tl::functions::payments::GetStarsStatus {
+            ton: false,
+            peer: tl::enums::InputPeer::PeerSelf,
+        })
+        .await
+        .map(StarsStatus::from_raw)
+    }
+
+    /// Iterate over the stars transactions of the given user, bot or channel, newest first.
+    ///
+    /// Only the logged-in account and the bots and channels it manages can be queried.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let me = client.get_me().await?;
+    /// let mut transactions = client.iter_star_transactions(&me).outbound_only();
+    ///
+    /// while let Some(transaction) = transactions.next().await? {
+    ///     if let Some(gift) = transaction.unique_gift() {
+    ///         println!("{}: bought {} for {:?}", transaction.date(), gift.slug(), transaction.amount());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_star_transactions<C: Into<PackedChat>>(&self, peer: C) -> StarsTransactionIter {
+        StarsTransactionIter::new(self, peer.into().to_input_peer())
+    }
+}
@@ -11,6 +11,7 @@
 use super::Client;
 use crate::types::{ChatMap, Update};
 use futures_util::future::{Either, select};
+use grammers_mtsender::TelegramError;
 use grammers_mtsender::utils::sleep_until;
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
 use grammers_session::{ChatHashCache, MessageBoxes, State, UpdatesLike};
@@ -145,7 +146,10 @@ impl Client {
 
                 let response = match maybe_response {
                     Ok(r) => r,
-                    Err(e) if e.is("PERSISTENT_TIMESTAMP_OUTDATED") => {
+                    Err(e)
+                        if e.telegram_error()
+                            == Some(TelegramError::PersistentTimestampOutdated) =>
+                    {
                         // According to Telegram's docs:
                         // "Channel internal replication issues, try again later (treat this like an RPC_CALL_FAIL)."
                         // We can treat this as "empty difference" and not update the local pts.
@@ -170,7 +174,7 @@ impl Client {
                         }
                         continue;
                     }
-                    Err(e) if e.is("CHANNEL_PRIVATE") => {
+                    Err(e) if e.telegram_error() == Some(TelegramError::ChannelPrivate) => {
                         log::info!(
                             "Account is now banned so we can no longer fetch updates with request: {:?}",
                             request
@@ -56,6 +56,9 @@ pub use client::{Client, Config, InitParams, SignInError};
 pub use types::{ChatMap, InputMedia, InputMessage, Update, button, reply_markup};
 
 pub use grammers_mtproto::transport;
-pub use grammers_mtsender::{FixedReconnect, InvocationError, NoReconnect, ReconnectionPolicy};
+pub use grammers_mtsender::{
+    FixedReconnect, InvocationError, NoReconnect, ReconnectionPolicy, RpcError, RpcErrorKind,
+    TelegramError,
+};
 pub use grammers_session as session;
 pub use grammers_tl_types;
@@ -0,0 +1,149 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::{Chat, User};
+use grammers_tl_types as tl;
+use std::fmt;
+
+/// Full information about a user, such as their bio or business hours.
+///
+/// Unlike [`User`], which comes "for free" along with messages and dialogs, this information
+/// must be explicitly requested with [`crate::Client::get_full_user`].
+#[derive(Clone)]
+pub struct FullUser {
+    pub raw: tl::types::UserFull,
+    user: User,
+}
+
+/// Full information about a channel or megagroup, such as its description or member counts.
+///
+/// This information must be explicitly requested with [`crate::Client::get_full_channel`].
+#[derive(Clone)]
+pub struct FullChannel {
+    pub raw: tl::types::ChannelFull,
+    chat: Chat,
+}
+
+impl fmt::Debug for FullUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl fmt::Debug for FullChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl FullUser {
+    pub(crate) fn from_raw(full: tl::types::users::UserFull) -> Self {
+        let tl::enums::UserFull::Full(raw) = full.full_user;
+        let user = full
+            .users
+            .into_iter()
+            .map(User::from_raw)
+            .find(|user| user.id() == raw.id)
+            .unwrap_or_else(|| User::empty_with_hash_and_bot(raw.id, None, false));
+
+        Self { raw, user }
+    }
+
+    /// The user this information belongs to.
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    /// The user's bio, if they have one.
+    pub fn about(&self) -> Option<&str> {
+        self.raw.about.as_deref()
+    }
+
+    /// Opening hours of a business account, if the user has set them.
+    pub fn business_work_hours(&self) -> Option<&tl::types::BusinessWorkHours> {
+        self.raw
+            .business_work_hours
+            .as_ref()
+            .map(|tl::enums::BusinessWorkHours::Hours(hours)| hours)
+    }
+
+    /// The user's birthday, if it is visible to the logged-in account.
+    pub fn birthday(&self) -> Option<&tl::types::Birthday> {
+        self.raw
+            .birthday
+            .as_ref()
+            .map(|tl::enums::Birthday::Birthday(birthday)| birthday)
+    }
+
+    /// The channel the user has pinned to their profile, if any.
+    pub fn personal_channel_id(&self) -> Option<i64> {
+        self.raw.personal_channel_id
+    }
+
+    /// How many chats the logged-in account has in common with this user.
+    pub fn common_chats_count(&self) -> i32 {
+        self.raw.common_chats_count
+    }
+
+    /// How many gifts the user is displaying on their profile, if known.
+    pub fn star_gifts_count(&self) -> Option<i32> {
+        self.raw.stargifts_count
+    }
+}
+
+impl FullChannel {
+    /// Build the full channel from the result of `channels.getFullChannel`.
+    ///
+    /// Returns `None` if the server described a small group chat instead of a channel.
+    pub(crate) fn from_raw(full: tl::types::messages::ChatFull) -> Option<Self> {
+        let tl::enums::ChatFull::ChannelFull(raw) = full.full_chat else {
+            return None;
+        };
+        let chat = full
+            .chats
+            .into_iter()
+            .map(Chat::from_raw)
+            .find(|chat| chat.id() == raw.id)?;
+
+        Some(Self { raw, chat })
+    }
+
+    /// The channel or megagroup this information belongs to.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The channel's description. Empty if none was set.
+    pub fn about(&self) -> &str {
+        &self.raw.about
+    }
+
+    /// Total amount of participants, if visible to the logged-in account.
+    pub fn participants_count(&self) -> Option<i32> {
+        self.raw.participants_count
+    }
+
+    /// Amount of administrators, if visible to the logged-in account.
+    pub fn admins_count(&self) -> Option<i32> {
+        self.raw.admins_count
+    }
+
+    /// Amount of participants currently online, if known.
+    pub fn online_count(&self) -> Option<i32> {
+        self.raw.online_count
+    }
+
+    /// The discussion group linked to a broadcast channel (or vice versa), if any.
+    pub fn linked_chat_id(&self) -> Option<i64> {
+        self.raw.linked_chat_id
+    }
+
+    /// How many gifts the channel is displaying on its profile, if known.
+    pub fn star_gifts_count(&self) -> Option<i32> {
+        self.raw.stargifts_count
+    }
+}
@@ -18,6 +18,7 @@ pub mod chat_map;
 pub mod chats;
 pub mod dialog;
 pub mod downloadable;
+pub mod full_chat;
 pub mod input_media;
 pub mod input_message;
 pub mod iter_buffer;
@@ -30,7 +31,10 @@ pub mod permissions;
 pub mod photo_sizes;
 pub mod reactions;
 pub mod reply_markup;
+pub mod star_gifts;
+pub mod stars;
 pub mod terms_of_service;
+pub mod unique_gift;
 pub mod update;
 
 pub use action::ActionSender;
@@ -40,6 +44,7 @@ pub use chat_map::ChatMap;
 pub use chats::{AdminRightsBuilder, BannedRightsBuilder};
 pub use dialog::Dialog;
 pub use downloadable::Downloadable;
+pub use full_chat::{FullChannel, FullUser};
 pub use input_media::InputMedia;
 pub use input_message::InputMessage;
 pub use iter_buffer::IterBuffer;
@@ -52,5 +57,13 @@ pub use password_token::PasswordToken;
 pub use permissions::{Permissions, Restrictions};
 pub use reactions::InputReactions;
 pub(crate) use reply_markup::ReplyMarkup;
+pub use star_gifts::{
+    SavedStarGift, SavedStarGiftId, StarGiftCatalog, StarGiftUpgrade, StarGiftUpgradePreview,
+};
+pub use stars::{StarsAmount, StarsStatus, StarsTransaction};
 pub use terms_of_service::TermsOfService;
+pub use unique_gift::{
+    GiftAttribute, GiftAvailability, GiftBackdrop, GiftModel, GiftOriginalDetails, GiftOwner,
+    GiftPattern, UniqueGift,
+};
 pub use update::Update;
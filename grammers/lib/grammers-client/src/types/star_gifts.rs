@@ -0,0 +1,277 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::{Chat, ChatMap, GiftAttribute, GiftBackdrop, GiftModel, GiftPattern, UniqueGift};
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+use std::fmt;
+
+/// The catalog of star gift types that can be bought, as returned by
+/// [`crate::Client::get_star_gifts`].
+///
+/// The catalog only changes when Telegram adds or sells out gifts, so its [`StarGiftCatalog::hash`]
+/// can be kept around and sent on the next request to avoid downloading it again.
+#[derive(Clone)]
+pub struct StarGiftCatalog {
+    pub raw: tl::types::payments::StarGifts,
+}
+
+/// A star gift kept on the profile of a user or channel, as returned by
+/// [`crate::Client::iter_saved_star_gifts`].
+#[derive(Clone)]
+pub struct SavedStarGift {
+    pub raw: tl::types::SavedStarGift,
+    sender: Option<Chat>,
+}
+
+/// Every attribute a gift type can get when upgraded into a unique collectible gift, as returned
+/// by [`crate::Client::get_star_gift_upgrade_preview`].
+///
+/// The rarity of each attribute is known as soon as the collection is announced, before most
+/// (or any) gifts are upgraded.
+#[derive(Clone)]
+pub struct StarGiftUpgradePreview {
+    pub raw: tl::types::payments::StarGiftUpgradePreview,
+}
+
+/// Identifies a gift received by the logged-in user or by a channel they manage.
+///
+/// Unique gifts can also be identified by their slug, so a `&str` or `String` can be used
+/// wherever a `SavedStarGiftId` is expected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SavedStarGiftId {
+    /// A gift received by the logged-in user, identified by the service message it came with.
+    User { msg_id: i32 },
+    /// A gift received by a channel, identified by its [`SavedStarGift::saved_id`].
+    Chat { chat: PackedChat, saved_id: i64 },
+    /// A unique collectible gift, identified by its slug.
+    Slug(String),
+}
+
+/// The outcome of [`crate::Client::upgrade_star_gift`].
+#[derive(Clone, Debug)]
+pub enum StarGiftUpgrade {
+    /// The gift was upgraded. `None` if Telegram did not send the new unique gift back.
+    Upgraded(Option<UniqueGift>),
+    /// The payment has to be confirmed by opening `url` first. Until then, the gift stays as is.
+    VerificationNeeded { url: String },
+}
+
+impl fmt::Debug for StarGiftCatalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl fmt::Debug for SavedStarGift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl SavedStarGiftId {
+    pub(crate) fn to_raw(&self) -> tl::enums::InputSavedStarGift {
+        match self {
+            Self::User { msg_id } => tl::types::InputSavedStarGiftUser { msg_id: *msg_id }.into(),
+            Self::Chat { chat, saved_id } => tl::types::InputSavedStarGiftChat {
+                peer: chat.to_input_peer(),
+                saved_id: *saved_id,
+            }
+            .into(),
+            Self::Slug(slug) => tl::types::InputSavedStarGiftSlug { slug: slug.clone() }.into(),
+        }
+    }
+}
+
+impl From<&str> for SavedStarGiftId {
+    fn from(slug: &str) -> Self {
+        Self::Slug(slug.to_string())
+    }
+}
+
+impl From<String> for SavedStarGiftId {
+    fn from(slug: String) -> Self {
+        Self::Slug(slug)
+    }
+}
+
+impl StarGiftCatalog {
+    pub(crate) fn from_raw(raw: tl::types::payments::StarGifts) -> Self {
+        Self { raw }
+    }
+
+    /// Hash identifying this version of the catalog.
+    ///
+    /// Passing it to [`crate::Client::get_star_gifts`] returns `None` while the catalog is
+    /// unchanged.
+    pub fn hash(&self) -> i32 {
+        self.raw.hash
+    }
+
+    /// Every gift type in the catalog, including sold out ones.
+    pub fn gifts(&self) -> impl Iterator<Item = &tl::types::StarGift> {
+        // The catalog only lists gift types, never unique gifts.
+        self.raw.gifts.iter().filter_map(|gift| match gift {
+            tl::enums::StarGift::Gift(gift) => Some(gift),
+            tl::enums::StarGift::Unique(_) => None,
+        })
+    }
+
+    /// Gift types that can be upgraded into unique collectible gifts.
+    pub fn upgradable(&self) -> impl Iterator<Item = &tl::types::StarGift> {
+        self.gifts().filter(|gift| gift.upgrade_stars.is_some())
+    }
+
+    /// Number of gift types in the catalog.
+    pub fn len(&self) -> usize {
+        self.gifts().count()
+    }
+
+    /// Whether the catalog has no gift types at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for StarGiftUpgradePreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl StarGiftUpgradePreview {
+    pub(crate) fn from_raw(preview: tl::enums::payments::StarGiftUpgradePreview) -> Self {
+        let tl::enums::payments::StarGiftUpgradePreview::Preview(raw) = preview;
+        Self { raw }
+    }
+
+    /// Every possible attribute, in the order Telegram sent them.
+    pub fn attributes(&self) -> impl Iterator<Item = GiftAttribute> + '_ {
+        self.raw.sample_attributes.iter().map(GiftAttribute::from)
+    }
+
+    /// The models a gift of this type can have.
+    pub fn models(&self) -> impl Iterator<Item = GiftModel> + '_ {
+        self.attributes().filter_map(|attribute| match attribute {
+            GiftAttribute::Model(model) => Some(model),
+            _ => None,
+        })
+    }
+
+    /// The backdrops a gift of this type can have.
+    pub fn backdrops(&self) -> impl Iterator<Item = GiftBackdrop> + '_ {
+        self.attributes().filter_map(|attribute| match attribute {
+            GiftAttribute::Backdrop(backdrop) => Some(backdrop),
+            _ => None,
+        })
+    }
+
+    /// The patterns a gift of this type can have.
+    pub fn patterns(&self) -> impl Iterator<Item = GiftPattern> + '_ {
+        self.attributes().filter_map(|attribute| match attribute {
+            GiftAttribute::Pattern(pattern) => Some(pattern),
+            _ => None,
+        })
+    }
+}
+
+impl SavedStarGift {
+    pub(crate) fn from_raw(gift: tl::enums::SavedStarGift, chats: &ChatMap) -> Self {
+        let tl::enums::SavedStarGift::Gift(raw) = gift;
+        let sender = raw
+            .from_id
+            .as_ref()
+            .and_then(|peer| chats.get(peer))
+            .cloned();
+        Self { raw, sender }
+    }
+
+    /// The gift itself, either a regular gift type or a unique collectible gift.
+    pub fn gift(&self) -> &tl::enums::StarGift {
+        &self.raw.gift
+    }
+
+    /// The unique collectible gift, if this gift was upgraded.
+    pub fn unique(&self) -> Option<&tl::types::StarGiftUnique> {
+        match &self.raw.gift {
+            tl::enums::StarGift::Unique(gift) => Some(gift),
+            tl::enums::StarGift::Gift(_) => None,
+        }
+    }
+
+    /// Who sent the gift, unless they chose to stay anonymous.
+    pub fn sender(&self) -> Option<&Chat> {
+        self.sender.as_ref()
+    }
+
+    /// When the gift was received.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date)
+    }
+
+    /// Whether the gift is shown on the profile. Hidden gifts are only listed to the owner.
+    pub fn is_saved(&self) -> bool {
+        !self.raw.unsaved
+    }
+
+    /// Whether the gift is pinned to the top of the profile.
+    pub fn is_pinned(&self) -> bool {
+        self.raw.pinned_to_top
+    }
+
+    /// Identifier of the service message the gift came with, if it was received by the
+    /// logged-in user. See [`SavedStarGiftId::User`].
+    pub fn msg_id(&self) -> Option<i32> {
+        self.raw.msg_id
+    }
+
+    /// Identifier of the gift among those of the channel that received it.
+    /// See [`SavedStarGiftId::Chat`].
+    pub fn saved_id(&self) -> Option<i64> {
+        self.raw.saved_id
+    }
+
+    /// How many stars the owner gets by converting the regular gift into stars, while it still
+    /// can be converted. Only known to the owner.
+    pub fn convert_stars(&self) -> Option<i64> {
+        self.raw.convert_stars
+    }
+
+    /// Whether the gift can be upgraded into a unique collectible gift.
+    pub fn can_upgrade(&self) -> bool {
+        self.raw.can_upgrade
+    }
+
+    /// How many stars the sender already paid to upgrade the gift, if they did.
+    /// Such gifts can be upgraded for free.
+    pub fn prepaid_upgrade_stars(&self) -> Option<i64> {
+        self.raw.upgrade_stars
+    }
+
+    /// Price in stars the owner has to pay to transfer the gift to someone else.
+    /// Only known to the owner.
+    pub fn transfer_stars(&self) -> Option<i64> {
+        self.raw.transfer_stars
+    }
+
+    /// When the unique gift can be exported to the TON blockchain.
+    pub fn can_export_at(&self) -> Option<DateTime<Utc>> {
+        self.raw.can_export_at.map(utils::date)
+    }
+
+    /// When the unique gift can be transferred to someone else.
+    pub fn can_transfer_at(&self) -> Option<DateTime<Utc>> {
+        self.raw.can_transfer_at.map(utils::date)
+    }
+
+    /// When the unique gift can be listed for resale.
+    pub fn can_resell_at(&self) -> Option<DateTime<Utc>> {
+        self.raw.can_resell_at.map(utils::date)
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::{Chat, ChatMap, UniqueGift};
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+use std::fmt;
+
+/// An amount of Telegram Stars, or of TON for balances kept in TON.
+///
+/// Amounts can be negative, such as those of outgoing transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StarsAmount {
+    /// Whole stars, plus a fraction of a star in billionths.
+    Stars { amount: i64, nanos: i32 },
+    /// An amount of TON, in nanotons.
+    Ton { nanotons: i64 },
+}
+
+/// The stars balance of an account along with its most recent transactions, as returned by
+/// [`crate::Client::get_star_balance`].
+#[derive(Clone)]
+pub struct StarsStatus {
+    pub raw: tl::types::payments::StarsStatus,
+    transactions: Vec<StarsTransaction>,
+}
+
+/// A single incoming or outgoing stars transaction.
+#[derive(Clone)]
+pub struct StarsTransaction {
+    pub raw: tl::types::StarsTransaction,
+    peer: Option<Chat>,
+}
+
+impl fmt::Debug for StarsStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl fmt::Debug for StarsTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl StarsAmount {
+    /// Whole stars in the amount, rounded towards zero, or `None` for TON amounts.
+    pub fn stars(&self) -> Option<i64> {
+        match self {
+            Self::Stars { amount, .. } => Some(*amount),
+            Self::Ton { .. } => None,
+        }
+    }
+}
+
+impl From<&tl::enums::StarsAmount> for StarsAmount {
+    fn from(amount: &tl::enums::StarsAmount) -> Self {
+        match amount {
+            tl::enums::StarsAmount::Amount(amount) => Self::Stars {
+                amount: amount.amount,
+                nanos: amount.nanos,
+            },
+            tl::enums::StarsAmount::StarsTonAmount(amount) => Self::Ton {
+                nanotons: amount.amount,
+            },
+        }
+    }
+}
+
+impl From<tl::enums::StarsAmount> for StarsAmount {
+    fn from(amount: tl::enums::StarsAmount) -> Self {
+        Self::from(&amount)
+    }
+}
+
+impl StarsStatus {
+    pub(crate) fn from_raw(status: tl::enums::payments::StarsStatus) -> Self {
+        let tl::enums::payments::StarsStatus::Status(raw) = status;
+        let chats = ChatMap::new(raw.users.clone(), raw.chats.clone());
+        let transactions = raw
+            .history
+            .iter()
+            .flatten()
+            .map(|transaction| StarsTransaction::from_raw(transaction.clone(), &chats))
+            .collect();
+        Self { raw, transactions }
+    }
+
+    /// The current balance.
+    pub fn balance(&self) -> StarsAmount {
+        StarsAmount::from(&self.raw.balance)
+    }
+
+    /// The most recent transactions of the account.
+    pub fn transactions(&self) -> &[StarsTransaction] {
+        &self.transactions
+    }
+}
+
+impl StarsTransaction {
+    pub(crate) fn from_raw(transaction: tl::enums::StarsTransaction, chats: &ChatMap) -> Self {
+        let tl::enums::StarsTransaction::Transaction(raw) = transaction;
+        let peer = match &raw.peer {
+            tl::enums::StarsTransactionPeer::Peer(peer) => chats.get(&peer.peer).cloned(),
+            _ => None,
+        };
+        Self { raw, peer }
+    }
+
+    /// Identifier of the transaction.
+    pub fn id(&self) -> &str {
+        &self.raw.id
+    }
+
+    /// How many stars were received, or spent if negative.
+    pub fn amount(&self) -> StarsAmount {
+        StarsAmount::from(&self.raw.amount)
+    }
+
+    /// When the transaction was made.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date)
+    }
+
+    /// The user, bot or channel on the other side of the transaction, if it was one.
+    ///
+    /// Top-ups through app stores or Fragment have no peer.
+    pub fn peer(&self) -> Option<&Chat> {
+        self.peer.as_ref()
+    }
+
+    /// Title of the product that was paid for, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.raw.title.as_deref()
+    }
+
+    /// Description of the product that was paid for, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.raw.description.as_deref()
+    }
+
+    /// The star gift that was bought, sold, upgraded or converted, if the transaction was for one.
+    pub fn gift(&self) -> Option<&tl::enums::StarGift> {
+        self.raw.stargift.as_ref()
+    }
+
+    /// The unique collectible gift the transaction was for, if any.
+    pub fn unique_gift(&self) -> Option<UniqueGift> {
+        self.raw
+            .stargift
+            .clone()
+            .and_then(UniqueGift::from_star_gift)
+    }
+
+    /// Whether the transaction paid for upgrading a gift into a unique collectible gift.
+    pub fn is_gift_upgrade(&self) -> bool {
+        self.raw.stargift_upgrade
+    }
+
+    /// Whether the transaction bought or sold a unique gift on the resale market.
+    pub fn is_gift_resale(&self) -> bool {
+        self.raw.stargift_resale
+    }
+
+    /// Whether the transaction was a refund.
+    pub fn is_refund(&self) -> bool {
+        self.raw.refund
+    }
+
+    /// Whether the transaction has not been completed yet.
+    pub fn is_pending(&self) -> bool {
+        self.raw.pending
+    }
+
+    /// Whether the transaction failed.
+    pub fn is_failed(&self) -> bool {
+        self.raw.failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_amount() {
+        let stars = StarsAmount::from(tl::enums::StarsAmount::Amount(tl::types::StarsAmount {
+            amount: -25,
+            nanos: 0,
+        }));
+        assert_eq!(
+            stars,
+            StarsAmount::Stars {
+                amount: -25,
+                nanos: 0
+            }
+        );
+        assert_eq!(stars.stars(), Some(-25));
+
+        let ton = StarsAmount::from(tl::enums::StarsAmount::StarsTonAmount(
+            tl::types::StarsTonAmount { amount: 1_500 },
+        ));
+        assert_eq!(ton, StarsAmount::Ton { nanotons: 1_500 });
+        assert_eq!(ton.stars(), None);
+    }
+}
@@ -0,0 +1,452 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::media::Document;
+use super::User;
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+use std::fmt;
+
+/// A unique collectible gift, obtained by upgrading a limited star gift.
+///
+/// Each unique gift belongs to a collection (its [`UniqueGift::title`]) and has a number within
+/// it, along with a randomly-assigned model, backdrop and symbol pattern.
+#[derive(Clone)]
+pub struct UniqueGift {
+    pub raw: tl::types::StarGiftUnique,
+    /// The users Telegram sent along with the gift, such as its owner.
+    ///
+    /// Only [`Client::get_unique_star_gift`] fills this in; it's empty otherwise.
+    ///
+    /// [`Client::get_unique_star_gift`]: crate::Client::get_unique_star_gift
+    pub users: Vec<tl::enums::User>,
+}
+
+/// The model of a unique gift, which determines the sticker shown for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GiftModel {
+    pub name: String,
+    /// How many gifts of the collection out of a thousand have this model.
+    pub rarity_permille: i32,
+    /// The animated sticker for this model.
+    pub sticker: Document,
+}
+
+/// The symbol pattern drawn on the backdrop of a unique gift.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GiftPattern {
+    pub name: String,
+    /// How many gifts of the collection out of a thousand have this pattern.
+    pub rarity_permille: i32,
+    /// The sticker used for the symbols of the pattern.
+    pub sticker: Document,
+}
+
+/// The backdrop of a unique gift. Colors are RGB packed as `0xRRGGBB`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GiftBackdrop {
+    pub name: String,
+    pub id: i32,
+    pub center_color: u32,
+    pub edge_color: u32,
+    pub pattern_color: u32,
+    pub text_color: u32,
+    /// How many gifts of the collection out of a thousand have this backdrop.
+    pub rarity_permille: i32,
+}
+
+/// Who the gift was originally sent to before it was upgraded, and by whom.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GiftOriginalDetails {
+    /// The sender of the gift, unless they sent it anonymously.
+    pub sender: Option<tl::enums::Peer>,
+    pub recipient: tl::enums::Peer,
+    pub date: DateTime<Utc>,
+    /// The message sent along with the gift, if any.
+    pub message: Option<String>,
+}
+
+/// One of the attributes a unique gift was assigned when it was upgraded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GiftAttribute {
+    Model(GiftModel),
+    Backdrop(GiftBackdrop),
+    Pattern(GiftPattern),
+    OriginalDetails(GiftOriginalDetails),
+}
+
+/// Who currently owns a unique gift.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GiftOwner {
+    /// The gift is owned by a user or channel. The name may be present even if the peer is not.
+    Peer {
+        peer: tl::enums::Peer,
+        name: Option<String>,
+    },
+    /// The owner is not known, only the name they chose to show.
+    Name(String),
+    /// The gift was exported to the TON blockchain and belongs to this wallet address.
+    Address(String),
+    /// The owner chose to hide who they are.
+    Hidden,
+}
+
+/// How many gifts of a collection have been upgraded so far, out of how many can exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GiftAvailability {
+    pub issued: i32,
+    pub total: i32,
+}
+
+impl fmt::Debug for UniqueGift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl UniqueGift {
+    pub fn from_raw(gift: tl::types::StarGiftUnique) -> Self {
+        Self::with_users(gift, Vec::new())
+    }
+
+    /// The unique gift along with the users sent in the same response.
+    pub fn with_users(gift: tl::types::StarGiftUnique, users: Vec<tl::enums::User>) -> Self {
+        Self { raw: gift, users }
+    }
+
+    /// The unique gift contained in a raw star gift, if it is one.
+    pub fn from_star_gift(gift: tl::enums::StarGift) -> Option<Self> {
+        match gift {
+            tl::enums::StarGift::Unique(gift) => Some(Self::from_raw(gift)),
+            tl::enums::StarGift::Gift(_) => None,
+        }
+    }
+
+    /// The identifier of the gift, such as `PlushPepe-1`. It can be opened in
+    /// `https://t.me/nft/{slug}`.
+    pub fn slug(&self) -> &str {
+        &self.raw.slug
+    }
+
+    /// The number of the gift within its collection.
+    pub fn num(&self) -> i32 {
+        self.raw.num
+    }
+
+    /// The name of the collection the gift belongs to, such as `Plush Pepe`.
+    pub fn title(&self) -> &str {
+        &self.raw.title
+    }
+
+    /// The attributes of the gift, in the order Telegram sent them.
+    pub fn attributes(&self) -> impl Iterator<Item = GiftAttribute> + '_ {
+        self.raw.attributes.iter().map(GiftAttribute::from)
+    }
+
+    /// The model of the gift.
+    pub fn model(&self) -> Option<GiftModel> {
+        self.attributes().find_map(|attribute| match attribute {
+            GiftAttribute::Model(model) => Some(model),
+            _ => None,
+        })
+    }
+
+    /// The backdrop of the gift.
+    pub fn backdrop(&self) -> Option<GiftBackdrop> {
+        self.attributes().find_map(|attribute| match attribute {
+            GiftAttribute::Backdrop(backdrop) => Some(backdrop),
+            _ => None,
+        })
+    }
+
+    /// The symbol pattern of the gift.
+    pub fn pattern(&self) -> Option<GiftPattern> {
+        self.attributes().find_map(|attribute| match attribute {
+            GiftAttribute::Pattern(pattern) => Some(pattern),
+            _ => None,
+        })
+    }
+
+    /// Who the gift was originally sent to, if the owner chose to show it.
+    pub fn original_details(&self) -> Option<GiftOriginalDetails> {
+        self.attributes().find_map(|attribute| match attribute {
+            GiftAttribute::OriginalDetails(details) => Some(details),
+            _ => None,
+        })
+    }
+
+    /// Who currently owns the gift.
+    pub fn owner(&self) -> GiftOwner {
+        match (
+            &self.raw.owner_id,
+            &self.raw.owner_name,
+            &self.raw.owner_address,
+        ) {
+            (Some(peer), name, _) => GiftOwner::Peer {
+                peer: peer.clone(),
+                name: name.clone(),
+            },
+            (None, Some(name), _) => GiftOwner::Name(name.clone()),
+            (None, None, Some(address)) => GiftOwner::Address(address.clone()),
+            (None, None, None) => GiftOwner::Hidden,
+        }
+    }
+
+    /// The user that owns the gift, if it was sent along with the gift (see [`UniqueGift::users`]).
+    pub fn owner_user(&self) -> Option<User> {
+        let Some(tl::enums::Peer::User(owner)) = &self.raw.owner_id else {
+            return None;
+        };
+        self.users
+            .iter()
+            .map(|user| User::from_raw(user.clone()))
+            .find(|user| user.id() == owner.user_id)
+    }
+
+    /// How many gifts of the collection have been upgraded, out of how many can exist.
+    pub fn availability(&self) -> GiftAvailability {
+        GiftAvailability {
+            issued: self.raw.availability_issued,
+            total: self.raw.availability_total,
+        }
+    }
+
+    /// Address of the gift on the TON blockchain, if it was exported there as an NFT.
+    pub fn gift_address(&self) -> Option<&str> {
+        self.raw.gift_address.as_deref()
+    }
+
+    /// Address of the TON wallet that owns the gift, if it was exported to the blockchain.
+    ///
+    /// Unlike [`UniqueGift::owner`], this is returned even when the owner is also known
+    /// by name.
+    pub fn owner_address(&self) -> Option<&str> {
+        self.raw.owner_address.as_deref()
+    }
+
+    /// Price in stars the gift is listed for on the resale market, or `None` if it's not for sale.
+    pub fn resale_price(&self) -> Option<i64> {
+        self.raw.resell_stars
+    }
+}
+
+impl From<UniqueGift> for tl::enums::payments::UniqueStarGift {
+    fn from(gift: UniqueGift) -> Self {
+        tl::types::payments::UniqueStarGift {
+            gift: gift.raw.into(),
+            users: gift.users,
+        }
+        .into()
+    }
+}
+
+impl GiftAttribute {
+    /// The name of the model, backdrop or pattern. Original details have no name.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Model(model) => Some(&model.name),
+            Self::Backdrop(backdrop) => Some(&backdrop.name),
+            Self::Pattern(pattern) => Some(&pattern.name),
+            Self::OriginalDetails(_) => None,
+        }
+    }
+
+    /// How many gifts of the collection out of a thousand share this attribute.
+    /// Original details are unique to each gift and have no rarity.
+    pub fn rarity_permille(&self) -> Option<i32> {
+        match self {
+            Self::Model(model) => Some(model.rarity_permille),
+            Self::Backdrop(backdrop) => Some(backdrop.rarity_permille),
+            Self::Pattern(pattern) => Some(pattern.rarity_permille),
+            Self::OriginalDetails(_) => None,
+        }
+    }
+
+    /// The sticker of a model or pattern.
+    pub fn document(&self) -> Option<&Document> {
+        match self {
+            Self::Model(model) => Some(&model.sticker),
+            Self::Pattern(pattern) => Some(&pattern.sticker),
+            Self::Backdrop(_) | Self::OriginalDetails(_) => None,
+        }
+    }
+}
+
+impl From<&tl::enums::StarGiftAttribute> for GiftAttribute {
+    fn from(attribute: &tl::enums::StarGiftAttribute) -> Self {
+        use tl::enums::StarGiftAttribute as A;
+
+        match attribute {
+            A::Model(model) => Self::Model(GiftModel {
+                name: model.name.clone(),
+                rarity_permille: model.rarity_permille,
+                sticker: sticker(&model.document),
+            }),
+            A::Backdrop(backdrop) => Self::Backdrop(GiftBackdrop {
+                name: backdrop.name.clone(),
+                id: backdrop.backdrop_id,
+                center_color: rgb(backdrop.center_color),
+                edge_color: rgb(backdrop.edge_color),
+                pattern_color: rgb(backdrop.pattern_color),
+                text_color: rgb(backdrop.text_color),
+                rarity_permille: backdrop.rarity_permille,
+            }),
+            A::Pattern(pattern) => Self::Pattern(GiftPattern {
+                name: pattern.name.clone(),
+                rarity_permille: pattern.rarity_permille,
+                sticker: sticker(&pattern.document),
+            }),
+            A::OriginalDetails(details) => Self::OriginalDetails(GiftOriginalDetails {
+                sender: details.sender_id.clone(),
+                recipient: details.recipient_id.clone(),
+                date: utils::date(details.date),
+                message: details
+                    .message
+                    .as_ref()
+                    .map(|tl::enums::TextWithEntities::Entities(message)| message.text.clone()),
+            }),
+        }
+    }
+}
+
+impl From<tl::enums::StarGiftAttribute> for GiftAttribute {
+    fn from(attribute: tl::enums::StarGiftAttribute) -> Self {
+        Self::from(&attribute)
+    }
+}
+
+// Documents can only be downloaded when wrapped as message media.
+fn sticker(document: &tl::enums::Document) -> Document {
+    Document::from_raw_media(tl::types::MessageMediaDocument {
+        nopremium: false,
+        spoiler: false,
+        video: false,
+        round: false,
+        voice: false,
+        document: Some(document.clone()),
+        alt_documents: None,
+        video_cover: None,
+        video_timestamp: None,
+        ttl_seconds: None,
+    })
+}
+
+fn rgb(color: i32) -> u32 {
+    color as u32 & 0xff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gift(
+        owner_id: Option<tl::enums::Peer>,
+        owner_name: Option<&str>,
+        owner_address: Option<&str>,
+    ) -> UniqueGift {
+        UniqueGift::from_raw(tl::types::StarGiftUnique {
+            id: 1,
+            title: "Plush Pepe".to_string(),
+            slug: "PlushPepe-7".to_string(),
+            num: 7,
+            owner_id,
+            owner_name: owner_name.map(str::to_string),
+            owner_address: owner_address.map(str::to_string),
+            attributes: vec![
+                tl::types::StarGiftAttributeBackdrop {
+                    name: "Black".to_string(),
+                    backdrop_id: 3,
+                    center_color: 0x363738,
+                    edge_color: -1,
+                    pattern_color: 0,
+                    text_color: 0xffffff,
+                    rarity_permille: 20,
+                }
+                .into(),
+                tl::types::StarGiftAttributeModel {
+                    name: "Gold".to_string(),
+                    document: tl::types::DocumentEmpty { id: 1 }.into(),
+                    rarity_permille: 15,
+                }
+                .into(),
+            ],
+            availability_issued: 10,
+            availability_total: 5000,
+            gift_address: None,
+            resell_stars: None,
+            released_by: None,
+        })
+    }
+
+    #[test]
+    fn check_attributes() {
+        let gift = gift(None, None, None);
+        assert_eq!(
+            gift.model().map(|model| model.name),
+            Some("Gold".to_string())
+        );
+        assert_eq!(gift.pattern(), None);
+        assert_eq!(
+            gift.attributes()
+                .map(|attribute| (
+                    attribute.name().map(str::to_string),
+                    attribute.rarity_permille()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("Black".to_string()), Some(20)),
+                (Some("Gold".to_string()), Some(15))
+            ]
+        );
+
+        let backdrop = gift.backdrop().unwrap();
+        assert_eq!(backdrop.center_color, 0x363738);
+        assert_eq!(backdrop.edge_color, 0xffffff);
+        assert_eq!(
+            gift.availability(),
+            GiftAvailability {
+                issued: 10,
+                total: 5000
+            }
+        );
+    }
+
+    #[test]
+    fn check_owner() {
+        let peer: tl::enums::Peer = tl::types::PeerUser { user_id: 1 }.into();
+        assert_eq!(
+            gift(Some(peer.clone()), Some("Alice"), None).owner(),
+            GiftOwner::Peer {
+                peer,
+                name: Some("Alice".to_string())
+            }
+        );
+        assert_eq!(
+            gift(None, Some("Alice"), Some("UQ...")).owner(),
+            GiftOwner::Name("Alice".to_string())
+        );
+        assert_eq!(
+            gift(None, None, Some("UQ...")).owner(),
+            GiftOwner::Address("UQ...".to_string())
+        );
+        assert_eq!(gift(None, None, None).owner(), GiftOwner::Hidden);
+    }
+
+    #[test]
+    fn check_owner_user() {
+        let peer: tl::enums::Peer = tl::types::PeerUser { user_id: 1 }.into();
+        let mut gift = gift(Some(peer), None, None);
+        assert!(gift.owner_user().is_none());
+
+        gift.users = vec![
+            tl::types::UserEmpty { id: 2 }.into(),
+            tl::types::UserEmpty { id: 1 }.into(),
+        ];
+        assert_eq!(gift.owner_user().map(|user| user.id()), Some(1));
+    }
+}
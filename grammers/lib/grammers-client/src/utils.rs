@@ -46,21 +46,26 @@ pub(crate) fn date(date: i32) -> DateTime<Utc> {
     DateTime::<Utc>::from_timestamp(date as i64, 0).expect("date out of range")
 }
 
+/// The `salt1`, `salt2`, `p` and `g` of the password KDF algorithm.
+pub(crate) type PasswordParameters<'a> = (&'a Vec<u8>, &'a Vec<u8>, &'a Vec<u8>, &'a i32);
+
+/// Extract the salts, `p` and `g` used to check the password with SRP.
+///
+/// Returns `None` for an unknown KDF (most likely, the client is outdated and does not support
+/// the specified KDF algorithm).
 pub(crate) fn extract_password_parameters(
     current_algo: &tl::enums::PasswordKdfAlgo,
-) -> (&Vec<u8>, &Vec<u8>, &Vec<u8>, &i32) {
+) -> Option<PasswordParameters<'_>> {
     let tl::types::PasswordKdfAlgoSha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow {
         salt1,
         salt2,
         p,
         g,
     } = match current_algo {
-        tl::enums::PasswordKdfAlgo::Unknown => panic!(
-            "Unknown KDF (most likely, the client is outdated and does not support the specified KDF algorithm)"
-        ),
+        tl::enums::PasswordKdfAlgo::Unknown => return None,
         tl::enums::PasswordKdfAlgo::Sha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow(alg) => alg,
     };
-    (salt1, salt2, p, g)
+    Some((salt1, salt2, p, g))
 }
 
 /// Get a `Chat`, no matter what.
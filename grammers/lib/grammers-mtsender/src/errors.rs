@@ -9,6 +9,7 @@ use grammers_mtproto::{authentication, mtp, transport};
 use grammers_tl_types as tl;
 use std::fmt;
 use std::io;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum ReadError {
@@ -86,6 +87,129 @@ pub struct RpcError {
 
 impl std::error::Error for RpcError {}
 
+/// Broad families of RPC errors, derived from the error name and code.
+///
+/// This lets callers react to whole classes of errors without matching on raw names.
+/// The list is not exhaustive, and errors which don't belong to any known family are
+/// reported as [`RpcErrorKind::Other`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RpcErrorKind {
+    /// The request must not be repeated until some time passes
+    /// (`FLOOD_WAIT_X`, `FLOOD_PREMIUM_WAIT_X`, `SLOWMODE_WAIT_X`).
+    FloodWait,
+
+    /// The request must be repeated in a different datacenter (`*_MIGRATE_X`).
+    Migrate,
+
+    /// The login code was wrong, expired, or empty (`PHONE_CODE_*`).
+    PhoneCode,
+
+    /// The account has two-factor authentication enabled (`SESSION_PASSWORD_NEEDED`).
+    PasswordRequired,
+
+    /// The session is not (or no longer) authorized (code 401, such as `AUTH_KEY_UNREGISTERED`
+    /// or `SESSION_REVOKED`).
+    Unauthorized,
+
+    /// One of the request parameters was invalid (`*_INVALID`).
+    InvalidParameter,
+
+    /// The server failed to process the request, which may succeed if retried (code 500).
+    Internal,
+
+    /// Any other error.
+    Other,
+}
+
+/// Common RPC errors, recognized from the error name and code.
+///
+/// Unlike [`RpcErrorKind`], which only tells the family an error belongs to, each variant here
+/// is one specific error, so callers can match on it instead of comparing names with
+/// [`RpcError::is`]. Errors which are not listed are reported as [`TelegramError::Other`],
+/// and the [`RpcError`] itself still has all the details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TelegramError {
+    /// The request must not be repeated until this much time passes
+    /// (`FLOOD_WAIT_X`, `FLOOD_PREMIUM_WAIT_X`, `SLOWMODE_WAIT_X`).
+    FloodWait(Duration),
+
+    /// The request must be repeated in the datacenter with this ID (`*_MIGRATE_X`).
+    Migrate(i32),
+
+    /// The slug, such as the one of a unique star gift, does not exist (`*SLUG_INVALID`).
+    SlugInvalid,
+
+    /// The peer is unknown or its access hash is wrong (`PEER_ID_INVALID`).
+    PeerIdInvalid,
+
+    /// The channel is private or the account was banned from it (`CHANNEL_PRIVATE`).
+    ChannelPrivate,
+
+    /// The username is malformed (`USERNAME_INVALID`).
+    UsernameInvalid,
+
+    /// Nobody uses the username (`USERNAME_NOT_OCCUPIED`).
+    UsernameNotOccupied,
+
+    /// The phone number is malformed or not in use (`PHONE_NUMBER_INVALID`).
+    PhoneNumberInvalid,
+
+    /// The login code was wrong (`PHONE_CODE_INVALID`).
+    PhoneCodeInvalid,
+
+    /// The login code expired and a new one must be requested (`PHONE_CODE_EXPIRED`).
+    PhoneCodeExpired,
+
+    /// The login code was missing (`PHONE_CODE_EMPTY`).
+    PhoneCodeEmpty,
+
+    /// The account has two-factor authentication enabled (`SESSION_PASSWORD_NEEDED`).
+    PasswordRequired,
+
+    /// The two-factor authentication password was wrong (`PASSWORD_HASH_INVALID`).
+    PasswordHashInvalid,
+
+    /// The session was never authorized or was logged out (`AUTH_KEY_UNREGISTERED`).
+    AuthKeyUnregistered,
+
+    /// The session was terminated from another device (`SESSION_REVOKED`).
+    SessionRevoked,
+
+    /// The account was deleted or deactivated (`USER_DEACTIVATED`, `USER_DEACTIVATED_BAN`).
+    UserDeactivated,
+
+    /// The server is having internal issues and the request should be retried later
+    /// (`PERSISTENT_TIMESTAMP_OUTDATED`).
+    PersistentTimestampOutdated,
+
+    /// Any other error.
+    Other,
+}
+
+/// Errors without a value which map directly to a [`TelegramError`].
+const KNOWN_ERRORS: &[(&str, TelegramError)] = &[
+    ("*SLUG_INVALID", TelegramError::SlugInvalid),
+    ("PEER_ID_INVALID", TelegramError::PeerIdInvalid),
+    ("CHANNEL_PRIVATE", TelegramError::ChannelPrivate),
+    ("USERNAME_INVALID", TelegramError::UsernameInvalid),
+    ("USERNAME_NOT_OCCUPIED", TelegramError::UsernameNotOccupied),
+    ("PHONE_NUMBER_INVALID", TelegramError::PhoneNumberInvalid),
+    ("PHONE_CODE_INVALID", TelegramError::PhoneCodeInvalid),
+    ("PHONE_CODE_EXPIRED", TelegramError::PhoneCodeExpired),
+    ("PHONE_CODE_EMPTY", TelegramError::PhoneCodeEmpty),
+    ("SESSION_PASSWORD_NEEDED", TelegramError::PasswordRequired),
+    ("PASSWORD_HASH_INVALID", TelegramError::PasswordHashInvalid),
+    ("AUTH_KEY_UNREGISTERED", TelegramError::AuthKeyUnregistered),
+    ("SESSION_REVOKED", TelegramError::SessionRevoked),
+    ("USER_DEACTIVATED*", TelegramError::UserDeactivated),
+    (
+        "PERSISTENT_TIMESTAMP_OUTDATED",
+        TelegramError::PersistentTimestampOutdated,
+    ),
+];
+
 impl fmt::Display for RpcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "rpc error {}: {}", self.code, self.name)?;
@@ -156,6 +280,79 @@ impl RpcError {
         }
     }
 
+    /// The family this error belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let rpc_err = grammers_mtsender::RpcError {
+    /// #     code: 420, name: "FLOOD_WAIT".to_string(), value: Some(31), caused_by: None };
+    /// use grammers_mtsender::RpcErrorKind;
+    ///
+    /// assert_eq!(rpc_err.kind(), RpcErrorKind::FloodWait);
+    /// ```
+    pub fn kind(&self) -> RpcErrorKind {
+        if self.code == 420
+            || self.is("FLOOD_WAIT")
+            || self.is("FLOOD_PREMIUM_WAIT")
+            || self.is("SLOWMODE_WAIT")
+        {
+            RpcErrorKind::FloodWait
+        } else if self.code == 303 || self.is("*_MIGRATE") {
+            RpcErrorKind::Migrate
+        } else if self.is("PHONE_CODE_*") {
+            RpcErrorKind::PhoneCode
+        } else if self.is("SESSION_PASSWORD_NEEDED") {
+            RpcErrorKind::PasswordRequired
+        } else if self.code == 401 {
+            RpcErrorKind::Unauthorized
+        } else if self.is("*_INVALID") {
+            RpcErrorKind::InvalidParameter
+        } else if self.code == 500 {
+            RpcErrorKind::Internal
+        } else {
+            RpcErrorKind::Other
+        }
+    }
+
+    /// How many seconds must pass before the request can be retried, if this is a flood-wait.
+    pub fn flood_wait_seconds(&self) -> Option<u32> {
+        match self.kind() {
+            RpcErrorKind::FloodWait => self.value,
+            _ => None,
+        }
+    }
+
+    /// The specific error this is, if it's one of the common ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let rpc_err = grammers_mtsender::RpcError {
+    /// #     code: 420, name: "FLOOD_WAIT".to_string(), value: Some(31), caused_by: None };
+    /// use grammers_mtsender::TelegramError;
+    /// use std::time::Duration;
+    ///
+    /// match rpc_err.telegram_error() {
+    ///     TelegramError::FloodWait(wait) => assert_eq!(wait, Duration::from_secs(31)),
+    ///     TelegramError::SlugInvalid => println!("no such gift"),
+    ///     _ => println!("{rpc_err}"),
+    /// }
+    /// ```
+    pub fn telegram_error(&self) -> TelegramError {
+        match (self.kind(), self.value) {
+            (RpcErrorKind::FloodWait, Some(seconds)) => {
+                return TelegramError::FloodWait(Duration::from_secs(seconds.into()));
+            }
+            (RpcErrorKind::Migrate, Some(dc_id)) => return TelegramError::Migrate(dc_id as i32),
+            _ => {}
+        }
+        KNOWN_ERRORS
+            .iter()
+            .find(|(name, _)| self.is(name))
+            .map_or(TelegramError::Other, |&(_, error)| error)
+    }
+
     pub fn with_caused_by(mut self, constructor_id: u32) -> Self {
         self.caused_by = Some(constructor_id);
         self
@@ -236,9 +433,68 @@ impl InvocationError {
             _ => false,
         }
     }
+
+    /// The family of the RPC error, or `None` if the error is not a RPC error.
+    pub fn kind(&self) -> Option<RpcErrorKind> {
+        match self {
+            Self::Rpc(rpc) => Some(rpc.kind()),
+            _ => None,
+        }
+    }
+
+    /// The specific RPC error, or `None` if the error is not a RPC error.
+    ///
+    /// See [`RpcError::telegram_error`].
+    pub fn telegram_error(&self) -> Option<TelegramError> {
+        match self {
+            Self::Rpc(rpc) => Some(rpc.telegram_error()),
+            _ => None,
+        }
+    }
+
+    /// How many seconds must pass before the request can be retried, if this is a flood-wait.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(err: grammers_mtsender::InvocationError) {
+    /// if let Some(seconds) = err.flood_wait_seconds() {
+    ///     tokio::time::sleep(std::time::Duration::from_secs(seconds as _)).await;
+    /// }
+    /// # }
+    /// ```
+    pub fn flood_wait_seconds(&self) -> Option<u32> {
+        match self {
+            Self::Rpc(rpc) => rpc.flood_wait_seconds(),
+            _ => None,
+        }
+    }
+
+    /// Whether the error means the requested slug (such as the one of a unique star gift)
+    /// does not exist.
+    pub fn is_slug_invalid(&self) -> bool {
+        self.telegram_error() == Some(TelegramError::SlugInvalid)
+    }
+
+    /// Whether the error was caused by losing the connection to Telegram rather than by the
+    /// request itself, so the request may succeed if it is sent again over a new connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(err: grammers_mtsender::InvocationError) {
+    /// if err.is_connection_lost() {
+    ///     println!("reconnecting...");
+    /// }
+    /// # }
+    /// ```
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(self, Self::Dropped | Self::Read(ReadError::Io(_)))
+    }
 }
 
-/// This error occurs when the process to generate an authorization key fails.
+/// This error occurs when the process to generate an authorization key fails, or when
+/// authorizing the account with it does.
 #[derive(Debug)]
 pub enum AuthorizationError {
     /// The generation failed because the generation process went wrong.
@@ -246,6 +502,15 @@ pub enum AuthorizationError {
 
     /// The generation failed because invoking a request failed.
     Invoke(InvocationError),
+
+    /// Telegram will only send the login code to this phone number after the user buys the
+    /// in-app subscription with the given product identifier, which can only be done from an
+    /// official application.
+    PaymentRequired { store_product: String },
+
+    /// Telegram answered with a response that does not make sense at this point of the
+    /// authorization, such as asking a bot to sign up. The name of the response is included.
+    UnexpectedResponse(&'static str),
 }
 
 impl std::error::Error for AuthorizationError {}
@@ -255,6 +520,13 @@ impl fmt::Display for AuthorizationError {
         match self {
             Self::Gen(err) => write!(f, "authorization error: {err}"),
             Self::Invoke(err) => write!(f, "authorization error: {err}"),
+            Self::PaymentRequired { store_product } => write!(
+                f,
+                "authorization error: payment required to receive the login code ({store_product})"
+            ),
+            Self::UnexpectedResponse(name) => {
+                write!(f, "authorization error: unexpected response {name}")
+            }
         }
     }
 }
@@ -324,4 +596,117 @@ mod tests {
             }
         );
     }
+    #[test]
+    fn check_telegram_error() {
+        let rpc = |code, name: &str, value| RpcError {
+            code,
+            name: name.into(),
+            value,
+            caused_by: None,
+        };
+
+        assert_eq!(
+            rpc(420, "FLOOD_WAIT", Some(31)).telegram_error(),
+            TelegramError::FloodWait(Duration::from_secs(31))
+        );
+        assert_eq!(
+            rpc(420, "SLOWMODE_WAIT", Some(5)).telegram_error(),
+            TelegramError::FloodWait(Duration::from_secs(5))
+        );
+        assert_eq!(
+            rpc(303, "PHONE_MIGRATE", Some(2)).telegram_error(),
+            TelegramError::Migrate(2)
+        );
+        assert_eq!(
+            rpc(400, "STARGIFT_SLUG_INVALID", None).telegram_error(),
+            TelegramError::SlugInvalid
+        );
+        assert_eq!(
+            rpc(401, "SESSION_PASSWORD_NEEDED", None).telegram_error(),
+            TelegramError::PasswordRequired
+        );
+        assert_eq!(
+            rpc(401, "USER_DEACTIVATED_BAN", None).telegram_error(),
+            TelegramError::UserDeactivated
+        );
+        assert_eq!(
+            rpc(403, "CHAT_WRITE_FORBIDDEN", None).telegram_error(),
+            TelegramError::Other
+        );
+        assert_eq!(InvocationError::Dropped.telegram_error(), None);
+    }
+
+    #[test]
+    fn check_connection_lost() {
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(InvocationError::Dropped.is_connection_lost());
+        assert!(InvocationError::Read(ReadError::Io(reset)).is_connection_lost());
+        assert!(
+            !InvocationError::Read(ReadError::Transport(transport::Error::MissingBytes))
+                .is_connection_lost()
+        );
+        assert!(
+            !InvocationError::Rpc(RpcError {
+                code: 400,
+                name: "GIFT_SLUG_INVALID".into(),
+                value: None,
+                caused_by: None,
+            })
+            .is_connection_lost()
+        );
+    }
+
+    #[test]
+    fn check_rpc_error_kind() {
+        let rpc = |code, name: &str, value| RpcError {
+            code,
+            name: name.into(),
+            value,
+            caused_by: None,
+        };
+
+        assert_eq!(
+            rpc(420, "FLOOD_WAIT", Some(31)).kind(),
+            RpcErrorKind::FloodWait
+        );
+        assert_eq!(
+            rpc(420, "FLOOD_WAIT", Some(31)).flood_wait_seconds(),
+            Some(31)
+        );
+        assert_eq!(
+            rpc(303, "PHONE_MIGRATE", Some(2)).kind(),
+            RpcErrorKind::Migrate
+        );
+        assert_eq!(
+            rpc(303, "PHONE_MIGRATE", Some(2)).flood_wait_seconds(),
+            None
+        );
+        assert_eq!(
+            rpc(400, "PHONE_CODE_INVALID", None).kind(),
+            RpcErrorKind::PhoneCode
+        );
+        assert_eq!(
+            rpc(401, "SESSION_PASSWORD_NEEDED", None).kind(),
+            RpcErrorKind::PasswordRequired
+        );
+        assert_eq!(
+            rpc(401, "AUTH_KEY_UNREGISTERED", None).kind(),
+            RpcErrorKind::Unauthorized
+        );
+        assert_eq!(
+            rpc(400, "STARGIFT_SLUG_INVALID", None).kind(),
+            RpcErrorKind::InvalidParameter
+        );
+        assert_eq!(
+            rpc(500, "INTERDC_CALL_ERROR", Some(2)).kind(),
+            RpcErrorKind::Internal
+        );
+        assert_eq!(
+            rpc(403, "CHAT_WRITE_FORBIDDEN", None).kind(),
+            RpcErrorKind::Other
+        );
+
+        assert!(InvocationError::Rpc(rpc(400, "STARGIFT_SLUG_INVALID", None)).is_slug_invalid());
+        assert!(!InvocationError::Dropped.is_slug_invalid());
+    }
 }
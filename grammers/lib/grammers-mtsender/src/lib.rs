@@ -14,7 +14,9 @@ mod reconnection;
 pub mod utils;
 
 pub use crate::reconnection::*;
-pub use errors::{AuthorizationError, InvocationError, ReadError, RpcError};
+pub use errors::{
+    AuthorizationError, InvocationError, ReadError, RpcError, RpcErrorKind, TelegramError,
+};
 use futures_util::future::{Either, pending, select};
 use grammers_crypto::DequeBuffer;
 use grammers_mtproto::mtp::{
@@ -58,17 +60,17 @@ const LEADING_BUFFER_SPACE: usize = mtp::MAX_TRANSPORT_HEADER_LEN
     + mtp::PLAIN_PACKET_HEADER_LEN
     + mtp::MESSAGE_CONTAINER_HEADER_LEN;
 
-/// Every how often are pings sent?
-const PING_DELAY: Duration = Duration::from_secs(60);
+/// Every how often are pings sent by default?
+pub const PING_DELAY: Duration = Duration::from_secs(60);
 
-/// After how many seconds should the server close the connection when we send a ping?
+/// How much longer than the ping delay should the server wait before closing the connection?
 ///
-/// What this value essentially means is that we have `NO_PING_DISCONNECT - PING_DELAY` seconds
-/// to keep sending pings, or the server will close the connection.
+/// Every ping asks the server to close the connection if no other ping arrives within
+/// `ping_delay + PING_DISCONNECT_GRACE`, so we have this much time to keep sending pings.
 ///
 /// Pings ensure the connection is kept active, and the delayed disconnect ensures the messages
 /// are getting through consistently enough.
-const NO_PING_DISCONNECT: i32 = 75;
+const PING_DISCONNECT_GRACE: Duration = Duration::from_secs(15);
 
 /// Generate a "random" ping ID.
 pub(crate) fn generate_random_id() -> i64 {
@@ -97,6 +99,7 @@ pub struct Sender<T: Transport, M: Mtp> {
     requests: Vec<Request>,
     request_rx: mpsc::UnboundedReceiver<Request>,
     next_ping: Instant,
+    ping_delay: Duration,
     reconnection_policy: &'static dyn ReconnectionPolicy,
 
     // Transport-level buffers and positions
@@ -180,6 +183,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
                 requests: vec![],
                 request_rx: rx,
                 next_ping: Instant::now() + PING_DELAY,
+                ping_delay: PING_DELAY,
                 reconnection_policy,
 
                 read_buffer: vec![0; MAXIMUM_DATA],
@@ -191,6 +195,19 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
         ))
     }
 
+    /// Change every how often keepalive pings are sent.
+    ///
+    /// Longer delays mean less traffic while idle, at the cost of detecting dead connections
+    /// later. The server is asked to close the connection if no ping arrives for slightly longer
+    /// than this delay.
+    ///
+    /// Pings are only sent while the sender is being stepped, so something has to keep calling
+    /// [`Sender::step`] during idle periods, or the connection will be closed by the server.
+    pub fn set_ping_delay(&mut self, ping_delay: Duration) {
+        self.ping_delay = ping_delay;
+        self.next_ping = Instant::now() + ping_delay;
+    }
+
     pub async fn invoke<R: RemoteCall>(&mut self, request: &R) -> Result<Vec<u8>, InvocationError> {
         let rx = self.enqueue_body(request.to_bytes());
         self.step_until_receive(rx).await
@@ -288,10 +305,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
                 self.on_net_write(n);
                 Vec::new()
             }),
-            Sel::Sleep => {
-                self.on_ping_timeout();
-                Ok(Vec::new())
-            }
+            Sel::Sleep => self.on_ping_timeout().map(|_| Vec::new()),
         };
 
         match res {
@@ -456,19 +470,36 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
     }
 
     /// Handle a ping timeout, meaning we need to enqueue a new ping request.
-    fn on_ping_timeout(&mut self) {
+    ///
+    /// If the timeout fired so late that the server has already closed the connection (e.g.
+    /// the process was suspended during a long idle period), the connection is considered dead
+    /// and an error is returned so that it can be re-established.
+    fn on_ping_timeout(&mut self) -> Result<(), ReadError> {
+        let overdue = Instant::now().saturating_duration_since(self.next_ping);
+        if overdue > PING_DISCONNECT_GRACE {
+            warn!(
+                "keepalive ping is {:?} overdue; connection is likely closed",
+                overdue
+            );
+            return Err(ReadError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "connection idle for longer than the keepalive disconnect delay",
+            )));
+        }
+
         let ping_id = generate_random_id();
         debug!("enqueueing keepalive ping {}", ping_id);
         drop(
             self.enqueue_body(
                 tl::functions::PingDelayDisconnect {
                     ping_id,
-                    disconnect_delay: NO_PING_DISCONNECT,
+                    disconnect_delay: (self.ping_delay + PING_DISCONNECT_GRACE).as_secs() as i32,
                 }
                 .to_bytes(),
             ),
         );
-        self.next_ping = Instant::now() + PING_DELAY;
+        self.next_ping = Instant::now() + self.ping_delay;
+        Ok(())
     }
 
     /// Handle errors that occured while performing I/O.
@@ -770,7 +801,8 @@ pub async fn generate_auth_key<T: Transport>(
                 .finish(auth_key),
             requests: sender.requests,
             request_rx: sender.request_rx,
-            next_ping: Instant::now() + PING_DELAY,
+            next_ping: Instant::now() + sender.ping_delay,
+            ping_delay: sender.ping_delay,
             read_buffer: sender.read_buffer,
             read_tail: sender.read_tail,
             write_buffer: sender.write_buffer,
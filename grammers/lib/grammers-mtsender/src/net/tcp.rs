@@ -100,10 +100,99 @@ impl NetStream {
                     ))
                 }
             }
+            "http" => {
+                let mut stream = TcpStream::connect(socks_addr).await?;
+                Self::http_connect(&mut stream, addr, username, password).await?;
+                Ok(NetStream::Tcp(stream))
+            }
             scheme => Err(io::Error::new(
                 ErrorKind::ConnectionAborted,
                 format!("proxy scheme not supported: {}", scheme),
             )),
         }
     }
+
+    /// Asks an HTTP proxy to open a tunnel to `addr` with a `CONNECT` request.
+    /// Once the proxy answers with a `2xx` status, the stream talks to `addr` directly.
+    #[cfg(feature = "proxy")]
+    async fn http_connect(
+        stream: &mut TcpStream,
+        addr: &std::net::SocketAddr,
+        username: &str,
+        password: &str,
+    ) -> Result<(), std::io::Error> {
+        use std::io::{self, ErrorKind};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Proxies answer with a few short headers, so anything longer is not a proxy.
+        const MAX_RESPONSE_LEN: usize = 8192;
+
+        let mut request = format!("CONNECT {addr} HTTP/1.1\r\nHost: {addr}\r\n");
+        if !username.is_empty() {
+            let credentials = base64_encode(format!("{username}:{password}").as_bytes());
+            request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read byte by byte so that nothing past the headers is consumed.
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_RESPONSE_LEN {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "proxy response headers are too long",
+                ));
+            }
+            response.push(stream.read_u8().await?);
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(status) if status.starts_with('2') => Ok(()),
+            _ => Err(io::Error::new(
+                ErrorKind::ConnectionRefused,
+                format!("proxy refused to connect: {status_line}"),
+            )),
+        }
+    }
+}
+
+/// Standard base64 with padding, as used by HTTP basic authentication.
+#[cfg(feature = "proxy")]
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(all(test, feature = "proxy"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"user:pass"), "dXNlcjpwYXNz");
+    }
 }
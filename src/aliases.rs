@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Таблица псевдонимов коллекций. Telegram иногда переименовывает коллекции или
+// меняет регистр slug, и без этой таблицы состояние и отчёты одной коллекции
+// расходились бы по разным именам.
+//
+// Формат файла - по строке на псевдоним, `#` - комментарий:
+//   plushpepe = PlushPepe
+//   OldName = NewName
+pub struct Aliases {
+    // Ключ - псевдоним в нижнем регистре, значение - каноническое имя.
+    table: HashMap<String, String>,
+}
+
+impl Aliases {
+    // Загружает таблицу; если файла нет - таблица пустая.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut table = HashMap::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((alias, canonical)) = line.split_once('=') else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{}:{}: ожидается «псевдоним = коллекция»",
+                        path.display(),
+                        n + 1
+                    ),
+                ));
+            };
+            let canonical = canonical.trim().to_string();
+            // Каноническое имя в другом регистре - тоже псевдоним.
+            table.insert(canonical.to_lowercase(), canonical.clone());
+            table.insert(alias.trim().to_lowercase(), canonical);
+        }
+
+        Ok(Self { table })
+    }
+
+    // Каноническое имя коллекции; неизвестные имена возвращаются как есть.
+    pub fn canonical(&self, collection: &str) -> String {
+        self.table
+            .get(&collection.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| collection.to_string())
+    }
+}
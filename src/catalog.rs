@@ -0,0 +1,126 @@
+use grammers_client::{Client, InvocationError};
+use parser_nft::fetch::catalog::{Catalog, collection_slug};
+use parser_nft::fetch::rate::RateLimiter;
+use parser_nft::report::json_string;
+
+// Один тип подарка из каталога.
+struct Entry {
+    title: String,
+    slug: String,
+    stars: i64,
+    upgrade_stars: Option<i64>,
+    resell_min_stars: Option<i64>,
+    total: Option<i32>,
+    remains: Option<i32>,
+    sold_out: bool,
+    // Сколько уже улучшено до уникальных; известно только с --upgraded.
+    upgraded: Option<i32>,
+}
+
+// Список всех типов подарков - чтобы узнать, какие коллекции вообще есть.
+// С `upgraded` для каждой улучшаемой коллекции запрашивается первый уникальный
+// подарок: в нём Telegram сообщает, сколько подарков уже улучшено.
+pub async fn catalog(
+    client: &Client,
+    cache: &Catalog,
+    upgraded: bool,
+    json: bool,
+    limiter: &mut RateLimiter,
+) -> Result<(), InvocationError> {
+    let Some(gifts) = cache.get(client).await? else {
+        return Ok(());
+    };
+    let mut entries = Vec::new();
+    for gift in gifts.gifts() {
+        // Без названия подарок нельзя найти по slug - это обычные, не коллекционные подарки.
+        let Some(title) = &gift.title else {
+            continue;
+        };
+        entries.push(Entry {
+            slug: collection_slug(title),
+            title: title.clone(),
+            stars: gift.stars,
+            upgrade_stars: gift.upgrade_stars,
+            resell_min_stars: gift.resell_min_stars,
+            total: gift.availability_total,
+            remains: gift.availability_remains,
+            sold_out: gift.sold_out,
+            upgraded: None,
+        });
+    }
+
+    if upgraded {
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| entry.upgrade_stars.is_some())
+        {
+            limiter.acquire().await;
+            entry.upgraded = match client
+                .get_unique_star_gift(&format!("{}-1", entry.slug))
+                .await
+            {
+                Ok(gift) => Some(gift.availability().issued),
+                Err(e) if e.is_slug_invalid() => Some(0),
+                Err(e) => return Err(e),
+            };
+        }
+    }
+
+    if json {
+        print_json(&entries);
+    } else {
+        print_table(&entries);
+    }
+    Ok(())
+}
+
+fn print_table(entries: &[Entry]) {
+    println!(
+        "{:<24} {:<22} {:>8} {:>9} {:>9} {:>8} {:>9} {:>9}",
+        "Название", "Slug", "Цена ⭐", "Улучш. ⭐", "Мин. ⭐", "Тираж", "Осталось", "Улучшено"
+    );
+    for entry in entries {
+        let remains = if entry.sold_out {
+            "0".to_string()
+        } else {
+            optional(entry.remains)
+        };
+        println!(
+            "{:<24} {:<22} {:>8} {:>9} {:>9} {:>8} {:>9} {:>9}",
+            entry.title,
+            entry.slug,
+            entry.stars,
+            optional(entry.upgrade_stars),
+            optional(entry.resell_min_stars),
+            optional(entry.total),
+            remains,
+            optional(entry.upgraded)
+        );
+    }
+}
+
+fn print_json(entries: &[Entry]) {
+    let number = |value: Option<i64>| value.map_or("null".to_string(), |v| v.to_string());
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "  {{\"title\": {}, \"slug\": {}, \"stars\": {}, \"upgrade_stars\": {}, \"resell_min_stars\": {}, \"availability_total\": {}, \"availability_remains\": {}, \"sold_out\": {}, \"upgraded\": {}}}",
+                json_string(&entry.title),
+                json_string(&entry.slug),
+                entry.stars,
+                number(entry.upgrade_stars),
+                number(entry.resell_min_stars),
+                number(entry.total.map(i64::from)),
+                number(entry.remains.map(i64::from)),
+                entry.sold_out,
+                number(entry.upgraded.map(i64::from))
+            )
+        })
+        .collect();
+    println!("[\n{}\n]", lines.join(",\n"));
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or("-".to_string(), |v| v.to_string())
+}
@@ -0,0 +1,134 @@
+use crate::Result;
+use crate::get::parse_slug;
+use grammers_client::Client;
+use parser_nft::error::GiftContext as _;
+use parser_nft::export::json::gift_json;
+use parser_nft::model::{Attribute, GiftRecord};
+
+// Загружает два подарка и печатает их атрибуты рядом - чтобы выбрать между двумя лотами.
+pub async fn compare(client: &Client, first: &str, second: &str, json: bool) -> Result<()> {
+    let mut gifts = Vec::new();
+    for input in [first, second] {
+        let slug = parse_slug(input);
+        let gift = client.get_unique_star_gift(slug).await.gift(slug)?;
+        gifts.push(GiftRecord::new(&gift, &gift.users));
+    }
+
+    if json {
+        print_json(&gifts);
+    } else {
+        print_table(&gifts);
+    }
+    Ok(())
+}
+
+fn print_table(gifts: &[GiftRecord]) {
+    let mut rows = vec![(
+        "Подарок",
+        gifts
+            .iter()
+            .map(|gift| format!("{} #{}", gift.title, gift.num))
+            .collect::<Vec<_>>(),
+    )];
+    let mut row = |label, value: fn(&GiftRecord) -> String| {
+        rows.push((label, gifts.iter().map(value).collect()));
+    };
+    row("Модель", |gift| attribute(&gift.attributes.model));
+    row("Фон", |gift| attribute(&gift.attributes.backdrop));
+    row("Узор", |gift| attribute(&gift.attributes.pattern));
+    row("Владелец", |gift| {
+        gift.owner
+            .display_name()
+            .unwrap_or_else(|| "скрыт".to_string())
+    });
+    row("Цена", |gift| {
+        gift.resale_price
+            .map_or_else(|| "не продаётся".to_string(), |stars| format!("{stars} ⭐"))
+    });
+    // Дату улучшения Telegram не присылает; известна только дата исходного подарка.
+    row("Исходный подарок", |gift| {
+        gift.provenance
+            .as_ref()
+            .map_or_else(|| "-".to_string(), |provenance| provenance.summary.clone())
+    });
+    row("Редкость", |gift| {
+        gift.rarity_score
+            .map_or_else(|| "-".to_string(), |score| format!("1 из {score:.0}"))
+    });
+
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| width(label))
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, values)| width(&values[0]))
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{}  {}  {}",
+        pad("", label_width),
+        pad(&gifts[0].slug, value_width),
+        gifts[1].slug
+    );
+    for (label, values) in &rows {
+        println!(
+            "{}  {}  {}",
+            pad(label, label_width),
+            pad(&values[0], value_width),
+            values[1]
+        );
+    }
+
+    let scores: Vec<Option<f64>> = gifts.iter().map(|gift| gift.rarity_score).collect();
+    if let [Some(first), Some(second)] = scores[..] {
+        let (rarer, ratio) = if first >= second {
+            (&gifts[0].slug, first / second)
+        } else {
+            (&gifts[1].slug, second / first)
+        };
+        println!();
+        if ratio < 1.01 {
+            println!("Сочетания атрибутов одинаково редкие");
+        } else {
+            println!("Реже сочетание атрибутов у {rarer}: в {ratio:.1} раза");
+        }
+    }
+}
+
+fn print_json(gifts: &[GiftRecord]) {
+    let items: Vec<String> = gifts
+        .iter()
+        .map(|gift| {
+            let score = gift
+                .rarity_score
+                .map_or_else(|| "null".to_string(), |score| format!("{score:.0}"));
+            format!(
+                "  {{\"gift\": {}, \"rarity_score\": {score}}}",
+                gift_json(gift, None)
+            )
+        })
+        .collect();
+    println!("[\n{}\n]", items.join(",\n"));
+}
+
+fn attribute(attribute: &Option<Attribute>) -> String {
+    match attribute {
+        Some(attribute) => format!(
+            "{} ({:.1}%)",
+            attribute.name,
+            attribute.rarity_permille as f64 / 10.0
+        ),
+        None => "-".to_string(),
+    }
+}
+
+// Ширина в символах, а не байтах: подписи по-русски.
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn pad(text: &str, columns: usize) -> String {
+    format!("{text}{}", " ".repeat(columns.saturating_sub(width(text))))
+}
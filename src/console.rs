@@ -0,0 +1,55 @@
+use crate::redact;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+// Куда писать сообщения о ходе парсинга. С --stream в stdout идут только
+// подарки в NDJSON, чтобы их можно было сразу передать другой программе,
+// а всё, что читает человек, уходит в stderr.
+pub fn status_to_stderr(enabled: bool) {
+    TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_stderr() -> bool {
+    TO_STDERR.load(Ordering::Relaxed)
+}
+
+// Как println!, но в stderr, если stdout занят потоком подарков, и без секретов:
+// в ошибках бывают адреса вебхуков и базы, путь к сессии.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        $crate::console::print_status(format_args!($($arg)*))
+    };
+}
+
+pub fn print_status(args: fmt::Arguments) {
+    let line = status_line(args);
+    if is_stderr() {
+        eprintln!("{line}");
+    } else {
+        println!("{line}");
+    }
+}
+
+fn status_line(args: fmt::Arguments) -> String {
+    redact::redact(&args.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_is_redacted() {
+        redact::add_secret("postgres://parser:pw@db.local/gifts");
+        assert_eq!(
+            status_line(format_args!(
+                "Не удалось записать подарки в базу данных: {}",
+                "error connecting to postgres://parser:pw@db.local/gifts"
+            )),
+            "Не удалось записать подарки в базу данных: error connecting to ***"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::Result;
+use crate::cli::Args;
+use grammers_client::Client;
+use parser_nft::error::GiftContext as _;
+use std::time::{Duration, Instant};
+
+// Оценка запуска без парсинга: узнаём размер коллекции по первому подарку
+// и прикидываем, сколько запросов и времени понадобится.
+pub async fn dry_run(client: &Client, collection: &str, args: &Args) -> Result<()> {
+    let slug = format!("{collection}-1");
+    let started = Instant::now();
+    let info = client.get_unique_star_gift(&slug).await.gift(&slug)?.raw;
+    let latency = started.elapsed();
+
+    // Сначала двоичный поиск последнего номера (удвоение, потом деление пополам -
+    // примерно по два запроса на каждый бит номера), потом по запросу на подарок.
+    let issued = info.availability_issued.max(0) as u32;
+    let discover_calls = 2 * (u32::BITS - issued.leading_zeros()) + 1;
+    let gift_calls = issued + discover_calls;
+    // Каталог и превью улучшения для палитр фонов.
+    let palette_calls = 2;
+    let total_calls = gift_calls + palette_calls;
+
+    println!("Коллекция: {} ({collection})", info.title);
+    println!(
+        "Выпущено подарков: {} из {}",
+        info.availability_issued, info.availability_total
+    );
+    println!("Запросов к Telegram: {total_calls}");
+    println!("  поиск последнего номера: {discover_calls}");
+    println!("  подарки: {issued}");
+    println!("  палитры фонов: {palette_calls}");
+    if args.artwork {
+        println!("  + по одному скачиванию превью на каждую модель");
+    }
+    if args.download_media.is_some() {
+        println!("  + скачивание стикеров модели и узора для каждой уникальной пары");
+    }
+
+    // Запросы идут последовательно, так что время - это задержка одного запроса,
+    // умноженная на их количество, но не быстрее, чем позволяет --rps. Подарки
+    // запрашиваются по одному на каждое соединение (--connections) сразу.
+    let sequential_calls = discover_calls + palette_calls;
+    let mut estimate = latency * (sequential_calls + issued.div_ceil(args.connections.into()));
+    if args.rps > 0.0 {
+        estimate = estimate.max(Duration::from_secs_f64(gift_calls as f64 / args.rps));
+    }
+    println!(
+        "Задержка одного запроса: {} мс, ожидаемое время: {}",
+        latency.as_millis(),
+        human_duration(estimate)
+    );
+
+    let pages = match args.page_size {
+        0 => 1,
+        size => (issued as usize).div_ceil(size).max(1),
+    };
+    match args.format.as_str() {
+        "html" => println!("Будет страниц HTML: {pages}"),
+        format => println!("Будет записан один файл {format}"),
+    }
+
+    Ok(())
+}
+
+fn human_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs} с"),
+        60..3600 => format!("{} мин {} с", secs / 60, secs % 60),
+        _ => format!("{} ч {} мин", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,99 @@
+use grammers_client::client::auth::AuthorizationError;
+use grammers_client::{InvocationError, SignInError, session};
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+// Ошибки парсера одним типом: по варианту видно, что не получилось, а в тексте -
+// подарок или файл, на котором это случилось.
+#[derive(Debug, Error)]
+pub enum ParserError {
+    // Чтение или запись файлов: снимков, состояния, сессии.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    // Не удалось записать отчёт или его часть в `path`.
+    #[error("не удалось записать {}: {source}", path.display())]
+    Export { path: PathBuf, source: io::Error },
+    // Вход в Telegram не удался: неверный код или пароль, нет терминала,
+    // отозванная сессия. Внутри - ошибка grammers или CLI.
+    #[error(transparent)]
+    Auth(Box<dyn Error + Send + Sync>),
+    // Запрос к Telegram, не связанный с одним подарком.
+    #[error(transparent)]
+    Rpc(#[from] InvocationError),
+    // Запрос подарка или коллекции `gift`: «PlushPepe-12» или «PlushPepe».
+    #[error("{gift}: {source}")]
+    Gift {
+        gift: String,
+        source: InvocationError,
+    },
+    // В коллекции нет ни одного подарка - скорее всего, опечатка в названии.
+    #[error("в коллекции {0} нет ни одного подарка, проверьте название")]
+    UnknownCollection(String),
+    // Владелец не найден: «@username» или id (по id находятся только уже
+    // встречавшиеся пользователи).
+    #[error("пользователь или канал {0} не найден")]
+    UnknownPeer(String),
+    // Запрос к другому HTTP-сервису: вебхуку, TON API.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    // Неверные аргументы или файл настроек.
+    #[error("{0}")]
+    Config(String),
+    // Запуск закончился, но `errors` ошибок по коллекциям оставили отчёты неполными.
+    #[error("отчёты неполные, ошибок: {errors} (подробности в manifest.json)")]
+    Partial { errors: usize },
+    // Ни одного подарка не получено из `from`: сообщений чата, файлов. Если
+    // подарки были, но не загрузились, `source` - ошибка на последнем из них.
+    #[error("не получен ни один подарок из {from}")]
+    NoGifts {
+        from: String,
+        source: Option<Box<ParserError>>,
+    },
+    // Источник отдаёт подарок коллекции под любым номером: последний не найти.
+    #[error("{0}: подарок есть под любым номером, последний не найти")]
+    EndlessCollection(String),
+}
+
+pub type Result<T> = std::result::Result<T, ParserError>;
+
+// Отказ в авторизации с обрывом связи внутри - это обрыв связи, а не неудачный вход.
+impl From<AuthorizationError> for ParserError {
+    fn from(e: AuthorizationError) -> Self {
+        match e {
+            AuthorizationError::Invoke(e) => ParserError::Rpc(e),
+            e => ParserError::Auth(Box::new(e)),
+        }
+    }
+}
+
+// Сохранённую сессию не прочитать - войти с ней не получится.
+impl From<session::Error> for ParserError {
+    fn from(e: session::Error) -> Self {
+        ParserError::Auth(Box::new(e))
+    }
+}
+
+impl From<SignInError> for ParserError {
+    fn from(e: SignInError) -> Self {
+        match e {
+            SignInError::Other(e) => ParserError::Rpc(e),
+            e => ParserError::Auth(Box::new(e)),
+        }
+    }
+}
+
+// Дописывает к ошибке запроса подарок, на котором она случилась.
+pub trait GiftContext<T> {
+    fn gift(self, gift: &str) -> Result<T>;
+}
+
+impl<T> GiftContext<T> for std::result::Result<T, InvocationError> {
+    fn gift(self, gift: &str) -> Result<T> {
+        self.map_err(|source| ParserError::Gift {
+            gift: gift.to_string(),
+            source,
+        })
+    }
+}
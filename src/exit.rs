@@ -0,0 +1,72 @@
+use grammers_client::{InvocationError, RpcErrorKind};
+use parser_nft::error::ParserError;
+use std::io;
+
+// Коды выхода rustfind, чтобы скрипты и CI могли понять, что пошло не так.
+// Код 2 занят clap: им заканчивается запуск с неверными аргументами.
+pub const OTHER: u8 = 1;
+// Не удалось войти: неверный код или пароль, нет терминала для ввода,
+// Telegram отозвал сессию и войти заново нельзя.
+pub const AUTH: u8 = 3;
+// Telegram велел подождать дольше, чем --flood-sleep, и ничего не записано.
+pub const FLOOD: u8 = 4;
+// Нет связи с Telegram, и ничего не записано.
+pub const NETWORK: u8 = 5;
+// Такого подарка или коллекции нет.
+pub const INVALID_SLUG: u8 = 6;
+// Отчёты записаны, но неполные: парсинг остановился на ошибке, часть подарков
+// или коллекций не получена. Подробности - в manifest.json.
+pub const PARTIAL: u8 = 7;
+
+// Справка по кодам выхода для --help.
+pub const HELP: &str = "Коды выхода:
+  0  всё получено
+  1  другая ошибка
+  2  неверные аргументы
+  3  не удалось войти в Telegram
+  4  flood-wait дольше --flood-sleep
+  5  нет связи с Telegram
+  6  такого подарка или коллекции нет
+  7  отчёты записаны, но неполные (см. manifest.json)";
+
+// Код выхода для ошибки, которой закончился запуск.
+pub fn code(e: &ParserError) -> u8 {
+    match e {
+        ParserError::Auth(_) => AUTH,
+        ParserError::Rpc(e) | ParserError::Gift { source: e, .. } => invocation_code(e),
+        ParserError::UnknownCollection(_) => INVALID_SLUG,
+        ParserError::Partial { .. } => PARTIAL,
+        ParserError::NoGifts {
+            source: Some(e), ..
+        } => code(e),
+        ParserError::Io(e) if is_network(e.kind()) => NETWORK,
+        ParserError::Http(e) if e.is_connect() || e.is_timeout() => NETWORK,
+        _ => OTHER,
+    }
+}
+
+fn invocation_code(e: &InvocationError) -> u8 {
+    match e.kind() {
+        _ if e.is_connection_lost() => NETWORK,
+        _ if e.is_slug_invalid() => INVALID_SLUG,
+        Some(RpcErrorKind::FloodWait) => FLOOD,
+        Some(
+            RpcErrorKind::Unauthorized | RpcErrorKind::PhoneCode | RpcErrorKind::PasswordRequired,
+        ) => AUTH,
+        _ => OTHER,
+    }
+}
+
+fn is_network(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::HostUnreachable
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::NetworkDown
+    )
+}
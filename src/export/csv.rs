@@ -0,0 +1,130 @@
+use crate::export::{Exporter, Report};
+use crate::model::{Attribute, GiftRecord};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::{self, Write};
+
+// Те же поля, что у подарка в JSON, но у модели, фона и узора редкость - отдельный столбец.
+const HEADER: [&str; 24] = [
+    "num",
+    "slug",
+    "title",
+    "link",
+    "model",
+    "model_rarity_permille",
+    "backdrop",
+    "backdrop_rarity_permille",
+    "pattern",
+    "pattern_rarity_permille",
+    "rarity_score",
+    "owner_kind",
+    "owner_id",
+    "owner_name",
+    "owner_username",
+    "resale_price",
+    "floor_price",
+    "gift_address",
+    "owner_address",
+    "original",
+    "original_message",
+    "transfer_stars",
+    "transfer_after",
+    "export_after",
+];
+
+// Таблица подарков в CSV (RFC 4180) - для Excel, Google Таблиц и pandas.
+// Только подарки, по строке на каждый: сведения о запуске сюда не помещаются.
+pub struct Csv<'a> {
+    report: &'a Report<'a>,
+}
+
+impl<'a> Csv<'a> {
+    pub fn new(report: &'a Report<'a>) -> Self {
+        Self { report }
+    }
+}
+
+impl Exporter for Csv<'_> {
+    fn file_name(&self) -> String {
+        format!("{}.csv", self.report.name)
+    }
+
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()> {
+        writer.write_all(row(&HEADER).as_bytes())?;
+        for gift in gifts {
+            let owner = &gift.owner;
+            let provenance = gift.provenance.as_ref();
+            let transfer = gift.transfer.as_ref();
+            let (model, model_rarity) = attribute(&gift.attributes.model);
+            let (backdrop, backdrop_rarity) = attribute(&gift.attributes.backdrop);
+            let (pattern, pattern_rarity) = attribute(&gift.attributes.pattern);
+            let cells = [
+                gift.num.to_string(),
+                gift.slug.clone(),
+                gift.title.clone(),
+                gift.link(),
+                model,
+                model_rarity,
+                backdrop,
+                backdrop_rarity,
+                pattern,
+                pattern_rarity,
+                gift.rarity_score
+                    .map(|score| format!("{score:.0}"))
+                    .unwrap_or_default(),
+                owner.kind().to_string(),
+                number(owner.id()),
+                owner.display_name().unwrap_or_default(),
+                owner.username().unwrap_or_default().to_string(),
+                number(gift.resale_price),
+                number(self.report.enrichment.floor_price(gift)),
+                gift.gift_address.clone().unwrap_or_default(),
+                gift.owner_address.clone().unwrap_or_default(),
+                provenance
+                    .map(|provenance| provenance.summary.clone())
+                    .unwrap_or_default(),
+                provenance
+                    .and_then(|provenance| provenance.message.clone())
+                    .unwrap_or_default(),
+                number(transfer.and_then(|transfer| transfer.stars)),
+                time(transfer.and_then(|transfer| transfer.transfer_after)),
+                time(transfer.and_then(|transfer| transfer.export_after)),
+            ];
+            writer.write_all(row(&cells).as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+fn attribute(attribute: &Option<Attribute>) -> (String, String) {
+    match attribute {
+        Some(attribute) => (
+            attribute.name.clone(),
+            attribute.rarity_permille.to_string(),
+        ),
+        None => (String::new(), String::new()),
+    }
+}
+
+fn number(value: Option<i64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn time(value: Option<DateTime<Utc>>) -> String {
+    value
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+fn row<T: AsRef<str>>(cells: &[T]) -> String {
+    let cells: Vec<String> = cells.iter().map(|cell| field(cell.as_ref())).collect();
+    format!("{}\r\n", cells.join(","))
+}
+
+// Поле в кавычках, если в нём есть запятая, кавычка или перевод строки.
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,1160 @@
+use crate::export::lang::Labels;
+use crate::export::lang::Lang;
+use crate::export::{Enrichment, Exporter, Output, Report, SplitBy, Theme};
+use crate::model::palette::Palette;
+use crate::model::provenance::Provenance;
+use crate::model::ton::explorer_url;
+use crate::model::{Attribute, GiftRecord, Transfer};
+use crate::report::RunInfo;
+use crate::report::ownership::Ownership;
+use crate::report::prices::{PriceRow, Prices};
+use crate::report::quality::Quality;
+use crate::report::stats::Stats;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+// Шаблон сделан с помощью ChatGPT - автор не умеет.
+const STYLE: &str = "
+  body {
+    font-family: \"Segoe UI\", Tahoma, Geneva, Verdana, sans-serif;
+    background: #f9fafb;
+    color: #2c3e50;
+    margin: 0;
+    padding: 20px;
+  }
+  .gifts-container {
+    max-width: 900px;
+    margin: 0 auto;
+  }
+  .gift-item {
+    background: white;
+    border-radius: 8px;
+    box-shadow: 0 2px 6px rgb(0 0 0 / 0.1);
+    padding: 15px 20px;
+    margin-bottom: 15px;
+    display: flex;
+    flex-wrap: wrap;
+    gap: 12px;
+    align-items: center;
+  }
+  .gift-item a {
+    color: #2980b9;
+    text-decoration: none;
+    font-weight: 600;
+  }
+  .gift-item a:hover {
+    text-decoration: underline;
+  }
+  .gift-model, .gift-backdrop {
+    background: #ecf0f1;
+    border-radius: 5px;
+    padding: 8px 12px;
+    font-size: 14px;
+    color: #34495e;
+    flex: 1 1 200px;
+  }
+  .gift-username, .gift-name {
+    flex: 0 0 auto;
+  }
+  .gift-floor, .gift-rarity, .gift-transfer {
+    flex: 0 0 auto;
+    font-size: 13px;
+    color: #7f8c8d;
+  }
+  .gift-ton {
+    flex: 1 1 100%;
+    font-size: 13px;
+  }
+  .gift-original {
+    flex: 1 1 100%;
+    font-size: 13px;
+    color: #7f8c8d;
+  }
+  .gift-price {
+    flex: 0 0 auto;
+    font-weight: 600;
+    color: #27ae60;
+  }
+  .data-quality {
+    margin-top: 30px;
+    padding: 10px 20px;
+    border-radius: 8px;
+    background: #ecf0f1;
+    font-size: 14px;
+  }
+  .data-quality h2 {
+    font-size: 16px;
+  }
+  .data-quality.incomplete {
+    background: #fdecea;
+    color: #c0392b;
+  }
+  .run-info {
+    margin-top: 30px;
+    font-size: 12px;
+    color: #7f8c8d;
+  }
+  .run-info dl {
+    display: grid;
+    grid-template-columns: max-content auto;
+    gap: 2px 12px;
+  }
+  .run-info dd {
+    margin: 0;
+    font-family: monospace;
+  }
+  .gift-art {
+    width: 96px;
+    height: 96px;
+    object-fit: contain;
+    align-self: center;
+  }
+  .gift-art-missing {
+    display: flex;
+    align-items: center;
+    justify-content: center;
+    border-radius: 8px;
+    background: #eee;
+    color: #888;
+    font-size: 12px;
+  }
+  .gift-palette {
+    display: flex;
+    gap: 6px;
+    font-size: 12px;
+    font-family: monospace;
+  }
+  .gift-palette span {
+    border-radius: 4px;
+    padding: 4px 6px;
+    border: 1px solid rgb(0 0 0 / 0.1);
+  }
+  .pages-nav {
+    display: flex;
+    justify-content: space-between;
+    margin: 0 0 15px;
+  }
+  .pages-nav a, .pages-list a {
+    color: #2980b9;
+    text-decoration: none;
+    font-weight: 600;
+  }
+  .pages-list li {
+    margin-bottom: 8px;
+  }
+  .gift-filters {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 10px;
+    margin-bottom: 15px;
+  }
+  .gift-filters input, .gift-filters select {
+    font: inherit;
+    padding: 8px 10px;
+    border: 1px solid #d0d7de;
+    border-radius: 5px;
+  }
+  .gift-filters input {
+    flex: 1 1 250px;
+  }
+";
+
+// Тёмная тема поверх STYLE: только цвета, раскладка та же.
+const DARK_STYLE: &str = "
+  :root {
+    color-scheme: dark;
+  }
+  body {
+    background: #0d1117;
+    color: #c9d1d9;
+  }
+  .gift-item {
+    background: #161b22;
+    box-shadow: 0 2px 6px rgb(0 0 0 / 0.4);
+  }
+  .gift-item a, .pages-nav a, .pages-list a {
+    color: #58a6ff;
+  }
+  .gift-model, .gift-backdrop {
+    background: #21262d;
+    color: #c9d1d9;
+  }
+  .gift-floor, .gift-rarity, .gift-transfer, .gift-original, .run-info {
+    color: #8b949e;
+  }
+  .gift-price {
+    color: #3fb950;
+  }
+  .data-quality {
+    background: #21262d;
+  }
+  .data-quality.incomplete {
+    background: #3d1d1d;
+    color: #ff7b72;
+  }
+  .gift-art-missing {
+    background: #21262d;
+    color: #8b949e;
+  }
+  .gift-filters input, .gift-filters select {
+    background: #0d1117;
+    color: #c9d1d9;
+    border-color: #30363d;
+  }
+";
+
+// Таблица коллекций на index.html.
+const INDEX_STYLE: &str = "
+  .collections {
+    width: 100%;
+    border-collapse: collapse;
+    font-size: 14px;
+  }
+  .collections th, .collections td {
+    padding: 8px 10px;
+    border-bottom: 1px solid rgb(127 127 127 / 0.25);
+    text-align: left;
+  }
+  .collections td.number {
+    text-align: right;
+  }
+  .collections a {
+    color: inherit;
+    font-weight: 600;
+  }
+  .collections .incomplete {
+    color: #c0392b;
+    font-size: 12px;
+  }
+";
+
+// Таблица атрибутов на странице подарка.
+const DETAILS_STYLE: &str = "
+  .gift-details {
+    margin-top: 20px;
+    font-size: 14px;
+  }
+  .gift-details h2 {
+    font-size: 16px;
+  }
+  .gift-details th {
+    padding: 4px 12px 4px 0;
+    text-align: left;
+    font-weight: 600;
+    vertical-align: top;
+  }
+  .gift-details td {
+    font-family: monospace;
+    overflow-wrap: anywhere;
+  }
+";
+
+// Стили страницы для темы: светлая - как есть, тёмная - с DARK_STYLE поверх,
+// автоматическая - с DARK_STYLE, когда браузер просит тёмную схему.
+fn style(theme: Theme) -> String {
+    match theme {
+        Theme::Light => STYLE.to_string(),
+        Theme::Dark => format!("{STYLE}{DARK_STYLE}"),
+        Theme::Auto => format!("{STYLE}@media (prefers-color-scheme: dark) {{{DARK_STYLE}}}\n"),
+    }
+}
+
+// Поиск и фильтры работают прямо в браузере по data-атрибутам карточек.
+fn filters(labels: &Labels) -> String {
+    format!(
+        r#"<div class="gift-filters">
+  <input id="gift-search" type="search" placeholder="{}" />
+  <select id="gift-model-filter"><option value="">{}</option></select>
+  <select id="gift-backdrop-filter"><option value="">{}</option></select>
+</div>
+{FILTERS_SCRIPT}"#,
+        labels.search, labels.all_models, labels.all_backdrops
+    )
+}
+
+const FILTERS_SCRIPT: &str = r#"<script>
+document.addEventListener("DOMContentLoaded", () => {
+  const cards = Array.from(document.querySelectorAll(".gift-item"));
+  const search = document.getElementById("gift-search");
+  const model = document.getElementById("gift-model-filter");
+  const backdrop = document.getElementById("gift-backdrop-filter");
+
+  const fill = (select, key) => {
+    const values = [...new Set(cards.map((card) => card.dataset[key]))].sort();
+    for (const value of values) {
+      select.add(new Option(value, value));
+    }
+  };
+  fill(model, "model");
+  fill(backdrop, "backdrop");
+
+  const apply = () => {
+    const query = search.value.trim().toLowerCase();
+    for (const card of cards) {
+      const text = [card.dataset.slug, card.dataset.model, card.dataset.backdrop, card.dataset.owner]
+        .join(" ")
+        .toLowerCase();
+      const visible = (!query || text.includes(query))
+        && (!model.value || card.dataset.model === model.value)
+        && (!backdrop.value || card.dataset.backdrop === backdrop.value);
+      card.style.display = visible ? "" : "none";
+    }
+  };
+  search.addEventListener("input", apply);
+  model.addEventListener("change", apply);
+  backdrop.addEventListener("change", apply);
+});
+</script>
+"#;
+
+// Красивый HTML-отчёт: карточки подарков с поиском и фильтрами.
+pub struct Html<'a> {
+    report: &'a Report<'a>,
+}
+
+impl<'a> Html<'a> {
+    pub fn new(report: &'a Report<'a>) -> Self {
+        Self { report }
+    }
+}
+
+impl Exporter for Html<'_> {
+    fn file_name(&self) -> String {
+        format!("{}.html", self.report.name)
+    }
+
+    // Одним потоком отчёт не режется на страницы: все подарки на одной.
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()> {
+        let pages = Pages::new(self.report, 0);
+        let gifts: Vec<&GiftRecord> = gifts.iter().collect();
+        pages.page(writer, "Telegram Gifts", &pages.filters, &gifts, "")
+    }
+
+    fn write_files(&self, gifts: &[GiftRecord], output: &mut dyn Output) -> io::Result<()> {
+        write_html(gifts, self.report, output)
+    }
+}
+
+// Пишет все страницы отчёта в `output`, каждую - по мере готовности карточек.
+// Если подарков больше, чем `page_size`, отчёт режется на parsed_001.html, parsed_002.html, …
+// а в parsed.html кладётся оглавление. `page_size == 0` отключает разбиение.
+// С `split_by` каждая модель (или фон) получает свой файл, а parsed.html ссылается на все.
+// Вместо «parsed» во всех именах стоит `report.name`.
+pub fn write_html(
+    gifts: &[GiftRecord],
+    report: &Report,
+    output: &mut dyn Output,
+) -> io::Result<()> {
+    let pages = Pages::new(report, report.page_size);
+    let labels = pages.labels;
+    let gifts: Vec<&GiftRecord> = gifts.iter().collect();
+    let name = report.name;
+
+    let Some(split_by) = report.split_by else {
+        return pages.write(output, name, "Telegram Gifts", &gifts, None);
+    };
+
+    let (key, label): (fn(&GiftRecord) -> &str, _) = match split_by {
+        SplitBy::Model => (card_model, labels.model),
+        SplitBy::Backdrop => (card_backdrop, labels.backdrop),
+    };
+    let mut groups: BTreeMap<&str, Vec<&GiftRecord>> = BTreeMap::new();
+    for gift in gifts {
+        groups.entry(key(gift)).or_default().push(gift);
+    }
+
+    let mut index = String::from("<ul class=\"pages-list\">\n");
+    for (value, group) in groups {
+        let stem = format!("{name}_{}_{}", split_by.as_str(), file_safe(value));
+        let title = format!("Telegram Gifts — {label}: {value}");
+        pages.write(output, &stem, &title, &group, Some(name))?;
+        index.push_str(&format!(
+            "  <li><a href=\"{stem}.html\">{label}: {} ({} {})</a></li>\n",
+            escape(value),
+            group.len(),
+            labels.pieces
+        ));
+    }
+    index.push_str("</ul>\n");
+
+    let mut writer = output.create(&format!("{name}.html"))?;
+    pages.page(&mut writer, "Telegram Gifts", &index, &[], "")
+}
+
+// Строка коллекции на index.html - сводка по её последнему отчёту.
+pub struct IndexEntry {
+    pub collection: String,
+    // Путь к отчёту коллекции относительно index.html.
+    pub link: String,
+    pub stats: Stats,
+    // Самый редкий подарок и его оценка редкости.
+    pub rarest: Option<(String, f64)>,
+    pub complete: bool,
+}
+
+impl IndexEntry {
+    pub fn new(collection: &str, link: String, gifts: &[GiftRecord], quality: &Quality) -> Self {
+        let rarest = gifts
+            .iter()
+            .filter_map(|gift| Some((gift.slug.clone(), gift.rarity_score?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        Self {
+            collection: collection.to_string(),
+            link,
+            stats: Stats::new(gifts),
+            rarest,
+            complete: quality.is_complete(),
+        }
+    }
+}
+
+// Оглавление по коллекциям (index.html): ссылка на отчёт каждой, сколько в ней
+// подарков, владельцев и лотов и самый редкий подарок. Перезаписывается после
+// каждого запуска, так что папка с отчётами работает как статическая панель.
+pub fn write_index(
+    entries: &[&IndexEntry],
+    lang: Lang,
+    theme: Theme,
+    css: Option<&str>,
+    run: &RunInfo,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let labels = lang.labels();
+    write!(
+        writer,
+        "<!DOCTYPE html>
+<html lang=\"{}\">
+<head>
+<meta charset=\"UTF-8\" />
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />
+<title>Telegram Gifts — {}</title>
+<style>{}{INDEX_STYLE}</style>
+{}</head>
+<body>
+
+<div class=\"gifts-container\">
+<table class=\"collections\">
+<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>
+",
+        labels.code,
+        labels.collections,
+        style(theme),
+        css.map(|css| format!("<style>{css}</style>\n"))
+            .unwrap_or_default(),
+        labels.collection,
+        labels.gifts_count,
+        labels.holders,
+        labels.for_sale,
+        labels.rarest
+    )?;
+    for entry in entries {
+        let incomplete = if entry.complete {
+            String::new()
+        } else {
+            format!(" <span class=\"incomplete\">{}</span>", labels.incomplete)
+        };
+        let rarest = entry
+            .rarest
+            .as_ref()
+            .map(|(slug, score)| {
+                format!(
+                    "<a href=\"https://t.me/nft/{0}\" target=\"_blank\" rel=\"noopener noreferrer\">{0}</a> ({1} {score:.0})",
+                    escape(slug),
+                    labels.one_in
+                )
+            })
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "<tr><td><a href=\"{}\">{}</a>{incomplete}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td>{rarest}</td></tr>",
+            escape(&entry.link),
+            escape(&entry.collection),
+            entry.stats.count,
+            entry.stats.holders,
+            entry.stats.listed
+        )?;
+    }
+    write!(
+        writer,
+        "</table>\n{}</div>\n</body>\n</html>",
+        footer(run, labels)
+    )?;
+    writer.flush()
+}
+
+// Цены перепродажи коллекции (--prices): строка по всей коллекции и таблицы
+// по моделям и фонам - сколько лотов и почём в звёздах. Таблицы - те же, что на index.html.
+pub fn write_prices(
+    prices: &Prices,
+    lang: Lang,
+    theme: Theme,
+    css: Option<&str>,
+    run: &RunInfo,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let labels = lang.labels();
+    let header = format!(
+        "<tr><th></th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+        labels.for_sale, labels.min_price, labels.median_price, labels.max_price
+    );
+    let table = |title: &str, rows: &[PriceRow]| {
+        let rows: String = rows.iter().map(price_row).collect();
+        format!("<h2>{title}</h2>\n<table class=\"collections\">\n{header}\n{rows}</table>\n")
+    };
+    write!(
+        writer,
+        "<!DOCTYPE html>
+<html lang=\"{}\">
+<head>
+<meta charset=\"UTF-8\" />
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />
+<title>{} — {}</title>
+<style>{}{INDEX_STYLE}</style>
+{}</head>
+<body>
+
+<div class=\"gifts-container\">
+<table class=\"collections\">
+{header}
+{}</table>
+{}{}{}</div>
+</body>
+</html>",
+        labels.code,
+        escape(&prices.collection),
+        labels.prices,
+        style(theme),
+        css.map(|css| format!("<style>{css}</style>\n"))
+            .unwrap_or_default(),
+        price_row(&prices.total),
+        table(labels.model, &prices.models),
+        table(labels.backdrop, &prices.backdrops),
+        footer(run, labels)
+    )?;
+    writer.flush()
+}
+
+fn price_row(row: &PriceRow) -> String {
+    let price = |price: Option<i64>| price.map(|stars| format!("{stars} ⭐")).unwrap_or_default();
+    format!(
+        "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>\n",
+        escape(&row.name),
+        row.listed,
+        price(row.min),
+        price(row.median),
+        price(row.max)
+    )
+}
+
+// Страница одного подарка «<slug>.html» рядом с отчётом коллекции (--site):
+// его карточка со ссылкой в Telegram, все атрибуты, исходные данные, история
+// владельцев по снимкам (`owners`, от старых к новым) и ссылка обратно на коллекцию.
+pub fn write_gift_page(
+    gift: &GiftRecord,
+    owners: &[Ownership],
+    report: &Report,
+    output: &mut dyn Output,
+) -> io::Result<()> {
+    let mut pages = Pages::new(report, 0);
+    pages.detail_pages = false;
+    pages.style.push_str(DETAILS_STYLE);
+    let labels = pages.labels;
+    let back = format!(
+        "<nav class=\"pages-nav\"><a href=\"{}.html\">← {}: {}</a></nav>\n",
+        report.name,
+        labels.back_to_collection,
+        escape(&gift.title)
+    );
+    let details = gift_details(gift, owners, labels);
+    let title = format!("{} #{}", gift.title, gift.num);
+    let mut writer = output.create(&format!("{}.html", gift.slug))?;
+    pages.page(&mut writer, &title, &back, &[gift], &details)
+}
+
+// Всё, что известно о подарке, таблицей - подробнее, чем в карточке.
+// «Можно передать после 2025-07-01 за 25 ⭐», а если известна дата вывода в TON -
+// ещё и она.
+fn transfer_terms(transfer: &Transfer, labels: &Labels) -> String {
+    let mut terms = vec![labels.transferable.to_string()];
+    if let Some(after) = transfer.transfer_after {
+        terms.push(format!("{} {}", labels.after, after.format("%Y-%m-%d")));
+    }
+    terms.push(match transfer.stars {
+        Some(stars) => format!("{} {stars} ⭐", labels.for_stars),
+        None => labels.free.to_string(),
+    });
+    let mut text = terms.join(" ");
+    if let Some(after) = transfer.export_after {
+        text.push_str(&format!(
+            " · {} {}",
+            labels.export_after,
+            after.format("%Y-%m-%d")
+        ));
+    }
+    text
+}
+
+// Имя владельца (уже экранированное) со ссылкой на его профиль, если она есть.
+fn owner_html(owner: &str, link: Option<&str>) -> String {
+    match link {
+        Some(link) => format!(
+            r#"<a href="{}" target="_blank" rel="noopener noreferrer">{owner}</a>"#,
+            escape(link)
+        ),
+        None => owner.to_string(),
+    }
+}
+
+fn gift_details(gift: &GiftRecord, owners: &[Ownership], labels: &Labels) -> String {
+    let attribute = |attribute: &Option<Attribute>| match attribute {
+        Some(attribute) => format!(
+            "{} ({}‰)",
+            escape(&attribute.name),
+            attribute.rarity_permille
+        ),
+        None => String::new(),
+    };
+    let attributes = &gift.attributes;
+    let mut rows = vec![
+        (labels.number, gift.num.to_string()),
+        (labels.model, attribute(&attributes.model)),
+        (labels.backdrop, attribute(&attributes.backdrop)),
+        (labels.pattern, attribute(&attributes.pattern)),
+    ];
+    if let Some(palette) = &attributes.palette {
+        rows.push((
+            labels.colors,
+            [
+                &palette.center_color,
+                &palette.edge_color,
+                &palette.pattern_color,
+                &palette.text_color,
+            ]
+            .map(|color| escape(color))
+            .join(" · "),
+        ));
+    }
+    if let Some(score) = gift.rarity_score {
+        rows.push((labels.rarity, format!("{} {score:.0}", labels.one_in)));
+    }
+    if let Some(provenance) = &gift.provenance {
+        rows.push((labels.original, escape(&provenance.summary)));
+        if let Some(message) = &provenance.message {
+            rows.push((labels.message, escape(message)));
+        }
+    }
+    if let Some(owner) = gift.owner.display_name() {
+        rows.push((
+            labels.owner,
+            owner_html(&escape(&owner), gift.owner.link().as_deref()),
+        ));
+    }
+    if let Some(address) = &gift.gift_address {
+        rows.push((labels.ton_gift, escape(address)));
+    }
+    if let Some(address) = &gift.owner_address {
+        rows.push((labels.ton_owner, escape(address)));
+    }
+    let rows: String = rows
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(label, value)| format!("<tr><th>{label}</th><td>{value}</td></tr>\n"))
+        .collect();
+
+    let history = if owners.is_empty() {
+        String::new()
+    } else {
+        let items: String = owners
+            .iter()
+            .map(|ownership| {
+                let first = ownership.first_seen.format("%Y-%m-%d").to_string();
+                let last = ownership.last_seen.format("%Y-%m-%d").to_string();
+                // Владелец из одного снимка - одна дата, иначе срок, когда он владел подарком.
+                let seen = if first == last {
+                    first
+                } else {
+                    format!("{first} – {last}")
+                };
+                format!(
+                    "<li>{seen} — {}</li>\n",
+                    escape(
+                        &ownership
+                            .owner
+                            .display_name()
+                            .unwrap_or_else(|| labels.hidden.to_string())
+                    )
+                )
+            })
+            .collect();
+        format!("<h2>{}</h2>\n<ul>\n{items}</ul>\n", labels.owner_history)
+    };
+
+    format!(
+        "<section class=\"gift-details\">\n<h2>{}</h2>\n<table>\n{rows}</table>\n{history}</section>\n",
+        labels.attributes
+    )
+}
+
+// Общее для всех страниц одного отчёта: обогащение карточек, подписи, размер страницы,
+// стили, фильтры и подвал.
+struct Pages<'a> {
+    enrichment: &'a Enrichment,
+    labels: &'static Labels,
+    page_size: usize,
+    style: String,
+    // <style> со стилями из --css или пустая строка.
+    custom_style: String,
+    filters: String,
+    footer: String,
+    // Карточки ведут на страницы подарков, а не сразу в Telegram.
+    detail_pages: bool,
+}
+
+impl<'a> Pages<'a> {
+    fn new(report: &'a Report, page_size: usize) -> Self {
+        let labels = report.lang.labels();
+        Self {
+            enrichment: report.enrichment,
+            labels,
+            page_size,
+            style: style(report.theme),
+            // Свои стили идут после встроенных, чтобы перекрывать их.
+            custom_style: report
+                .css
+                .map(|css| format!("<style>{css}</style>\n"))
+                .unwrap_or_default(),
+            filters: filters(labels),
+            footer: format!(
+                "{}{}",
+                quality_section(report.quality, labels),
+                footer(report.run, labels)
+            ),
+            detail_pages: report.detail_pages,
+        }
+    }
+
+    // Раскладывает подарки в `{stem}.html`, а если они не влезают в одну страницу - в
+    // `{stem}_001.html`, `{stem}_002.html`, … с оглавлением в `{stem}.html`.
+    // `parent` - файл верхнего оглавления, на который нужно сослаться (без расширения).
+    fn write(
+        &self,
+        output: &mut dyn Output,
+        stem: &str,
+        title: &str,
+        gifts: &[&GiftRecord],
+        parent: Option<&str>,
+    ) -> io::Result<()> {
+        let back = match parent {
+            Some(parent) => format!(
+                "<nav class=\"pages-nav\"><a href=\"{parent}.html\">← {}</a></nav>\n",
+                self.labels.back_to_groups
+            ),
+            None => String::new(),
+        };
+
+        let page_size = self.page_size;
+        if page_size == 0 || gifts.len() <= page_size {
+            let mut writer = output.create(&format!("{stem}.html"))?;
+            return self.page(
+                &mut writer,
+                title,
+                &format!("{back}{}", self.filters),
+                gifts,
+                "",
+            );
+        }
+
+        let chunks: Vec<&[&GiftRecord]> = gifts.chunks(page_size).collect();
+        let mut index = format!("{back}<ul class=\"pages-list\">\n");
+        for (i, chunk) in chunks.iter().enumerate() {
+            let first = i * page_size + 1;
+            let last = first + chunk.len() - 1;
+            index.push_str(&format!(
+                "  <li><a href=\"{}\">{} {} ({} {}–{})</a></li>\n",
+                page_file_name(stem, i),
+                self.labels.page,
+                i + 1,
+                self.labels.gifts,
+                first,
+                last
+            ));
+
+            let nav = pages_nav(stem, i, chunks.len(), self.labels);
+            let title = format!(
+                "{title} — {} {} {} {}",
+                self.labels.page.to_lowercase(),
+                i + 1,
+                self.labels.page_of,
+                chunks.len()
+            );
+            let mut writer = output.create(&page_file_name(stem, i))?;
+            let before = format!("{}{nav}", self.filters);
+            self.page(&mut writer, &title, &before, chunk, &nav)?;
+        }
+        index.push_str("</ul>\n");
+
+        let mut writer = output.create(&format!("{stem}.html"))?;
+        self.page(&mut writer, title, &index, &[], "")
+    }
+
+    // Одна страница: `before`, карточки подарков и `after`. Карточки пишутся
+    // по одной, так что весь документ в памяти не собирается.
+    fn page(
+        &self,
+        writer: &mut dyn Write,
+        title: &str,
+        before: &str,
+        gifts: &[&GiftRecord],
+        after: &str,
+    ) -> io::Result<()> {
+        write!(
+            writer,
+            "<!DOCTYPE html>
+<html lang=\"{}\">
+<head>
+<meta charset=\"UTF-8\" />
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />
+<title>{}</title>
+<style>{}</style>
+{}</head>
+<body>
+
+<div class=\"gifts-container\">
+{before}",
+            self.labels.code,
+            escape(title),
+            self.style,
+            self.custom_style
+        )?;
+        for gift in gifts {
+            let mut card = Card::new(gift, self.enrichment);
+            card.detail_page = self.detail_pages;
+            writer.write_all(card.render(self.labels).as_bytes())?;
+        }
+        write!(writer, "{after}{}</div>\n</body>\n</html>", self.footer)?;
+        writer.flush()
+    }
+}
+
+fn page_file_name(stem: &str, i: usize) -> String {
+    format!("{stem}_{:03}.html", i + 1)
+}
+
+// Ссылки «назад / к оглавлению / вперёд» для страницы с номером `i`.
+fn pages_nav(stem: &str, i: usize, total: usize, labels: &Labels) -> String {
+    let prev = if i > 0 {
+        format!(
+            "<a href=\"{}\">← {}</a>",
+            page_file_name(stem, i - 1),
+            labels.prev
+        )
+    } else {
+        "<span></span>".to_string()
+    };
+    let next = if i + 1 < total {
+        format!(
+            "<a href=\"{}\">{} →</a>",
+            page_file_name(stem, i + 1),
+            labels.next
+        )
+    } else {
+        "<span></span>".to_string()
+    };
+    format!(
+        "<nav class=\"pages-nav\">{prev}<a href=\"{stem}.html\">{}</a>{next}</nav>\n",
+        labels.all_pages
+    )
+}
+
+// Значение атрибута в виде, пригодном для имени файла: «Gold Coin» -> «Gold_Coin».
+fn file_safe(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Раздел «Качество данных»: что в отчёт не попало. Полный снимок показываем свёрнутым.
+fn quality_section(quality: &Quality, labels: &Labels) -> String {
+    let items: String = quality
+        .lines()
+        .iter()
+        .map(|line| format!("<li>{}</li>", escape(line)))
+        .collect();
+    let class = if quality.is_complete() {
+        "data-quality"
+    } else {
+        "data-quality incomplete"
+    };
+    format!(
+        "<section class=\"{class}\"><h2>{}</h2><ul>{items}</ul></section>\n",
+        labels.data_quality
+    )
+}
+
+// Подвал с версией парсера, слоем TL и параметрами запуска.
+fn footer(run: &RunInfo, labels: &Labels) -> String {
+    let params: String = run
+        .params
+        .iter()
+        .map(|(name, value)| format!("<dt>{name}</dt><dd>{}</dd>", escape(value)))
+        .collect();
+    let time = run
+        .report_timestamp()
+        .map(|time| format!(" · {time}"))
+        .unwrap_or_default();
+    format!(
+        "<footer class=\"run-info\">
+  rustfind {} · TL layer {}{time}
+  <details><summary>{}</summary><dl>{params}</dl></details>
+</footer>
+",
+        run.version, run.layer, labels.run_params
+    )
+}
+
+// Экранирует текст для вставки в HTML, в том числе внутрь атрибутов.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Модель и фон карточки; по ним же отчёт режется с --split-by.
+fn card_model(gift: &GiftRecord) -> &str {
+    gift.attributes
+        .model
+        .as_ref()
+        .map_or("Test", |model| &model.name)
+}
+
+fn card_backdrop(gift: &GiftRecord) -> &str {
+    gift.attributes
+        .backdrop
+        .as_ref()
+        .map_or("Test", |backdrop| &backdrop.name)
+}
+
+// Один подарок - всё, что нужно для его карточки в отчёте.
+struct Card {
+    slug: String,
+    link: String,
+    model: String,
+    backdrop: String,
+    owner: Option<String>,
+    // Ссылка на профиль владельца: пользователя или публичного канала.
+    owner_link: Option<String>,
+    // Цена перепродажи в звёздах, если подарок выставлен на продажу.
+    resale_price: Option<i64>,
+    // Самая низкая цена на перепродаже среди подарков с такими же моделью и фоном.
+    floor_price: Option<i64>,
+    transfer: Option<Transfer>,
+    rarity_score: Option<f64>,
+    // Адреса в блокчейне TON, если подарок туда выведен.
+    gift_address: Option<String>,
+    owner_address: Option<String>,
+    // Кто кому подарил подарок до улучшения, если владелец это оставил.
+    provenance: Option<Provenance>,
+    palette: Option<Palette>,
+    artwork: Option<String>,
+    // Картинки качали, но для этой модели её нет - показываем заглушку.
+    artwork_missing: bool,
+    // Имя подарка ведёт на его страницу «<slug>.html» рядом с отчётом.
+    detail_page: bool,
+}
+
+impl Card {
+    fn new(gift: &GiftRecord, enrichment: &Enrichment) -> Self {
+        let mut card = Card {
+            link: gift.link(),
+            model: card_model(gift).to_string(),
+            backdrop: card_backdrop(gift).to_string(),
+            owner: gift.owner.display_name(),
+            owner_link: gift.owner.link(),
+            slug: gift.slug.clone(),
+            resale_price: gift.resale_price,
+            floor_price: None,
+            transfer: gift.transfer.clone(),
+            rarity_score: gift.rarity_score,
+            gift_address: gift.gift_address.clone(),
+            owner_address: gift.owner_address.clone(),
+            provenance: gift.provenance.clone(),
+            palette: gift.attributes.palette.clone(),
+            artwork: None,
+            artwork_missing: false,
+            detail_page: false,
+        };
+
+        // Если в самом подарке цветов нет - берём их из палитры коллекции.
+        if card.palette.is_none() {
+            card.palette = enrichment.palettes.get(&card.backdrop).cloned();
+        }
+        if let Some(artwork) = &enrichment.artwork {
+            card.artwork = artwork.get(&card.model).cloned();
+            card.artwork_missing = card.artwork.is_none();
+        }
+        if let Some(floors) = &enrichment.floor_prices {
+            card.floor_price = floors
+                .get(&(card.model.clone(), card.backdrop.clone()))
+                .copied();
+        }
+        card
+    }
+
+    fn render(&self, labels: &Labels) -> String {
+        let (backdrop_style, palette_html) = match &self.palette {
+            Some(palette) => (
+                format!(
+                    r#" style="background: radial-gradient(circle, {} 0%, {} 100%); color: {}""#,
+                    palette.center_color, palette.edge_color, palette.text_color
+                ),
+                format!(
+                    r#"
+    <div class="gift-palette">
+      <span style="background: {0}">{0}</span>
+      <span style="background: {1}">{1}</span>
+      <span style="background: {2}">{2}</span>
+      <span style="background: {3}">{3}</span>
+    </div>"#,
+                    palette.center_color,
+                    palette.edge_color,
+                    palette.pattern_color,
+                    palette.text_color
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        let slug = escape(&self.slug);
+        let model = escape(&self.model);
+        let backdrop = escape(&self.backdrop);
+        let owner = escape(self.owner.as_deref().unwrap_or(""));
+        let artwork_html = match &self.artwork {
+            Some(src) => format!(
+                r#"
+    <img class="gift-art" src="{}" alt="{model}" loading="lazy" />"#,
+                escape(src)
+            ),
+            None if self.artwork_missing => format!(
+                r#"
+    <div class="gift-art gift-art-missing">{}</div>"#,
+                labels.no_artwork
+            ),
+            None => String::new(),
+        };
+        let rarity_html = match self.rarity_score {
+            Some(score) => format!(
+                r#"
+    <div class="gift-rarity">{}: {} {score:.0}</div>"#,
+                labels.rarity, labels.one_in
+            ),
+            None => String::new(),
+        };
+        let owner_html = if owner.is_empty() {
+            String::new()
+        } else {
+            format!(
+                r#"
+    <div class="gift-username">{}: {}</div>"#,
+                labels.owner,
+                owner_html(&owner, self.owner_link.as_deref())
+            )
+        };
+        let mut price_html = match self.resale_price {
+            Some(stars) => format!(
+                r#"
+    <div class="gift-price">{}: {stars} ⭐</div>"#,
+                labels.for_sale
+            ),
+            None => String::new(),
+        };
+        if let Some(stars) = self.floor_price {
+            price_html.push_str(&format!(
+                r#"
+    <div class="gift-floor">{}: {stars} ⭐</div>"#,
+                labels.floor_price
+            ));
+        }
+        if let Some(transfer) = &self.transfer {
+            price_html.push_str(&format!(
+                r#"
+    <div class="gift-transfer">{}</div>"#,
+                transfer_terms(transfer, labels)
+            ));
+        }
+
+        let provenance_html = match &self.provenance {
+            Some(provenance) => format!(
+                r#"
+    <div class="gift-original">{}: {}{}</div>"#,
+                labels.original,
+                escape(&provenance.summary),
+                provenance
+                    .message
+                    .as_deref()
+                    .map(|message| format!(" — «{}»", escape(message)))
+                    .unwrap_or_default()
+            ),
+            None => String::new(),
+        };
+
+        let ton_links: Vec<String> = [
+            (labels.ton_gift, &self.gift_address),
+            (labels.ton_owner, &self.owner_address),
+        ]
+        .into_iter()
+        .filter_map(|(label, address)| {
+            let address = address.as_deref()?;
+            Some(format!(
+                r#"<a href="{}" title="{}" target="_blank" rel="noopener noreferrer">{label}</a>"#,
+                escape(&explorer_url(address)),
+                escape(address)
+            ))
+        })
+        .collect();
+        let ton_html = if ton_links.is_empty() {
+            String::new()
+        } else {
+            format!(
+                r#"
+    <div class="gift-ton">{}: {}</div>"#,
+                labels.ton,
+                ton_links.join(" · ")
+            )
+        };
+
+        let name_html = if self.detail_page {
+            format!(r#"<a href="{slug}.html" class="gift-name">{slug}</a>"#)
+        } else {
+            format!(
+                r#"<a href="{}" class="gift-name" target="_blank" rel="noopener noreferrer">{slug}</a>"#,
+                escape(&self.link)
+            )
+        };
+
+        format!(
+            r#"<div class="gift-item" data-slug="{slug}" data-model="{model}" data-backdrop="{backdrop}" data-owner="{owner}">{artwork_html}
+    <div class="gift-model">{}: {model}</div>
+    <div class="gift-backdrop"{backdrop_style}>{}: {backdrop}</div>{palette_html}{rarity_html}{owner_html}{price_html}{provenance_html}{ton_html}
+    {name_html}
+</div>
+"#,
+            labels.model, labels.backdrop,
+        )
+    }
+}
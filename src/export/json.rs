@@ -0,0 +1,166 @@
+use crate::export::{Exporter, Report};
+use crate::model::{Attribute, GiftRecord, Owner};
+use crate::report::json_string;
+use crate::report::ownership::Ownership;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::{self, Write};
+
+// Снимок коллекции в JSON - для скриптов и других программ. Рядом с подарками
+// лежат сведения о запуске и качество данных, как в остальных форматах.
+// Набор полей подарка не зависит от параметров запуска: чего нет - там null.
+pub struct Json<'a> {
+    report: &'a Report<'a>,
+}
+
+impl<'a> Json<'a> {
+    pub fn new(report: &'a Report<'a>) -> Self {
+        Self { report }
+    }
+}
+
+impl Exporter for Json<'_> {
+    fn file_name(&self) -> String {
+        format!("{}.json", self.report.name)
+    }
+
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()> {
+        let report = self.report;
+        let run = report.run.report_json().trim_end().replace('\n', "\n  ");
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"run\": {run},")?;
+        writeln!(writer, "  \"quality\": {},", report.quality.to_json())?;
+        writeln!(writer, "  \"gifts\": [")?;
+        for (i, gift) in gifts.iter().enumerate() {
+            let comma = if i + 1 < gifts.len() { "," } else { "" };
+            let floor_price = report.enrichment.floor_price(gift);
+            let mut fields = gift_fields(gift, floor_price);
+            fields.push((
+                "owner_history",
+                owner_history(report.enrichment.owners(gift)),
+            ));
+            writeln!(writer, "    {}{comma}", object(&fields))?;
+        }
+        writeln!(writer, "  ]")?;
+        writeln!(writer, "}}")
+    }
+}
+
+// Подарок одной строкой JSON - так же его отдаёт `serve`.
+pub fn gift_json(gift: &GiftRecord, floor_price: Option<i64>) -> String {
+    object(&gift_fields(gift, floor_price))
+}
+
+fn gift_fields(gift: &GiftRecord, floor_price: Option<i64>) -> Vec<(&'static str, String)> {
+    let owner = &gift.owner;
+    let provenance = gift.provenance.as_ref();
+    let transfer = gift.transfer.as_ref();
+    vec![
+        ("num", gift.num.to_string()),
+        ("slug", json_string(&gift.slug)),
+        ("title", json_string(&gift.title)),
+        ("link", json_string(&gift.link())),
+        ("model", attribute(&gift.attributes.model)),
+        ("backdrop", attribute(&gift.attributes.backdrop)),
+        ("pattern", attribute(&gift.attributes.pattern)),
+        (
+            "rarity_score",
+            gift.rarity_score
+                .map_or_else(|| "null".to_string(), |score| format!("{score:.0}")),
+        ),
+        ("owner_kind", json_string(owner.kind())),
+        ("owner_id", number(owner.id())),
+        ("owner_name", string(owner.display_name().as_deref())),
+        ("owner_username", string(owner.username())),
+        ("resale_price", number(gift.resale_price)),
+        ("floor_price", number(floor_price)),
+        ("gift_address", string(gift.gift_address.as_deref())),
+        ("owner_address", string(gift.owner_address.as_deref())),
+        (
+            "original",
+            string(provenance.map(|provenance| provenance.summary.as_str())),
+        ),
+        (
+            "original_message",
+            string(provenance.and_then(|provenance| provenance.message.as_deref())),
+        ),
+        (
+            "transfer_stars",
+            number(transfer.and_then(|transfer| transfer.stars)),
+        ),
+        (
+            "transfer_after",
+            transfer
+                .and_then(|transfer| transfer.transfer_after)
+                .map_or_else(|| "null".to_string(), timestamp),
+        ),
+        (
+            "export_after",
+            transfer
+                .and_then(|transfer| transfer.export_after)
+                .map_or_else(|| "null".to_string(), timestamp),
+        ),
+    ]
+}
+
+fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields
+        .iter()
+        .map(|(name, value)| format!("\"{name}\": {value}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{{fields}}}")
+}
+
+// История владельцев подарка: владельцы от старых к новым с первым и последним
+// снимком, где они владели подарком, или null, если снимков для истории не было.
+fn owner_history(owners: Option<&[Ownership]>) -> String {
+    let Some(owners) = owners else {
+        return "null".to_string();
+    };
+    let owners = owners
+        .iter()
+        .map(|ownership| {
+            object(&[
+                ("owner", owner_json(&ownership.owner)),
+                ("first_seen", timestamp(ownership.first_seen)),
+                ("last_seen", timestamp(ownership.last_seen)),
+            ])
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{owners}]")
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    json_string(&time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+// Владелец отдельным объектом - для событий, где он не часть подарка.
+pub fn owner_json(owner: &Owner) -> String {
+    format!(
+        "{{\"kind\": {}, \"id\": {}, \"name\": {}, \"username\": {}}}",
+        json_string(owner.kind()),
+        number(owner.id()),
+        string(owner.display_name().as_deref()),
+        string(owner.username())
+    )
+}
+
+fn attribute(attribute: &Option<Attribute>) -> String {
+    match attribute {
+        Some(attribute) => format!(
+            "{{\"name\": {}, \"rarity_permille\": {}}}",
+            json_string(&attribute.name),
+            attribute.rarity_permille
+        ),
+        None => "null".to_string(),
+    }
+}
+
+fn string(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+fn number(value: Option<i64>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
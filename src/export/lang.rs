@@ -0,0 +1,193 @@
+use std::env;
+
+// Язык подписей в HTML-отчёте. Сами данные (имена, описание исходного подарка,
+// строки качества) не переводятся - они одинаковы во всех форматах.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    Ru,
+    En,
+}
+
+// Все подписи отчёта на одном языке.
+pub struct Labels {
+    // Значение атрибута lang у <html>.
+    pub code: &'static str,
+    pub search: &'static str,
+    pub all_models: &'static str,
+    pub all_backdrops: &'static str,
+    pub model: &'static str,
+    pub backdrop: &'static str,
+    pub pieces: &'static str,
+    pub rarity: &'static str,
+    pub one_in: &'static str,
+    pub collections: &'static str,
+    pub collection: &'static str,
+    pub gifts_count: &'static str,
+    pub holders: &'static str,
+    pub rarest: &'static str,
+    pub incomplete: &'static str,
+    pub back_to_groups: &'static str,
+    pub back_to_collection: &'static str,
+    pub attributes: &'static str,
+    pub number: &'static str,
+    pub pattern: &'static str,
+    pub colors: &'static str,
+    pub message: &'static str,
+    pub owner_history: &'static str,
+    pub hidden: &'static str,
+    pub page: &'static str,
+    pub page_of: &'static str,
+    pub gifts: &'static str,
+    pub prev: &'static str,
+    pub next: &'static str,
+    pub all_pages: &'static str,
+    pub data_quality: &'static str,
+    pub run_params: &'static str,
+    pub no_artwork: &'static str,
+    pub owner: &'static str,
+    pub for_sale: &'static str,
+    pub floor_price: &'static str,
+    pub transferable: &'static str,
+    pub after: &'static str,
+    pub for_stars: &'static str,
+    pub free: &'static str,
+    pub export_after: &'static str,
+    pub prices: &'static str,
+    pub min_price: &'static str,
+    pub median_price: &'static str,
+    pub max_price: &'static str,
+    pub original: &'static str,
+    pub ton: &'static str,
+    pub ton_gift: &'static str,
+    pub ton_owner: &'static str,
+}
+
+const RU: Labels = Labels {
+    code: "ru",
+    search: "Поиск по номеру, модели, фону, владельцу",
+    all_models: "Все модели",
+    all_backdrops: "Все фоны",
+    model: "Модель",
+    backdrop: "Фон",
+    pieces: "шт.",
+    rarity: "Редкость",
+    one_in: "1 из",
+    collections: "Коллекции",
+    collection: "Коллекция",
+    gifts_count: "Подарков",
+    holders: "Владельцев",
+    rarest: "Самый редкий",
+    incomplete: "снимок неполный",
+    back_to_groups: "Ко всем группам",
+    back_to_collection: "К коллекции",
+    attributes: "Атрибуты",
+    number: "Номер",
+    pattern: "Узор",
+    colors: "Цвета фона",
+    message: "Сообщение",
+    owner_history: "История владельцев",
+    hidden: "скрыт",
+    page: "Страница",
+    page_of: "из",
+    gifts: "подарки",
+    prev: "Назад",
+    next: "Вперёд",
+    all_pages: "Все страницы",
+    data_quality: "Качество данных",
+    run_params: "Параметры запуска",
+    no_artwork: "Нет картинки",
+    owner: "Владелец",
+    for_sale: "Продаётся",
+    floor_price: "Минимальная цена такой пары",
+    transferable: "Можно передать",
+    after: "после",
+    for_stars: "за",
+    free: "бесплатно",
+    export_after: "Вывод в TON после",
+    prices: "Цены перепродажи",
+    min_price: "Мин.",
+    median_price: "Медиана",
+    max_price: "Макс.",
+    original: "Исходный подарок",
+    ton: "В блокчейне TON",
+    ton_gift: "подарок",
+    ton_owner: "кошелёк владельца",
+};
+
+const EN: Labels = Labels {
+    code: "en",
+    search: "Search by number, model, backdrop, owner",
+    all_models: "All models",
+    all_backdrops: "All backdrops",
+    model: "Model",
+    backdrop: "Backdrop",
+    pieces: "pcs",
+    rarity: "Rarity",
+    one_in: "1 in",
+    collections: "Collections",
+    collection: "Collection",
+    gifts_count: "Gifts",
+    holders: "Holders",
+    rarest: "Rarest",
+    incomplete: "incomplete snapshot",
+    back_to_groups: "All groups",
+    back_to_collection: "Back to collection",
+    attributes: "Attributes",
+    number: "Number",
+    pattern: "Pattern",
+    colors: "Backdrop colors",
+    message: "Message",
+    owner_history: "Owner history",
+    hidden: "hidden",
+    page: "Page",
+    page_of: "of",
+    gifts: "gifts",
+    prev: "Previous",
+    next: "Next",
+    all_pages: "All pages",
+    data_quality: "Data quality",
+    run_params: "Run parameters",
+    no_artwork: "No picture",
+    owner: "Owner",
+    for_sale: "For sale",
+    floor_price: "Floor price for this pair",
+    transferable: "Transferable",
+    after: "after",
+    for_stars: "for",
+    free: "for free",
+    export_after: "TON export after",
+    prices: "Resale prices",
+    min_price: "Min",
+    median_price: "Median",
+    max_price: "Max",
+    original: "Original gift",
+    ton: "On TON blockchain",
+    ton_gift: "gift",
+    ton_owner: "owner wallet",
+};
+
+impl Lang {
+    // Язык системы по LC_ALL, LC_MESSAGES и LANG: русский для «ru_RU.UTF-8»,
+    // английский для любой другой заданной локали и русский, если не задано ничего.
+    pub fn from_system() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) if !locale.starts_with("ru") => Lang::En,
+            _ => Lang::Ru,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.labels().code
+    }
+
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Lang::Ru => &RU,
+            Lang::En => &EN,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::export::{Exporter, Report};
+use crate::model::ton::explorer_url;
+use crate::model::{FloorPrices, GiftRecord, Owner, Transfer};
+use crate::report::RunInfo;
+use crate::report::quality::Quality;
+use std::io::{self, Write};
+
+// Таблица подарков в Markdown - для вставки в GitHub, Notion или Telegram.
+// С минимальными ценами добавляется столбец с ценой пары модели и фона,
+// а если хоть у одного подарка сохранились исходные данные или известны
+// условия передачи - столбцы с ними.
+pub struct Markdown<'a> {
+    report: &'a Report<'a>,
+}
+
+impl<'a> Markdown<'a> {
+    pub fn new(report: &'a Report<'a>) -> Self {
+        Self { report }
+    }
+}
+
+impl Exporter for Markdown<'_> {
+    fn file_name(&self) -> String {
+        format!("{}.md", self.report.name)
+    }
+
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()> {
+        let report = self.report;
+        write_markdown(
+            gifts,
+            report.enrichment.floor_prices.as_ref(),
+            report.run,
+            report.quality,
+            writer,
+        )
+    }
+}
+
+// Пишет таблицу построчно: строка подарка уходит в `writer`, как только готова.
+pub fn write_markdown(
+    gifts: &[GiftRecord],
+    floor_prices: Option<&FloorPrices>,
+    run: &RunInfo,
+    quality: &Quality,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let mut header = vec!["№", "Модель", "Фон", "Редкость", "Владелец", "Цена ⭐"];
+    let mut align = vec!["---:", "---", "---", "---:", "---", "---:"];
+    if floor_prices.is_some() {
+        header.push("Мин. цена пары ⭐");
+        align.push("---:");
+    }
+    let with_provenance = gifts.iter().any(|gift| gift.provenance.is_some());
+    if with_provenance {
+        header.push("Исходный подарок");
+        align.push("---");
+    }
+    let with_transfer = gifts.iter().any(|gift| gift.transfer.is_some());
+    if with_transfer {
+        header.push("Передача");
+        align.push("---");
+    }
+    header.push("TON");
+    align.push("---");
+    writeln!(writer, "{}|{}|", row(&header), align.join("|"))?;
+
+    for gift in gifts {
+        let model = gift.attributes.model_name();
+        let backdrop = gift.attributes.backdrop_name();
+
+        let mut cells = vec![
+            format!("[{}]({})", gift.num, gift.link()),
+            cell(model),
+            cell(backdrop),
+            gift.rarity_score
+                .map(|score| format!("1 из {score:.0}"))
+                .unwrap_or_default(),
+            owner_link(&gift.owner),
+            // Пустая ячейка - подарок не продаётся.
+            gift.resale_price
+                .map(|stars| stars.to_string())
+                .unwrap_or_default(),
+        ];
+        if let Some(floors) = floor_prices {
+            cells.push(
+                floors
+                    .get(&(model.to_string(), backdrop.to_string()))
+                    .map(|stars| stars.to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        if with_provenance {
+            cells.push(
+                gift.provenance
+                    .as_ref()
+                    .map(|provenance| match &provenance.message {
+                        Some(message) => {
+                            format!("{} — «{}»", cell(&provenance.summary), cell(message))
+                        }
+                        None => cell(&provenance.summary),
+                    })
+                    .unwrap_or_default(),
+            );
+        }
+        if with_transfer {
+            cells.push(
+                gift.transfer
+                    .as_ref()
+                    .map(transfer_terms)
+                    .unwrap_or_default(),
+            );
+        }
+        cells.push(ton_links(gift));
+        writer.write_all(row(&cells).as_bytes())?;
+    }
+
+    writer.write_all("\n### Качество данных\n\n".as_bytes())?;
+    for line in quality.lines() {
+        writeln!(writer, "- {}", cell(&line))?;
+    }
+
+    let time = run
+        .report_timestamp()
+        .map(|time| format!(" · {time}"))
+        .unwrap_or_default();
+    writeln!(
+        writer,
+        "\n_rustfind {} · TL layer {}{time}_",
+        run.version, run.layer
+    )
+}
+
+// Владелец-пользователь превращается в ссылку на профиль (t.me, если известен
+// username, иначе tg://user), остальные - просто в текст.
+fn owner_link(owner: &Owner) -> String {
+    match owner {
+        Owner::User { id, name, username } => {
+            let name = name.as_deref().filter(|name| !name.is_empty()).map(cell);
+            match username {
+                Some(username) => format!(
+                    "[{}](https://t.me/{username})",
+                    name.unwrap_or_else(|| format!("@{username}"))
+                ),
+                None => format!(
+                    "[{}](tg://user?id={id})",
+                    name.unwrap_or_else(|| format!("user {id}"))
+                ),
+            }
+        }
+        // У публичного канала ссылка по username, у закрытого - только название.
+        Owner::Channel {
+            name,
+            username: Some(username),
+            ..
+        } => format!(
+            "[{}](https://t.me/{username})",
+            name.as_deref()
+                .filter(|name| !name.is_empty())
+                .map(cell)
+                .unwrap_or_else(|| format!("@{username}"))
+        ),
+        Owner::Chat {
+            name: Some(name), ..
+        }
+        | Owner::Channel {
+            name: Some(name), ..
+        }
+        | Owner::Name(name) => cell(name),
+        Owner::Chat { id, name: None } => format!("chat {id}"),
+        Owner::Channel { id, name: None, .. } => format!("channel {id}"),
+        Owner::Address(_) | Owner::Hidden => String::new(),
+    }
+}
+
+// «после 2025-07-01 за 25 ⭐ · в TON после 2025-07-10».
+fn transfer_terms(transfer: &Transfer) -> String {
+    let mut terms = Vec::new();
+    if let Some(after) = transfer.transfer_after {
+        terms.push(format!("после {}", after.format("%Y-%m-%d")));
+    }
+    terms.push(match transfer.stars {
+        Some(stars) => format!("за {stars} ⭐"),
+        None => "бесплатно".to_string(),
+    });
+    let mut text = terms.join(" ");
+    if let Some(after) = transfer.export_after {
+        text.push_str(&format!(" · в TON после {}", after.format("%Y-%m-%d")));
+    }
+    text
+}
+
+// Ссылки на подарок и кошелёк владельца в обозревателе TON, если подарок выведен в блокчейн.
+fn ton_links(gift: &GiftRecord) -> String {
+    [
+        ("подарок", &gift.gift_address),
+        ("кошелёк владельца", &gift.owner_address),
+    ]
+    .into_iter()
+    .filter_map(|(label, address)| {
+        Some(format!("[{label}]({})", explorer_url(address.as_deref()?)))
+    })
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+fn row<T: AsRef<str>>(cells: &[T]) -> String {
+    let cells: Vec<&str> = cells.iter().map(AsRef::as_ref).collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+// Экранирует текст так, чтобы он не ломал таблицу и разметку.
+fn cell(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '|' | '[' | ']' | '*' | '_' | '`' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,217 @@
+use crate::export::lang::Lang;
+use crate::model::palette::Palette;
+use crate::model::{FloorPrices, GiftRecord};
+use crate::report::RunInfo;
+use crate::report::ownership::{OwnerHistory, Ownership};
+use crate::report::quality::Quality;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod lang;
+pub mod markdown;
+pub mod ndjson;
+pub mod prices;
+pub mod publish;
+pub mod site;
+
+// Формат отчёта. Новый формат - это модуль с реализацией и строка в EXPORTERS,
+// больше ничего менять не нужно: --format находит его по имени.
+pub trait Exporter {
+    // Главный файл отчёта, например «parsed.html»: имя из `Report::name` и расширение формата.
+    fn file_name(&self) -> String;
+
+    // Пишет весь отчёт одним потоком.
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()>;
+
+    // Пишет файлы отчёта в `output`. Обычно это один `file_name`; форматы,
+    // которые режут отчёт на страницы, переопределяют это.
+    fn write_files(&self, gifts: &[GiftRecord], output: &mut dyn Output) -> io::Result<()> {
+        let mut writer = output.create(&self.file_name())?;
+        self.export(gifts, &mut writer)?;
+        writer.flush()
+    }
+}
+
+// Куда форматы пишут файлы отчёта. Каждый файл пишется потоком, так что
+// отчёт по большой коллекции не собирается в памяти целиком.
+pub trait Output {
+    fn create(&mut self, name: &str) -> io::Result<Box<dyn Write + '_>>;
+}
+
+// Файлы отчёта в папке на диске.
+pub struct Directory {
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+impl Directory {
+    // Папка создаётся, если её ещё нет.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(&dir)?;
+        }
+        Ok(Self {
+            dir,
+            files: Vec::new(),
+        })
+    }
+
+    // Пути всех записанных файлов по порядку, для manifest.json.
+    pub fn into_files(self) -> Vec<PathBuf> {
+        self.files
+    }
+}
+
+impl Output for Directory {
+    fn create(&mut self, name: &str) -> io::Result<Box<dyn Write + '_>> {
+        let path = self.dir.join(name);
+        let file = File::create(&path)?;
+        self.files.push(path);
+        Ok(Box::new(BufWriter::new(file)))
+    }
+}
+
+// Файлы отчёта в памяти - для тестов и тех, кому файлы не нужны.
+#[derive(Default)]
+pub struct Memory {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Memory {
+    // Пары «имя файла - содержимое» в том порядке, в каком их писали.
+    pub fn into_files(self) -> io::Result<Vec<(String, String)>> {
+        self.files
+            .into_iter()
+            .map(|(name, content)| {
+                let content = String::from_utf8(content)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                Ok((name, content))
+            })
+            .collect()
+    }
+}
+
+impl Output for Memory {
+    fn create(&mut self, name: &str) -> io::Result<Box<dyn Write + '_>> {
+        self.files.push((name.to_string(), Vec::new()));
+        let (_, content) = self.files.last_mut().unwrap();
+        Ok(Box::new(content))
+    }
+}
+
+type Constructor = for<'a> fn(&'a Report<'a>) -> Box<dyn Exporter + 'a>;
+
+// Все форматы по именам для --format, по алфавиту.
+const EXPORTERS: &[(&str, Constructor)] = &[
+    ("csv", |report| Box::new(csv::Csv::new(report))),
+    ("html", |report| Box::new(html::Html::new(report))),
+    ("json", |report| Box::new(json::Json::new(report))),
+    ("markdown", |report| {
+        Box::new(markdown::Markdown::new(report))
+    }),
+    ("ndjson", |report| Box::new(ndjson::Ndjson::new(report))),
+];
+
+// Имена, которые принимает --format.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    EXPORTERS.iter().map(|(name, _)| *name)
+}
+
+// Формат по имени из --format.
+pub fn exporter<'a>(name: &str, report: &'a Report<'a>) -> Option<Box<dyn Exporter + 'a>> {
+    EXPORTERS
+        .iter()
+        .find(|(exporter, _)| *exporter == name)
+        .map(|(_, new)| new(report))
+}
+
+// Всё, что кроме самих подарков нужно форматам: сведения о запуске, качество
+// снимка, данные о коллекции и настройки страниц HTML.
+pub struct Report<'a> {
+    // Имя файлов отчёта без расширения, «parsed» по умолчанию.
+    pub name: &'a str,
+    pub enrichment: &'a Enrichment,
+    pub page_size: usize,
+    pub split_by: Option<SplitBy>,
+    // Язык подписей HTML-отчёта.
+    pub lang: Lang,
+    // Тема HTML-отчёта и свои стили поверх встроенных (--css).
+    pub theme: Theme,
+    pub css: Option<&'a str>,
+    // Карточки ссылаются на страницы подарков «<slug>.html» рядом с отчётом (--site).
+    pub detail_pages: bool,
+    pub run: &'a RunInfo,
+    pub quality: &'a Quality,
+}
+
+// Данные о коллекции, загруженные отдельно от подарков, которыми дополняются карточки.
+#[derive(Default)]
+pub struct Enrichment {
+    // Палитры фонов по названию фона - на случай, если в самом подарке цветов нет.
+    pub palettes: HashMap<String, Palette>,
+    // Пути к картинкам моделей по их названию (None, если картинки не качали);
+    // у моделей без картинки в карточке стоит заглушка.
+    pub artwork: Option<HashMap<String, String>>,
+    // Минимальные цены на перепродаже (None, если их не запрашивали).
+    pub floor_prices: Option<FloorPrices>,
+    // История владельцев по снимкам (None без --store и --site).
+    pub owner_history: Option<OwnerHistory>,
+}
+
+impl Enrichment {
+    // Самая низкая цена на перепродаже среди подарков с такими же моделью и фоном.
+    pub fn floor_price(&self, gift: &GiftRecord) -> Option<i64> {
+        let pair = (
+            gift.attributes.model_name().to_string(),
+            gift.attributes.backdrop_name().to_string(),
+        );
+        self.floor_prices.as_ref()?.get(&pair).copied()
+    }
+
+    // Владельцы подарка от старых к новым; None, если истории нет.
+    pub fn owners(&self, gift: &GiftRecord) -> Option<&[Ownership]> {
+        let history = self.owner_history.as_ref()?;
+        Some(history.get(&gift.slug).map_or(&[][..], Vec::as_slice))
+    }
+}
+
+// По какому атрибуту разбивать HTML-отчёт на отдельные файлы.
+#[derive(Clone, Copy, Debug)]
+pub enum SplitBy {
+    Model,
+    Backdrop,
+}
+
+impl SplitBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SplitBy::Model => "model",
+            SplitBy::Backdrop => "backdrop",
+        }
+    }
+}
+
+// Цветовая схема HTML-отчёта.
+#[derive(Clone, Copy, Debug)]
+pub enum Theme {
+    Light,
+    Dark,
+    // Как в системе читателя (prefers-color-scheme).
+    Auto,
+}
+
+impl Theme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::Auto => "auto",
+        }
+    }
+}
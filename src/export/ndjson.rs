@@ -0,0 +1,31 @@
+use crate::export::json::gift_json;
+use crate::export::{Exporter, Report};
+use crate::model::GiftRecord;
+use std::io::{self, Write};
+
+// Подарки в NDJSON: по объекту JSON на строку, те же поля, что в JSON-отчёте.
+// Без сведений о запуске - каждую строку можно читать отдельно, например
+// `tail -f` во время парсинга: пока он идёт, файл дописывается по подарку.
+pub struct Ndjson<'a> {
+    report: &'a Report<'a>,
+}
+
+impl<'a> Ndjson<'a> {
+    pub fn new(report: &'a Report<'a>) -> Self {
+        Self { report }
+    }
+}
+
+impl Exporter for Ndjson<'_> {
+    fn file_name(&self) -> String {
+        format!("{}.ndjson", self.report.name)
+    }
+
+    fn export(&self, gifts: &[GiftRecord], writer: &mut dyn Write) -> io::Result<()> {
+        for gift in gifts {
+            let floor_price = self.report.enrichment.floor_price(gift);
+            writeln!(writer, "{}", gift_json(gift, floor_price))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::export::html;
+use crate::export::{Output, Report};
+use crate::report::prices::{PriceRow, Prices};
+use std::io::{self, Write};
+
+// Отчёт о ценах перепродажи (--prices) рядом с основным: «<stem>.html» для
+// людей и «<stem>.json» для скриптов. Оформление и сведения о запуске - из `report`.
+pub fn write_prices(
+    prices: &Prices,
+    report: &Report,
+    stem: &str,
+    output: &mut dyn Output,
+) -> io::Result<()> {
+    html::write_prices(
+        prices,
+        report.lang,
+        report.theme,
+        report.css,
+        report.run,
+        &mut output.create(&format!("{stem}.html"))?,
+    )?;
+
+    let array = |rows: &[PriceRow]| {
+        if rows.is_empty() {
+            return "[]".to_string();
+        }
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|row| format!("    {}", row.to_json()))
+            .collect();
+        format!("[\n{}\n  ]", rows.join(",\n"))
+    };
+    let run = report.run.report_json().trim_end().replace('\n', "\n  ");
+    let mut writer = output.create(&format!("{stem}.json"))?;
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"run\": {run},")?;
+    writeln!(writer, "  \"total\": {},", prices.total.to_json())?;
+    writeln!(writer, "  \"models\": {},", array(&prices.models))?;
+    writeln!(writer, "  \"backdrops\": {}", array(&prices.backdrops))?;
+    writeln!(writer, "}}")?;
+    writer.flush()
+}
@@ -0,0 +1,175 @@
+use crate::fetch::artwork::ARTWORK_DIR;
+use crate::report::json_string;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Готовит статическую галерею для GitHub Pages / Netlify: копирует последний
+// отчёт (parsed*.html, parsed.md), картинки и медиа в `out`, делает parsed.html
+// главной страницей и добавляет маленький поиск по всем подаркам.
+pub fn publish(out: &Path, base_url: Option<&str>, media: Option<&Path>) -> io::Result<()> {
+    fs::create_dir_all(out)?;
+
+    let mut index = Vec::new();
+    let mut pages = 0;
+    for entry in fs::read_dir(".")? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if !name.starts_with("parsed") {
+            continue;
+        }
+
+        if name.ends_with(".html") {
+            let html = fs::read_to_string(&name)?;
+            let target = published_name(&name);
+            index.extend(search_entries(&html, &target));
+            fs::write(out.join(&target), fix_links(&html, base_url))?;
+            pages += 1;
+        } else if name.ends_with(".md") {
+            fs::copy(&name, out.join(&name))?;
+        }
+    }
+    if pages == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "нет отчёта parsed.html - сначала запустите парсинг",
+        ));
+    }
+
+    if Path::new(ARTWORK_DIR).is_dir() {
+        copy_dir(Path::new(ARTWORK_DIR), &out.join(ARTWORK_DIR))?;
+    }
+    if let Some(media) = media {
+        copy_dir(media, &out.join("media"))?;
+    }
+
+    index.sort();
+    index.dedup();
+    fs::write(
+        out.join("search-index.json"),
+        format!("[\n{}\n]\n", index.join(",\n")),
+    )?;
+    fs::write(out.join("search.html"), fix_links(SEARCH_PAGE, base_url))?;
+    // Без этого файла GitHub Pages прогоняет сайт через Jekyll.
+    fs::write(out.join(".nojekyll"), "")?;
+
+    println!(
+        "Галерея собрана в {}: {pages} страниц, {} подарков в поиске",
+        out.display(),
+        index.len()
+    );
+    Ok(())
+}
+
+// Главный файл отчёта становится index.html, остальные сохраняют имена.
+fn published_name(name: &str) -> String {
+    if name == "parsed.html" {
+        "index.html".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+// Все ссылки в отчёте уже относительные, так что достаточно поправить ссылки на
+// переименованный parsed.html, а при заданном адресе сайта - добавить <base>.
+fn fix_links(html: &str, base_url: Option<&str>) -> String {
+    let html = html.replace("href=\"parsed.html\"", "href=\"index.html\"");
+    match base_url {
+        Some(base_url) => html.replacen(
+            "<head>",
+            &format!(
+                "<head>\n<base href=\"{}/\" />",
+                base_url.trim_end_matches('/')
+            ),
+            1,
+        ),
+        None => html,
+    }
+}
+
+// Записи поискового индекса по data-атрибутам карточек подарков на странице.
+fn search_entries(html: &str, page: &str) -> Vec<String> {
+    html.split("<div class=\"gift-item\"")
+        .skip(1)
+        .map(|card| {
+            let card = card.split('>').next().unwrap_or("");
+            format!(
+                "  {{\"slug\": {}, \"model\": {}, \"backdrop\": {}, \"owner\": {}, \"page\": {}}}",
+                json_string(&attribute(card, "data-slug")),
+                json_string(&attribute(card, "data-model")),
+                json_string(&attribute(card, "data-backdrop")),
+                json_string(&attribute(card, "data-owner")),
+                json_string(page)
+            )
+        })
+        .collect()
+}
+
+fn attribute(tag: &str, name: &str) -> String {
+    let Some((_, rest)) = tag.split_once(&format!("{name}=\"")) else {
+        return String::new();
+    };
+    let value = rest.split('"').next().unwrap_or("");
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+const SEARCH_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+<meta charset="UTF-8" />
+<meta name="viewport" content="width=device-width, initial-scale=1" />
+<title>Поиск подарков</title>
+<style>
+  body { font-family: "Segoe UI", Tahoma, Geneva, Verdana, sans-serif; max-width: 900px; margin: 0 auto; padding: 20px; }
+  input { width: 100%; padding: 8px 12px; font-size: 16px; box-sizing: border-box; }
+  li { margin: 6px 0; }
+</style>
+</head>
+<body>
+<p><a href="index.html">← К отчёту</a></p>
+<input id="q" type="search" placeholder="Slug, модель, фон или владелец" autofocus />
+<ul id="results"></ul>
+<script>
+fetch("search-index.json").then((r) => r.json()).then((gifts) => {
+  const q = document.getElementById("q");
+  const results = document.getElementById("results");
+  const render = () => {
+    const text = q.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (!text) return;
+    for (const gift of gifts) {
+      const haystack = [gift.slug, gift.model, gift.backdrop, gift.owner].join(" ").toLowerCase();
+      if (!haystack.includes(text)) continue;
+      const li = document.createElement("li");
+      const a = document.createElement("a");
+      a.href = gift.page;
+      a.textContent = gift.slug;
+      li.append(a, ` — ${gift.model}, ${gift.backdrop}${gift.owner ? ", " + gift.owner : ""}`);
+      results.append(li);
+      if (results.children.length >= 200) break;
+    }
+  };
+  q.addEventListener("input", render);
+});
+</script>
+</body>
+</html>
+"#;
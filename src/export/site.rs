@@ -0,0 +1,80 @@
+use crate::export::html::{IndexEntry, write_gift_page, write_html, write_index};
+use crate::export::json::Json;
+use crate::export::lang::Lang;
+use crate::export::{Exporter, Output, Report, Theme};
+use crate::model::GiftRecord;
+use crate::report::RunInfo;
+use crate::report::json_string;
+use std::io::{self, Write};
+
+// Статический сайт (--site): папка, которую можно сразу выложить на GitHub Pages
+// или Netlify. Все ссылки относительные, так что сайт работает из любой папки.
+//   index.html, collections.json - все коллекции
+//   <коллекция>/index.html        - карточки (index_001.html, … при разбиении)
+//   <коллекция>/<slug>.html       - страница каждого подарка со всеми атрибутами
+//   <коллекция>/gifts.json        - подарки в том же JSON, что parsed.json
+//   <коллекция>/parsed_art/       - превью моделей, если их качали
+
+// Страницы коллекции в `output` - папку коллекции на сайте. На страницах
+// подарков - история владельцев из обогащения отчёта.
+pub fn write_collection(
+    gifts: &[GiftRecord],
+    report: &Report,
+    output: &mut dyn Output,
+) -> io::Result<()> {
+    let report = Report {
+        name: "index",
+        detail_pages: true,
+        ..*report
+    };
+    write_html(gifts, &report, output)?;
+    for gift in gifts {
+        let owners = report.enrichment.owners(gift).unwrap_or_default();
+        write_gift_page(gift, owners, &report, output)?;
+    }
+    let data = Report {
+        name: "gifts",
+        ..report
+    };
+    Json::new(&data).write_files(gifts, output)
+}
+
+// Корень сайта: оглавление коллекций, оно же в collections.json, и .nojekyll.
+pub fn write_root(
+    entries: &[&IndexEntry],
+    lang: Lang,
+    theme: Theme,
+    css: Option<&str>,
+    run: &RunInfo,
+    output: &mut dyn Output,
+) -> io::Result<()> {
+    write_index(
+        entries,
+        lang,
+        theme,
+        css,
+        run,
+        &mut output.create("index.html")?,
+    )?;
+
+    let collections: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "  {{\"collection\": {}, \"link\": {}, \"complete\": {}, \"stats\": {}}}",
+                json_string(&entry.collection),
+                json_string(&entry.link),
+                entry.complete,
+                entry.stats.to_json()
+            )
+        })
+        .collect();
+    {
+        let mut writer = output.create("collections.json")?;
+        writeln!(writer, "[\n{}\n]", collections.join(",\n"))?;
+        writer.flush()?;
+    }
+
+    // Без этого файла GitHub Pages прогоняет сайт через Jekyll.
+    output.create(".nojekyll")?.flush()
+}
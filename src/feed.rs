@@ -0,0 +1,72 @@
+use crate::cli::Args;
+use crate::{Result, owner, watchlist};
+use grammers_client::Client;
+use parser_nft::error::ParserError;
+use parser_nft::fetch::links::message_slugs;
+use parser_nft::status;
+use std::collections::HashSet;
+
+// Подарки, упомянутые в чате: в последних `limit` сообщениях (без него - во всей
+// истории) ищутся ссылки t.me/nft/… и служебные сообщения об улучшенных и
+// переданных подарках. Подарки по ним загружаются заново и идут в такой же
+// отчёт, как по владельцу. Для ленты канала с анонсами улучшений (`feed`) так
+// находятся свежие улучшения, не перебирая коллекции; для любого чата (`scan-chat`) -
+// все подарки, которые в нём когда-либо дарили или обсуждали.
+pub async fn scan(client: &Client, input: &str, limit: Option<usize>, args: &Args) -> Result<()> {
+    let chat = owner::resolve(client, input).await?;
+    let name = owner::chat_name(&chat);
+
+    let mut messages = client.iter_messages(&chat);
+    if let Some(limit) = limit {
+        messages = messages.limit(limit);
+    }
+    let mut seen = HashSet::new();
+    let mut slugs = Vec::new();
+    let mut read = 0;
+    let mut stop_error = None;
+    loop {
+        match messages.next().await {
+            Ok(Some(message)) => {
+                read += 1;
+                if read % 1000 == 0 {
+                    status!(
+                        "{name}: прочитано сообщений {read}, найдено подарков {}",
+                        slugs.len()
+                    );
+                }
+                for slug in message_slugs(&message.raw) {
+                    if seen.insert(slug.clone()) {
+                        slugs.push(slug);
+                    }
+                }
+            }
+            Ok(None) => break,
+            // Уже найденные ссылки не выбрасываем: ошибка попадёт в раздел качества.
+            Err(e) if !slugs.is_empty() => {
+                status!("{name}: не удалось прочитать остальные сообщения: {e}");
+                stop_error = Some(e.to_string());
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if slugs.is_empty() {
+        status!("В сообщениях {name} ({read}) нет ссылок на подарки");
+        return Ok(());
+    }
+    status!(
+        "{name}: в сообщениях ({read}) ссылок на подарки {}",
+        slugs.len()
+    );
+
+    let (gifts, mut failed) = watchlist::fetch(client, &slugs, args.rps).await;
+    if gifts.is_empty() {
+        // Код выхода - по ошибке подарка: нет связи или flood-wait, а не «другая ошибка».
+        return Err(ParserError::NoGifts {
+            from: format!("сообщений {name}"),
+            source: failed.pop().map(Box::new),
+        });
+    }
+    let failed = failed.iter().map(ToString::to_string).collect();
+    owner::write_gifts(args, &name, None, gifts, stop_error, failed)
+}
@@ -0,0 +1,75 @@
+use crate::fetch::media::{DownloadError, download_file};
+use crate::report::quality::Quality;
+use crate::status;
+use grammers_client::Client;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::types::UniqueGift;
+use grammers_client::types::photo_sizes::PhotoSize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Папка с картинками моделей рядом с отчётом.
+pub const ARTWORK_DIR: &str = "parsed_art";
+
+// Скачивает превью стикера каждой модели в ARTWORK_DIR рядом с отчётом в `dir`.
+// Стикер модели - анимированный .tgs, который браузер показать не умеет, поэтому
+// берём готовое webp-превью, которое Telegram кладёт в thumbs документа.
+// Одна модель встречается у многих подарков, так что качаем каждую один раз.
+// Возвращает путь к картинке (относительно отчёта) по названию модели.
+pub async fn download_artwork(
+    client: &Client,
+    gifts: &[UniqueStarGift],
+    dir: &Path,
+    quality: &mut Quality,
+) -> std::io::Result<HashMap<String, String>> {
+    fs::create_dir_all(dir.join(ARTWORK_DIR))?;
+
+    let mut artwork = HashMap::new();
+    for gift in gifts {
+        let UniqueStarGift::Gift(gift_obj) = gift;
+        let Some(model) =
+            UniqueGift::from_star_gift(gift_obj.gift.clone()).and_then(|gift| gift.model())
+        else {
+            continue;
+        };
+        if artwork.contains_key(&model.name) {
+            continue;
+        }
+
+        let thumb = model
+            .sticker
+            .thumbs()
+            .into_iter()
+            .filter(|thumb| matches!(thumb, PhotoSize::Size(_)))
+            .max_by_key(|thumb| thumb.size());
+        let Some(thumb) = thumb else {
+            status!("У модели {} нет превью", model.name);
+            continue;
+        };
+
+        let path = format!("{ARTWORK_DIR}/{}.webp", model.sticker.id());
+        let file = dir.join(&path);
+        if !file.exists() {
+            match download_file(client, &thumb, &file).await {
+                Ok(()) => {}
+                // Без датацентра с медиа отчёт всё равно нужен - просто без картинок.
+                Err(DownloadError::Unreachable(reason)) => {
+                    status!("Датацентр с медиа недоступен ({reason}), отчёт будет без картинок");
+                    quality.media_unreachable = Some(reason);
+                    return Ok(artwork);
+                }
+                Err(DownloadError::Failed(e)) => {
+                    status!("Не удалось скачать превью модели {}: {e}", model.name);
+                    quality
+                        .failed_media
+                        .push(format!("превью модели {}", model.name));
+                    continue;
+                }
+            }
+        }
+        artwork.insert(model.name, path);
+    }
+
+    Ok(artwork)
+}
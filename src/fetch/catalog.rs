@@ -0,0 +1,92 @@
+use crate::status;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::{Deserializable as _, Serializable as _};
+use grammers_client::types::StarGiftCatalog;
+use grammers_client::{Client, InvocationError};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// Файл, в котором каталог хранится между запусками.
+pub const CACHE_FILE: &str = "star_gifts.catalog";
+
+// Каталог типов подарков с кэшем на диске. Каталог один на все коллекции и
+// меняется редко, поэтому Telegram получает hash сохранённого каталога и присылает
+// новый, только если тот изменился, - иначе обновление стоит одного короткого
+// запроса. Клоны делят один кэш.
+#[derive(Clone)]
+pub struct Catalog {
+    path: PathBuf,
+    cached: Arc<Mutex<Option<Arc<StarGiftCatalog>>>>,
+}
+
+impl Catalog {
+    // Кэш в файле `path`; если файла нет или он повреждён, первый запрос
+    // загрузит каталог целиком.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let cached = match fs::read(&path) {
+            Ok(bytes) => match tl::enums::payments::StarGifts::from_bytes(&bytes) {
+                Ok(tl::enums::payments::StarGifts::Gifts(raw)) => {
+                    Some(Arc::new(StarGiftCatalog { raw }))
+                }
+                Ok(tl::enums::payments::StarGifts::NotModified) => None,
+                Err(e) => {
+                    status!("Кэш каталога подарков повреждён, загрузим заново: {e}");
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+        Self {
+            path,
+            cached: Arc::new(Mutex::new(cached)),
+        }
+    }
+
+    // Актуальный каталог: сохранённый, если Telegram ответил, что он не изменился,
+    // иначе новый - он же сразу записывается в кэш. None, только если каталога
+    // нет ни в кэше, ни у Telegram.
+    pub async fn get(
+        &self,
+        client: &Client,
+    ) -> Result<Option<Arc<StarGiftCatalog>>, InvocationError> {
+        let cached = self.cached.lock().unwrap().clone();
+        let hash = cached.as_ref().map_or(0, |catalog| catalog.hash());
+        let Some(catalog) = client.get_star_gifts(hash).await? else {
+            return Ok(cached);
+        };
+        let catalog = Arc::new(catalog);
+        if let Err(e) = self.save(&catalog) {
+            status!("Не удалось сохранить кэш каталога подарков: {e}");
+        }
+        *self.cached.lock().unwrap() = Some(catalog.clone());
+        Ok(Some(catalog))
+    }
+
+    fn save(&self, catalog: &StarGiftCatalog) -> io::Result<()> {
+        let raw = tl::enums::payments::StarGifts::Gifts(catalog.raw.clone());
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, raw.to_bytes())?;
+        fs::rename(tmp, &self.path)
+    }
+}
+
+// Тип подарка коллекции из каталога - по названию («Plush Pepe») или slug («PlushPepe»).
+pub fn find_collection<'a>(
+    catalog: &'a StarGiftCatalog,
+    collection: &str,
+) -> Option<&'a tl::types::StarGift> {
+    catalog.gifts().find(|gift| {
+        gift.title
+            .as_deref()
+            .is_some_and(|title| title == collection || collection_slug(title) == collection)
+    })
+}
+
+// Slug коллекции - название без пробелов и знаков: «Plush Pepe» -> «PlushPepe».
+pub fn collection_slug(title: &str) -> String {
+    title.chars().filter(|c| c.is_alphanumeric()).collect()
+}
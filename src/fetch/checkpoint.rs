@@ -0,0 +1,148 @@
+use crate::fetch::Progress;
+use crate::status;
+use chrono::{DateTime, Utc};
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::grammers_tl_types::{Cursor, Deserializable, Serializable as _, deserialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Как часто (в подарках) сохранять состояние парсинга на диск.
+pub const CHECKPOINT_EVERY: usize = 100;
+
+// Состояние незаконченного парсинга коллекции: уже полученные подарки в том же
+// TL-формате, в котором их присылает Telegram, а за ними - номера, отложенные
+// на вторую попытку, не полученные и с неё и время сохранения. Если парсинг
+// прервали (Ctrl+C, обрыв связи), следующий запуск для той же коллекции
+// продолжит с места остановки.
+fn state_file(dir: &Path, collection: &str) -> PathBuf {
+    dir.join(format!("parsed_{collection}.state"))
+}
+
+pub fn save(progress: &Progress) -> io::Result<()> {
+    let path = state_file(&progress.state_dir, &progress.collection);
+    let mut bytes = progress.gifts.to_bytes();
+    let retry: Vec<i32> = progress.retry.iter().map(|&num| num as i32).collect();
+    retry.serialize(&mut bytes);
+    progress.failed.serialize(&mut bytes);
+    Utc::now().timestamp().serialize(&mut bytes);
+    // Пишем во временный файл и переименовываем, чтобы не оставить обрезанное состояние.
+    let tmp = path.with_extension("state.tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(tmp, path)
+}
+
+// Что осталось от прошлого незаконченного запуска.
+#[derive(Default)]
+pub struct Saved {
+    pub gifts: Vec<UniqueStarGift>,
+    pub retry: Vec<usize>,
+    // «slug: причина», как в `Progress::failed`.
+    pub failed: Vec<String>,
+    // Когда состояние сохранено; None для файлов прошлых версий.
+    pub taken_at: Option<DateTime<Utc>>,
+}
+
+// Состояние прошлого незаконченного запуска из папки `dir`; пусто, если продолжать нечего.
+pub fn load(dir: &Path, collection: &str) -> Saved {
+    let Ok(bytes) = fs::read(state_file(dir, collection)) else {
+        return Saved::default();
+    };
+    match decode(&bytes) {
+        Ok(saved) => saved,
+        Err(e) => {
+            status!("Файл состояния повреждён, начинаем заново: {e}");
+            Saved::default()
+        }
+    }
+}
+
+// Состояние из файла `path` - для `merge`, которому файлы называют явно.
+pub fn read(path: &Path) -> io::Result<Saved> {
+    decode(&fs::read(path)?).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} повреждён или не содержит подарков: {e}", path.display()),
+        )
+    })
+}
+
+fn decode(bytes: &[u8]) -> deserialize::Result<Saved> {
+    let mut cursor = Cursor::from_slice(bytes);
+    let gifts = Vec::deserialize(&mut cursor)?;
+    // В файлах прошлых версий после подарков ничего нет.
+    if cursor.pos() == bytes.len() {
+        return Ok(Saved {
+            gifts,
+            ..Saved::default()
+        });
+    }
+    let retry = Vec::<i32>::deserialize(&mut cursor)?;
+    let failed = Vec::deserialize(&mut cursor)?;
+    // Время сохранения появилось позже отложенных номеров.
+    let taken_at = if cursor.pos() == bytes.len() {
+        None
+    } else {
+        DateTime::from_timestamp(i64::deserialize(&mut cursor)?, 0)
+    };
+    Ok(Saved {
+        gifts,
+        retry: retry.into_iter().map(|num| num as usize).collect(),
+        failed,
+        taken_at,
+    })
+}
+
+// Коллекция спарсена до конца - продолжать больше нечего.
+pub fn clear(progress: &Progress) -> io::Result<()> {
+    match fs::remove_file(state_file(&progress.state_dir, &progress.collection)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::source::numbered_gift;
+    use grammers_client::grammers_tl_types::Serializable;
+
+    // Файлы прошлых версий: только подарки, потом без времени сохранения.
+    #[test]
+    fn legacy() {
+        let gifts = vec![numbered_gift("PlushPepe", 1), numbered_gift("PlushPepe", 3)];
+        let saved = decode(&gifts.to_bytes()).unwrap();
+        assert_eq!(saved.gifts.len(), 2);
+        assert!(saved.retry.is_empty() && saved.failed.is_empty());
+        assert!(saved.taken_at.is_none());
+
+        let mut bytes = gifts.to_bytes();
+        vec![2].serialize(&mut bytes);
+        vec!["PlushPepe-4: rpc error 500: INTERNAL".to_string()].serialize(&mut bytes);
+        let saved = decode(&bytes).unwrap();
+        assert_eq!(saved.retry, [2]);
+        assert_eq!(saved.failed, ["PlushPepe-4: rpc error 500: INTERNAL"]);
+        assert!(saved.taken_at.is_none());
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = std::env::temp_dir().join(format!("rustfind-checkpoint-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut progress = Progress::new("PlushPepe", 4);
+        progress.state_dir = dir.clone();
+        progress.gifts = vec![numbered_gift("PlushPepe", 1), numbered_gift("PlushPepe", 3)];
+        progress.retry = vec![2];
+        save(&progress).unwrap();
+
+        let saved = load(&dir, "PlushPepe");
+        assert_eq!(saved.gifts.len(), 2);
+        assert_eq!(saved.retry, [2]);
+        assert!(saved.taken_at.is_some());
+        clear(&progress).unwrap();
+        assert!(load(&dir, "PlushPepe").gifts.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use crate::error::{GiftContext as _, ParserError, Result};
+use crate::fetch::rate::RateLimiter;
+use crate::fetch::source::GiftSource;
+
+// Сколько номеров после найденной границы проверить, прежде чем счесть её концом
+// коллекции: пропуск длиннее этого поиск примет за конец.
+const GAP_PROBES: u32 = 8;
+
+// Находит наибольший существующий номер подарка в коллекции за O(log n) запросов:
+// сначала удваиваем номер, пока подарок существует, потом делим пополам
+// промежуток между последним найденным и первым ненайденным.
+// Возвращает 0, если в коллекции нет ни одного подарка.
+// Номера выдаются по возрастанию, но в коллекции бывают пропуски, и поиск может
+// попасть в пропуск. Поэтому найденная граница проверяется следующими GAP_PROBES
+// номерами: если за ней есть подарок, поиск продолжается от него.
+pub async fn highest_number(
+    source: &impl GiftSource,
+    collection: &str,
+    limiter: &mut RateLimiter,
+) -> Result<u32> {
+    let mut found = 0;
+    let mut missing = 1;
+    loop {
+        while exists(source, collection, missing, limiter).await? {
+            found = missing;
+            missing = double(collection, missing)?;
+        }
+
+        while missing - found > 1 {
+            let middle = found + (missing - found) / 2;
+            if exists(source, collection, middle, limiter).await? {
+                found = middle;
+            } else {
+                missing = middle;
+            }
+        }
+
+        // found + 1 уже не нашёлся, проверяем, не пропуск ли это.
+        let mut beyond = None;
+        for number in found.saturating_add(2)..=found.saturating_add(GAP_PROBES + 1) {
+            if exists(source, collection, number, limiter).await? {
+                beyond = Some(number);
+                break;
+            }
+        }
+        match beyond {
+            Some(number) => {
+                found = number;
+                missing = double(collection, number)?;
+            }
+            None => return Ok(found),
+        }
+    }
+}
+
+// Источник, у которого есть любой номер, - не коллекция Telegram.
+fn double(collection: &str, number: u32) -> Result<u32> {
+    number
+        .checked_mul(2)
+        .ok_or_else(|| ParserError::EndlessCollection(collection.to_string()))
+}
+
+async fn exists(
+    source: &impl GiftSource,
+    collection: &str,
+    number: u32,
+    limiter: &mut RateLimiter,
+) -> Result<bool> {
+    limiter.acquire().await;
+    let slug = format!("{collection}-{number}");
+    match source.gift(&slug).await {
+        Ok(_) => Ok(true),
+        Err(e) if e.is_slug_invalid() => Ok(false),
+        Err(e) => Err(e).gift(&slug),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::source::MockSource;
+    use grammers_client::InvocationError;
+    use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+
+    async fn highest(source: &impl GiftSource) -> Result<u32> {
+        highest_number(source, "PlushPepe", &mut RateLimiter::new(0.0)).await
+    }
+
+    #[tokio::test]
+    async fn consecutive() {
+        assert_eq!(
+            highest(&MockSource::numbered("PlushPepe", 1..=100))
+                .await
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            highest(&MockSource::numbered("PlushPepe", []))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    // Пропуски на проверяемых номерах не обрывают поиск: ни первый номер, ни
+    // 4096, на который попадает удвоение.
+    #[tokio::test]
+    async fn gaps() {
+        let source = MockSource::numbered("PlushPepe", 2..=50);
+        assert_eq!(highest(&source).await.unwrap(), 50);
+        let numbers = (1..=5000).filter(|num| !(4096..4100).contains(num));
+        let source = MockSource::numbered("PlushPepe", numbers);
+        assert_eq!(highest(&source).await.unwrap(), 5000);
+        // Пропуск длиннее GAP_PROBES считается концом коллекции.
+        let source = MockSource::numbered("PlushPepe", (1..=10).chain(30..=40));
+        assert_eq!(highest(&source).await.unwrap(), 10);
+    }
+
+    // Источник, отдающий подарок под любым номером, не роняет поиск переполнением,
+    // а заканчивает его ошибкой.
+    #[tokio::test]
+    async fn endless_source() {
+        struct Endless(UniqueStarGift);
+        impl GiftSource for Endless {
+            async fn gift(&self, _: &str) -> std::result::Result<UniqueStarGift, InvocationError> {
+                Ok(self.0.clone())
+            }
+        }
+
+        let gift = MockSource::numbered("PlushPepe", [1])
+            .gift("PlushPepe-1")
+            .await
+            .unwrap();
+        let e = highest(&Endless(gift)).await.unwrap_err();
+        assert!(matches!(e, ParserError::EndlessCollection(name) if name == "PlushPepe"));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::fetch::catalog::{Catalog, find_collection};
+use crate::model::FloorPrices;
+use grammers_client::{Client, InvocationError};
+
+// Проходит по всем подаркам коллекции, выставленным на перепродажу, и для каждой
+// пары модели и фона запоминает самую низкую цену. Telegram отдаёт объявления
+// от дешёвых к дорогим, так что первое попавшееся для пары - и есть минимум,
+// но берём min на случай, если цены изменятся прямо во время обхода.
+pub async fn fetch_floor_prices(
+    client: &Client,
+    catalog: &Catalog,
+    title: &str,
+) -> Result<FloorPrices, InvocationError> {
+    let mut floors = FloorPrices::new();
+
+    let Some(catalog) = catalog.get(client).await? else {
+        return Ok(floors);
+    };
+    let Some(gift) = find_collection(&catalog, title) else {
+        return Ok(floors);
+    };
+
+    let mut listings = client.iter_resale_star_gifts(gift.id).sort_by_price();
+    while let Some(listing) = listings.next().await? {
+        let (Some(price), Some(model), Some(backdrop)) =
+            (listing.resale_price(), listing.model(), listing.backdrop())
+        else {
+            continue;
+        };
+        floors
+            .entry((model.name, backdrop.name))
+            .and_modify(|floor| *floor = (*floor).min(price))
+            .or_insert(price);
+    }
+
+    Ok(floors)
+}
@@ -0,0 +1,71 @@
+use grammers_client::grammers_tl_types as tl;
+
+// Начала ссылок на подарки: «https://t.me/nft/PlushPepe-1234» и т. п.
+const NFT_LINKS: [&str; 2] = ["t.me/nft/", "telegram.me/nft/"];
+
+// Slug всех подарков, на которые ссылается текст, по порядку и с повторами.
+// Берутся только ссылки вида «…/nft/<Коллекция>-<номер>»: ссылки на коллекции
+// и обрезанные ссылки без номера пропускаются.
+pub fn slugs_in(text: &str) -> Vec<&str> {
+    let mut slugs = Vec::new();
+    for prefix in NFT_LINKS {
+        for (at, _) in text.match_indices(prefix) {
+            let rest = &text[at + prefix.len()..];
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                .unwrap_or(rest.len());
+            let slug = &rest[..end];
+            if let Some((collection, number)) = slug.rsplit_once('-')
+                && !collection.is_empty()
+                && !number.is_empty()
+                && number.bytes().all(|b| b.is_ascii_digit())
+            {
+                slugs.push((at, slug));
+            }
+        }
+    }
+    // Ссылки собраны по префиксам - возвращаем их в порядке текста.
+    slugs.sort_by_key(|(at, _)| *at);
+    slugs.into_iter().map(|(_, slug)| slug).collect()
+}
+
+// Slug подарков из сообщения (как его присылает Telegram): из текста, скрытых за
+// словами ссылок и кнопок-ссылок, а в служебном сообщении об улучшенном или
+// переданном подарке - его собственный.
+pub fn message_slugs(message: &tl::enums::Message) -> Vec<String> {
+    let message = match message {
+        tl::enums::Message::Message(message) => message,
+        tl::enums::Message::Service(service) => {
+            return match &service.action {
+                tl::enums::MessageAction::StarGiftUnique(
+                    tl::types::MessageActionStarGiftUnique {
+                        gift: tl::enums::StarGift::Unique(gift),
+                        ..
+                    },
+                ) => vec![gift.slug.clone()],
+                _ => Vec::new(),
+            };
+        }
+        tl::enums::Message::Empty(_) => return Vec::new(),
+    };
+    let mut texts = vec![message.message.as_str()];
+    for entity in message.entities.iter().flatten() {
+        if let tl::enums::MessageEntity::TextUrl(entity) = entity {
+            texts.push(&entity.url);
+        }
+    }
+    if let Some(tl::enums::ReplyMarkup::ReplyInlineMarkup(markup)) = &message.reply_markup {
+        for tl::enums::KeyboardButtonRow::Row(row) in &markup.rows {
+            for button in &row.buttons {
+                if let tl::enums::KeyboardButton::Url(button) = button {
+                    texts.push(&button.url);
+                }
+            }
+        }
+    }
+    texts
+        .into_iter()
+        .flat_map(slugs_in)
+        .map(str::to_string)
+        .collect()
+}
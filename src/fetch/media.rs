@@ -0,0 +1,143 @@
+use crate::model::palette::Palette;
+use crate::report::quality::Quality;
+use crate::report::{RunInfo, json_string};
+use crate::status;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::types::{Downloadable, GiftAttribute, GiftBackdrop, UniqueGift};
+use grammers_client::{Client, InvocationError};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::timeout;
+
+// Сколько ждать один файл: если датацентр с медиа заблокирован, соединение
+// с ним может висеть минутами.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub enum DownloadError {
+    // До датацентра с медиа не достучаться - остальные файлы тоже не скачаются.
+    Unreachable(String),
+    Failed(io::Error),
+}
+
+// Сохраняет медиа каждого подарка для офлайн-архива:
+//   <dir>/<slug>/model.tgs     - стикер модели
+//   <dir>/<slug>/pattern.tgs   - стикер узора
+//   <dir>/<slug>/backdrop.json - цвета и редкость фона
+// Модели и узоры повторяются между подарками, поэтому каждый документ качаем
+// из Telegram один раз, а дальше копируем уже скачанный файл.
+// В <dir>/meta.json записываются версия и параметры запуска.
+pub async fn download_media(
+    client: &Client,
+    gifts: &[UniqueStarGift],
+    dir: &Path,
+    run: &RunInfo,
+    quality: &mut Quality,
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("meta.json"), run.to_json())?;
+
+    let mut downloaded: HashMap<i64, PathBuf> = HashMap::new();
+
+    for gift in gifts {
+        let UniqueStarGift::Gift(gift_obj) = gift;
+        let Some(gift) = UniqueGift::from_star_gift(gift_obj.gift.clone()) else {
+            continue;
+        };
+        let gift_dir = dir.join(gift.slug());
+        fs::create_dir_all(&gift_dir)?;
+
+        for attribute in gift.attributes() {
+            let (document, file_name) = match attribute {
+                GiftAttribute::Model(model) => (model.sticker, "model.tgs"),
+                GiftAttribute::Pattern(pattern) => (pattern.sticker, "pattern.tgs"),
+                GiftAttribute::Backdrop(backdrop) => {
+                    fs::write(gift_dir.join("backdrop.json"), backdrop_json(&backdrop))?;
+                    continue;
+                }
+                GiftAttribute::OriginalDetails(_) => continue,
+            };
+
+            let path = gift_dir.join(file_name);
+            match downloaded.get(&document.id()) {
+                Some(source) => {
+                    fs::copy(source, &path)?;
+                }
+                None => match download_file(client, &document, &path).await {
+                    Ok(()) => {
+                        downloaded.insert(document.id(), path);
+                    }
+                    Err(DownloadError::Unreachable(reason)) => {
+                        status!("Датацентр с медиа недоступен ({reason}), медиа не сохранены");
+                        quality.media_unreachable = Some(reason);
+                        return Ok(());
+                    }
+                    Err(DownloadError::Failed(e)) => {
+                        status!("Не удалось скачать {} для {}: {e}", file_name, gift.slug());
+                        quality
+                            .failed_media
+                            .push(format!("{}: {file_name}", gift.slug()));
+                    }
+                },
+            }
+        }
+        status!("Медиа подарка {} сохранены", gift.slug());
+    }
+
+    Ok(())
+}
+
+// Скачивает один файл, отличая недоступный датацентр от ошибки с конкретным файлом.
+// Недокачанный файл удаляется, чтобы его не приняли за готовый при следующем запуске.
+pub async fn download_file<D: Downloadable, P: AsRef<Path>>(
+    client: &Client,
+    file: &D,
+    path: P,
+) -> Result<(), DownloadError> {
+    let result = match timeout(DOWNLOAD_TIMEOUT, client.download_media(file, &path)).await {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(e)) if is_network_error(&e) => Err(DownloadError::Unreachable(e.to_string())),
+        Ok(Err(e)) => Err(DownloadError::Failed(e)),
+        Err(_) => Err(DownloadError::Unreachable(format!(
+            "нет ответа за {} с",
+            DOWNLOAD_TIMEOUT.as_secs()
+        ))),
+    };
+    let _ = fs::remove_file(path);
+    result
+}
+
+// Ошибка RPC - это ответ сервера, а обрыв соединения или ошибка чтения значат,
+// что до датацентра не достучаться.
+fn is_network_error(e: &io::Error) -> bool {
+    matches!(
+        e.get_ref()
+            .and_then(|inner| inner.downcast_ref::<InvocationError>()),
+        Some(InvocationError::Read(_) | InvocationError::Dropped)
+    )
+}
+
+fn backdrop_json(backdrop: &GiftBackdrop) -> String {
+    let palette = Palette::from_backdrop(backdrop);
+    format!(
+        r#"{{
+  "name": {},
+  "backdrop_id": {},
+  "center_color": "{}",
+  "edge_color": "{}",
+  "pattern_color": "{}",
+  "text_color": "{}",
+  "rarity_permille": {}
+}}
+"#,
+        json_string(&backdrop.name),
+        backdrop.id,
+        palette.center_color,
+        palette.edge_color,
+        palette.pattern_color,
+        palette.text_color,
+        backdrop.rarity_permille
+    )
+}
@@ -0,0 +1,338 @@
+use crate::error::ParserError;
+use crate::fetch::rate::RateLimiter;
+use crate::fetch::source::GiftSource;
+use crate::report::timing::Timings;
+use crate::status;
+use crate::store;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesOrdered;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::{InvocationError, RpcErrorKind};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub mod artwork;
+pub mod catalog;
+pub mod checkpoint;
+pub mod discover;
+pub mod floor;
+pub mod links;
+pub mod media;
+pub mod owners;
+pub mod palette;
+pub mod rate;
+pub mod source;
+
+// Докуда дошёл парсинг коллекции. После обрыва связи fetch_gifts можно вызвать
+// снова с новым клиентом - она продолжит с `next`.
+pub struct Progress {
+    pub collection: String,
+    pub gifts: Vec<UniqueStarGift>,
+    // Номер следующего подарка.
+    pub next: usize,
+    // Последний выпущенный номер; 0, пока его не нашёл discover::highest_number.
+    pub highest: usize,
+    // Номера, которые не получились из-за ошибки самого подарка: их пробуем ещё
+    // раз, когда пройдём всю коллекцию.
+    pub retry: Vec<usize>,
+    // Подарки, которые не получились и со второй попытки: «slug: причина».
+    pub failed: Vec<String>,
+    // Время ответов на запросы подарков.
+    pub timings: Timings,
+    // Папка, где лежит файл состояния (см. checkpoint).
+    pub state_dir: PathBuf,
+}
+
+impl Progress {
+    // Парсинг коллекции с первого номера до `highest`; состояние - в текущей папке.
+    pub fn new(collection: &str, highest: usize) -> Self {
+        Self {
+            collection: collection.to_string(),
+            gifts: Vec::new(),
+            next: 1,
+            highest,
+            retry: Vec::new(),
+            failed: Vec::new(),
+            timings: Timings::default(),
+            state_dir: PathBuf::from("."),
+        }
+    }
+
+    // Продолжает с места, где остановился прошлый незаконченный запуск, если его
+    // состояние есть в `state_dir`.
+    pub fn resume(state_dir: &Path, collection: &str) -> Self {
+        let saved = checkpoint::load(state_dir, collection);
+        // Номера идут с пропусками, так что считаем от последнего, а не по количеству.
+        let next = saved
+            .gifts
+            .last()
+            .map_or(1, |gift| store::number(gift) as usize + 1);
+        // Отложенные номера и не полученные со второй попытки пробуем снова. Те, что
+        // после последнего полученного подарка, и так будут запрошены по порядку.
+        let failed = saved
+            .failed
+            .iter()
+            .filter_map(|failed| failed_number(collection, failed));
+        let mut retry: Vec<usize> = saved
+            .retry
+            .into_iter()
+            .chain(failed)
+            .filter(|&num| num < next)
+            .collect();
+        retry.sort_unstable();
+        retry.dedup();
+        Self {
+            gifts: saved.gifts,
+            next,
+            retry,
+            state_dir: state_dir.to_path_buf(),
+            ..Self::new(collection, 0)
+        }
+    }
+
+    // Обновление по прошлому снимку (--update): подарки снимка остаются как есть,
+    // а последние `window` номеров из него и всё, что выпущено после, загружаются
+    // заново - у свежих подарков чаще всего меняются владельцы и цены.
+    pub fn update(collection: &str, stored: Vec<UniqueStarGift>, window: usize) -> Self {
+        let highest_stored = stored.iter().map(store::number).max().unwrap_or(0) as usize;
+        let keep = highest_stored.saturating_sub(window);
+        let mut gifts: Vec<UniqueStarGift> = stored
+            .into_iter()
+            .filter(|gift| store::number(gift) as usize <= keep)
+            .collect();
+        gifts.sort_by_key(store::number);
+        Self {
+            gifts,
+            next: keep + 1,
+            ..Self::new(collection, 0)
+        }
+    }
+}
+
+// Почему fetch_gifts остановилась.
+pub enum Stop {
+    // Получены все подарки до `highest`.
+    Done,
+    // Пропала связь с Telegram: можно переподключиться и продолжить.
+    ConnectionLost(InvocationError),
+    // Telegram отозвал авторизацию сессии: продолжить можно только после нового входа.
+    SessionRevoked(InvocationError),
+    // Завершился `interrupt`; внутри - slug, на котором остановились.
+    Interrupted(String),
+    // Ошибка запроса подарка (ParserError::Gift): со slug и ошибкой Telegram.
+    Failed(ParserError),
+}
+
+// Загружает подарки коллекции по порядку номеров, начиная с `progress.next`.
+// Несуществующие номера пропускаются, каждые CHECKPOINT_EVERY подарков состояние
+// сохраняется на диск. С `adaptive` скорость снижается по flood-wait.
+// Как только завершится `interrupt` (например, Ctrl+C), парсинг останавливается.
+// Каждый полученный подарок сразу передаётся в `on_gift` - так работает --stream.
+// Номера, на которых Telegram вернул ошибку самого подарка, откладываются в
+// `progress.retry` и запрашиваются ещё раз в конце; не получившиеся и тогда
+// попадают в `progress.failed`, а парсинг идёт дальше.
+//
+// Запросы уходят, не дожидаясь ответов на предыдущие, - столько сразу, сколько
+// источник принимает параллельно (по одному на соединение, --connections). Ответы
+// разбираются строго по порядку номеров, так что остальное - как при одном запросе за раз.
+pub async fn fetch_gifts(
+    source: &impl GiftSource,
+    progress: &mut Progress,
+    limiter: &mut RateLimiter,
+    adaptive: bool,
+    interrupt: &mut (impl Future + Unpin),
+    on_gift: &mut impl FnMut(&UniqueStarGift),
+) -> io::Result<Stop> {
+    let collection = progress.collection.clone();
+    // Клон делит ведро с `limiter`: очередь за токенами общая.
+    let shared = limiter.clone();
+    let request = |num: usize| {
+        let slug = format!("{collection}-{num}");
+        let mut limiter = shared.clone();
+        async move {
+            limiter.acquire().await;
+            let sent = Instant::now();
+            let get_gift = source.gift(&slug).await;
+            (slug, sent, get_gift)
+        }
+    };
+    let parallel = source.parallel_requests().max(1);
+    let mut in_flight = FuturesOrdered::new();
+    // Первый номер, который ещё не запрошен.
+    let mut requested = progress.next;
+    loop {
+        while in_flight.len() < parallel && requested <= progress.highest {
+            in_flight.push_back(request(requested));
+            requested += 1;
+        }
+        let (slug, sent, get_gift) = tokio::select! {
+            response = in_flight.next() => match response {
+                Some(response) => response,
+                None => break,
+            },
+            _ = &mut *interrupt => {
+                status!("Парсинг прерван, сохраняем полученные подарки");
+                return Ok(Stop::Interrupted(format!("{collection}-{}", progress.next)));
+            }
+        };
+        progress.timings.record(sent, &get_gift);
+        match get_gift {
+            Ok(gift) => {
+                status!(
+                    "{}: парсинг подарка с номером {} из {}",
+                    progress.collection,
+                    progress.next,
+                    progress.highest
+                );
+                on_gift(&gift);
+                progress.gifts.push(gift);
+                limiter.on_success();
+                progress.next += 1;
+                if progress
+                    .gifts
+                    .len()
+                    .is_multiple_of(checkpoint::CHECKPOINT_EVERY)
+                {
+                    checkpoint::save(progress)?;
+                }
+            }
+            Err(e) if adaptive && e.flood_wait_seconds().is_some() => {
+                let seconds = e.flood_wait_seconds().unwrap_or_default();
+                limiter.on_flood_wait();
+                status!(
+                    "Flood-wait {seconds} с, снижаем скорость до {:.1} запросов/с",
+                    limiter.rps()
+                );
+                tokio::time::sleep(Duration::from_secs(seconds as u64)).await;
+                // Ответы на следующие номера, скорее всего, тоже flood-wait:
+                // после паузы запрашиваем всё заново с этого номера.
+                in_flight = FuturesOrdered::new();
+                requested = progress.next;
+            }
+            Err(e) if e.is_slug_invalid() => {
+                status!("Подарка {slug} нет, пропускаем");
+                progress.next += 1;
+            }
+            Err(e) if e.is_connection_lost() => return Ok(Stop::ConnectionLost(e)),
+            Err(e) if is_session_revoked(&e) => return Ok(Stop::SessionRevoked(e)),
+            Err(e) if is_gift_error(&e) => {
+                status!("{slug}: {e}, попробуем ещё раз в конце");
+                progress.retry.push(progress.next);
+                progress.next += 1;
+            }
+            Err(e) => {
+                status!("{}", slug);
+                return Ok(Stop::Failed(ParserError::Gift {
+                    gift: slug,
+                    source: e,
+                }));
+            }
+        }
+    }
+    retry_gifts(source, progress, limiter, adaptive, interrupt, on_gift).await
+}
+
+// Вторая попытка для номеров из `progress.retry`, по одному запросу на номер
+// и по одному за раз: таких номеров единицы.
+// Полученные подарки встают среди остальных по номеру.
+async fn retry_gifts(
+    source: &impl GiftSource,
+    progress: &mut Progress,
+    limiter: &mut RateLimiter,
+    adaptive: bool,
+    interrupt: &mut (impl Future + Unpin),
+    on_gift: &mut impl FnMut(&UniqueStarGift),
+) -> io::Result<Stop> {
+    while let Some(&num) = progress.retry.first() {
+        let slug = format!("{}-{num}", progress.collection);
+        limiter.acquire().await;
+        let sent = Instant::now();
+        let get_gift = tokio::select! {
+            get_gift = source.gift(&slug) => get_gift,
+            _ = &mut *interrupt => {
+                status!("Парсинг прерван, сохраняем полученные подарки");
+                return Ok(Stop::Interrupted(slug));
+            }
+        };
+        progress.timings.record(sent, &get_gift);
+        match get_gift {
+            Ok(gift) => {
+                status!("{slug}: получен со второй попытки");
+                on_gift(&gift);
+                let position = progress
+                    .gifts
+                    .partition_point(|gift| store::number(gift) < num as i32);
+                progress.gifts.insert(position, gift);
+                limiter.on_success();
+                progress.retry.remove(0);
+            }
+            Err(e) if adaptive && e.flood_wait_seconds().is_some() => {
+                let seconds = e.flood_wait_seconds().unwrap_or_default();
+                limiter.on_flood_wait();
+                status!(
+                    "Flood-wait {seconds} с, снижаем скорость до {:.1} запросов/с",
+                    limiter.rps()
+                );
+                tokio::time::sleep(Duration::from_secs(seconds as u64)).await;
+            }
+            Err(e) if e.is_slug_invalid() => {
+                status!("Подарка {slug} нет, пропускаем");
+                progress.retry.remove(0);
+            }
+            Err(e) if e.is_connection_lost() => return Ok(Stop::ConnectionLost(e)),
+            Err(e) if is_session_revoked(&e) => return Ok(Stop::SessionRevoked(e)),
+            Err(e) if is_gift_error(&e) => {
+                status!("{slug}: {e}, не получен");
+                progress.failed.push(format!("{slug}: {e}"));
+                progress.retry.remove(0);
+            }
+            Err(e) => {
+                return Ok(Stop::Failed(ParserError::Gift {
+                    gift: slug,
+                    source: e,
+                }));
+            }
+        }
+    }
+
+    if !progress.failed.is_empty() {
+        status!(
+            "{}: не удалось получить подарков: {}",
+            progress.collection,
+            progress.failed.len()
+        );
+        for failed in &progress.failed {
+            status!("  {failed}");
+        }
+    }
+    Ok(Stop::Done)
+}
+
+// Номер подарка из записи `Progress::failed` («PlushPepe-2: причина»).
+fn failed_number(collection: &str, failed: &str) -> Option<usize> {
+    let rest = failed.strip_prefix(collection)?.strip_prefix('-')?;
+    rest.split(':').next()?.parse().ok()
+}
+
+// Сессия больше не авторизована (401: AUTH_KEY_UNREGISTERED, SESSION_REVOKED):
+// из аккаунта вышли на другом устройстве или сессию завершили в настройках.
+pub fn is_session_revoked(e: &InvocationError) -> bool {
+    e.kind() == Some(RpcErrorKind::Unauthorized)
+}
+
+// Ошибка, из-за которой не получился только этот подарок: Telegram отклонил
+// запрос (400), сломался на нём (5xx) или прислал то, что не разобрать.
+// Авторизация, flood-wait и обрыв связи касаются всех запросов сразу.
+fn is_gift_error(e: &InvocationError) -> bool {
+    match e {
+        InvocationError::Rpc(rpc) => match rpc.kind() {
+            RpcErrorKind::Internal | RpcErrorKind::InvalidParameter => true,
+            RpcErrorKind::Other => rpc.code == 400 || rpc.code >= 500,
+            _ => false,
+        },
+        InvocationError::Read(_) => !e.is_connection_lost(),
+        InvocationError::Dropped => false,
+    }
+}
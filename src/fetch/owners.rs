@@ -0,0 +1,70 @@
+use crate::fetch::rate::RateLimiter;
+use crate::model::owners::find_user;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::{Client, InvocationError};
+use std::collections::{HashMap, HashSet};
+
+// Сколько пользователей запрашивать за раз.
+const BATCH_SIZE: usize = 100;
+
+// Telegram присылает владельца подарка как голый `owner_id`, а сам пользователь
+// с username и именем приходит в `users` не всегда. Собираем известных
+// пользователей из всех подарков, недостающих запрашиваем пачками по id и
+// дописываем каждому подарку его владельца. Каждый id запрашивается не больше
+// одного раза, даже если у владельца сотни подарков.
+// Возвращает, скольким подаркам удалось найти владельца.
+pub async fn resolve_owners(
+    client: &Client,
+    gifts: &mut [UniqueStarGift],
+    limiter: &mut RateLimiter,
+) -> Result<usize, InvocationError> {
+    let mut known: HashMap<i64, tl::enums::User> = HashMap::new();
+    for UniqueStarGift::Gift(gift) in gifts.iter() {
+        for user in &gift.users {
+            if let tl::enums::User::User(raw) = user {
+                known.entry(raw.id).or_insert_with(|| user.clone());
+            }
+        }
+    }
+
+    let missing: Vec<i64> = gifts
+        .iter()
+        .filter_map(owner_id)
+        .filter(|id| !known.contains_key(id))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    for batch in missing.chunks(BATCH_SIZE) {
+        limiter.acquire().await;
+        for user in client.get_users(batch).await? {
+            known.insert(user.id(), user.raw);
+        }
+    }
+
+    let mut resolved = 0;
+    for gift in gifts.iter_mut() {
+        let Some(id) = owner_id(gift) else {
+            continue;
+        };
+        let UniqueStarGift::Gift(gift) = gift;
+        if find_user(&gift.users, id).is_some() {
+            resolved += 1;
+        } else if let Some(user) = known.get(&id) {
+            gift.users.push(user.clone());
+            resolved += 1;
+        }
+    }
+    Ok(resolved)
+}
+
+fn owner_id(gift: &UniqueStarGift) -> Option<i64> {
+    let UniqueStarGift::Gift(gift) = gift;
+    match &gift.gift {
+        tl::enums::StarGift::Unique(tl::types::StarGiftUnique {
+            owner_id: Some(tl::enums::Peer::User(user)),
+            ..
+        }) => Some(user.user_id),
+        _ => None,
+    }
+}
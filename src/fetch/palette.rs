@@ -0,0 +1,31 @@
+use crate::fetch::catalog::{Catalog, find_collection};
+use crate::model::palette::Palette;
+use grammers_client::{Client, InvocationError};
+use std::collections::HashMap;
+
+// Загружает палитры фонов коллекции один раз: ищем базовый подарок по названию
+// в каталоге и берём фоны из его превью улучшения.
+// Ключ - название фона, как в атрибуте Backdrop у уникального подарка.
+pub async fn fetch_palettes(
+    client: &Client,
+    catalog: &Catalog,
+    title: &str,
+) -> Result<HashMap<String, Palette>, InvocationError> {
+    let mut palettes = HashMap::new();
+
+    let Some(catalog) = catalog.get(client).await? else {
+        return Ok(palettes);
+    };
+    let Some(gift) = find_collection(&catalog, title) else {
+        return Ok(palettes);
+    };
+
+    let preview = client.get_star_gift_upgrade_preview(gift.id).await?;
+    palettes.extend(
+        preview
+            .backdrops()
+            .map(|backdrop| (backdrop.name.clone(), Palette::from_backdrop(&backdrop))),
+    );
+
+    Ok(palettes)
+}
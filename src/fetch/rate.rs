@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+// Настройки автоподбора скорости (--adaptive).
+const ADAPTIVE_START_RPS: f64 = 5.0;
+const ADAPTIVE_MIN_RPS: f64 = 0.2;
+const ADAPTIVE_MAX_RPS: f64 = 50.0;
+// Сколько запросов подряд без flood-wait нужно, чтобы прибавить скорость.
+const ADAPTIVE_STREAK: u32 = 20;
+const ADAPTIVE_STEP_RPS: f64 = 0.5;
+
+// Ограничитель запросов «ведро с токенами»: в секунду добавляется `rps` токенов,
+// каждый запрос забирает один. Ведро вмещает не больше секунды запросов, так что
+// после простоя можно сделать короткий всплеск, но в среднем не быстрее `rps`.
+// Клоны делят одно ведро: коллекции, которые парсятся одновременно, вместе
+// укладываются в общий --rps, а flood-wait у одной замедляет все.
+#[derive(Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+struct Bucket {
+    rps: f64,
+    // Может уйти в минус: столько запросов уже ждут своей очереди.
+    tokens: f64,
+    last: Instant,
+    adaptive: bool,
+    // Успешные запросы с последнего изменения скорости.
+    streak: u32,
+}
+
+impl RateLimiter {
+    // `rps == 0` - без ограничения.
+    pub fn new(rps: f64) -> Self {
+        Self::with_bucket(Bucket {
+            rps,
+            tokens: rps.max(1.0),
+            last: Instant::now(),
+            adaptive: false,
+            streak: 0,
+        })
+    }
+
+    // Скорость подбирается сама: растёт понемногу, пока нет flood-wait, и
+    // падает вдвое, когда он случается (AIMD, как у TCP). Так она сходится
+    // к пределу аккаунта без ручной настройки. `rps` - начальная скорость.
+    pub fn adaptive(rps: f64) -> Self {
+        let rps = if rps > 0.0 { rps } else { ADAPTIVE_START_RPS };
+        Self::with_bucket(Bucket {
+            rps,
+            tokens: rps.max(1.0),
+            last: Instant::now(),
+            adaptive: true,
+            streak: 0,
+        })
+    }
+
+    fn with_bucket(bucket: Bucket) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(bucket)),
+        }
+    }
+
+    fn bucket(&self) -> MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap()
+    }
+
+    pub fn rps(&self) -> f64 {
+        self.bucket().rps
+    }
+
+    pub fn on_success(&mut self) {
+        let mut bucket = self.bucket();
+        if !bucket.adaptive {
+            return;
+        }
+        bucket.streak += 1;
+        if bucket.streak >= ADAPTIVE_STREAK {
+            bucket.streak = 0;
+            bucket.rps = (bucket.rps + ADAPTIVE_STEP_RPS).min(ADAPTIVE_MAX_RPS);
+        }
+    }
+
+    pub fn on_flood_wait(&mut self) {
+        let mut bucket = self.bucket();
+        if !bucket.adaptive {
+            return;
+        }
+        bucket.streak = 0;
+        bucket.rps = (bucket.rps / 2.0).max(ADAPTIVE_MIN_RPS);
+        bucket.tokens = 0.0;
+    }
+
+    // Ждёт, пока в ведре появится токен, и забирает его. Токен занимается сразу,
+    // а ждём уже без блокировки, так что одновременные запросы встают в очередь.
+    pub async fn acquire(&mut self) {
+        let wait = {
+            let mut bucket = self.bucket();
+            if bucket.rps <= 0.0 {
+                return;
+            }
+
+            let now = Instant::now();
+            let refill = now.duration_since(bucket.last).as_secs_f64() * bucket.rps;
+            bucket.tokens = (bucket.tokens + refill).min(bucket.rps.max(1.0));
+            bucket.last = now;
+            bucket.tokens -= 1.0;
+            Duration::from_secs_f64((-bucket.tokens).max(0.0) / bucket.rps)
+        };
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Скорость прибавляет шаг после ADAPTIVE_STREAK запросов подряд без
+    // flood-wait и падает вдвое на flood-wait, который сбрасывает и серию.
+    #[test]
+    fn adaptive() {
+        let mut limiter = RateLimiter::adaptive(4.0);
+        for _ in 1..ADAPTIVE_STREAK {
+            limiter.on_success();
+        }
+        assert_eq!(limiter.rps(), 4.0);
+        limiter.on_success();
+        assert_eq!(limiter.rps(), 4.5);
+        limiter.on_flood_wait();
+        assert_eq!(limiter.rps(), 2.25);
+        for _ in 1..ADAPTIVE_STREAK {
+            limiter.on_success();
+        }
+        limiter.on_flood_wait();
+        limiter.on_success();
+        assert_eq!(limiter.rps(), 1.125);
+    }
+
+    #[test]
+    fn adaptive_bounds() {
+        assert_eq!(RateLimiter::adaptive(0.0).rps(), ADAPTIVE_START_RPS);
+        let mut limiter = RateLimiter::adaptive(1.0);
+        for _ in 0..10 {
+            limiter.on_flood_wait();
+        }
+        assert_eq!(limiter.rps(), ADAPTIVE_MIN_RPS);
+        let mut limiter = RateLimiter::adaptive(ADAPTIVE_MAX_RPS);
+        for _ in 0..ADAPTIVE_STREAK {
+            limiter.on_success();
+        }
+        assert_eq!(limiter.rps(), ADAPTIVE_MAX_RPS);
+    }
+
+    // Без --adaptive скорость не меняется, а клоны делят одно ведро.
+    #[test]
+    fn fixed_and_shared() {
+        let mut limiter = RateLimiter::new(3.0);
+        limiter.on_flood_wait();
+        assert_eq!(limiter.rps(), 3.0);
+        let mut adaptive = RateLimiter::adaptive(4.0);
+        let shared = adaptive.clone();
+        adaptive.on_flood_wait();
+        assert_eq!(shared.rps(), 2.0);
+    }
+
+    // Полное ведро отдаёт секунду запросов сразу, следующий ждёт своего токена.
+    #[tokio::test]
+    async fn acquire() {
+        let mut limiter = RateLimiter::new(20.0);
+        let start = Instant::now();
+        for _ in 0..20 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(40));
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
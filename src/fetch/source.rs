@@ -0,0 +1,161 @@
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::{Client, InvocationError, RpcError};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+// Откуда берутся подарки по slug. Обычно это Telegram, но парсинг и экспорт
+// можно прогнать и на подарках из памяти - так устроены тесты всего конвейера.
+pub trait GiftSource {
+    // Подарок со slug вида «PlushPepe-1». Несуществующий - ошибка, у которой
+    // `is_slug_invalid()`, как у Telegram.
+    fn gift(&self, slug: &str) -> impl Future<Output = Result<UniqueStarGift, InvocationError>>;
+
+    // Сколько запросов подарков держать в полёте одновременно.
+    fn parallel_requests(&self) -> usize {
+        1
+    }
+}
+
+impl GiftSource for Client {
+    async fn gift(&self, slug: &str) -> Result<UniqueStarGift, InvocationError> {
+        self.get_unique_star_gift(slug).await.map(Into::into)
+    }
+
+    // По запросу на каждое соединение, между которыми клиент их распределяет (--connections).
+    fn parallel_requests(&self) -> usize {
+        self.spread_connections()
+    }
+}
+
+// Подарки из памяти вместо Telegram: отдаёт заранее известные подарки по их slug.
+#[derive(Default)]
+pub struct MockSource {
+    gifts: HashMap<String, UniqueStarGift>,
+    // Сколько ещё раз подарок ответит ошибкой сервера вместо себя.
+    failures: Mutex<HashMap<String, usize>>,
+    // На этом подарке Telegram отвечает 401, как после отзыва сессии.
+    revoked_at: Option<String>,
+    // На этот подарок Telegram не отвечает (после ошибок из `failures`).
+    stalled: Option<String>,
+    // Сколько запросов принимается одновременно, как у клиента с --connections.
+    parallel: usize,
+}
+
+impl MockSource {
+    pub fn new(gifts: impl IntoIterator<Item = UniqueStarGift>) -> Self {
+        let gifts = gifts
+            .into_iter()
+            .filter_map(|gift| {
+                let UniqueStarGift::Gift(raw) = &gift;
+                match &raw.gift {
+                    tl::enums::StarGift::Unique(unique) => Some((unique.slug.clone(), gift)),
+                    tl::enums::StarGift::Gift(_) => None,
+                }
+            })
+            .collect();
+        Self {
+            gifts,
+            failures: Mutex::default(),
+            revoked_at: None,
+            stalled: None,
+            parallel: 1,
+        }
+    }
+
+    // Коллекция `collection` из подарков под номерами `numbers` (см. numbered_gift).
+    pub fn numbered(collection: &str, numbers: impl IntoIterator<Item = i32>) -> Self {
+        Self::new(
+            numbers
+                .into_iter()
+                .map(|num| numbered_gift(collection, num)),
+        )
+    }
+
+    // Первые `times` запросов подарка `slug` заканчиваются ошибкой 500, как при сбое Telegram.
+    pub fn failing(self, slug: &str, times: usize) -> Self {
+        self.failures
+            .lock()
+            .unwrap()
+            .insert(slug.to_string(), times);
+        self
+    }
+
+    // Запрос подарка `slug` заканчивается ошибкой 401, как если сессию отозвали.
+    pub fn revoked_at(mut self, slug: &str) -> Self {
+        self.revoked_at = Some(slug.to_string());
+        self
+    }
+
+    // Запрос подарка `slug` не получает ответа, как при зависшем соединении.
+    pub fn stalled(mut self, slug: &str) -> Self {
+        self.stalled = Some(slug.to_string());
+        self
+    }
+
+    // Запросы принимаются по `parallel` сразу.
+    pub fn parallel(mut self, parallel: usize) -> Self {
+        self.parallel = parallel;
+        self
+    }
+}
+
+impl GiftSource for MockSource {
+    async fn gift(&self, slug: &str) -> Result<UniqueStarGift, InvocationError> {
+        if self.revoked_at.as_deref() == Some(slug) {
+            return Err(rpc_error(401, "AUTH_KEY_UNREGISTERED"));
+        }
+        if let Some(times) = self.failures.lock().unwrap().get_mut(slug)
+            && *times > 0
+        {
+            *times -= 1;
+            return Err(rpc_error(500, "INTERNAL"));
+        }
+        if self.stalled.as_deref() == Some(slug) {
+            std::future::pending::<()>().await;
+        }
+        self.gifts
+            .get(slug)
+            .cloned()
+            .ok_or_else(|| rpc_error(400, "STAR_GIFT_SLUG_INVALID"))
+    }
+
+    fn parallel_requests(&self) -> usize {
+        self.parallel
+    }
+}
+
+fn rpc_error(code: i32, name: &str) -> InvocationError {
+    InvocationError::Rpc(RpcError {
+        code,
+        name: name.to_string(),
+        value: None,
+        caused_by: None,
+    })
+}
+
+// Подарок `collection`-`num` без владельца и атрибутов: для проверок, которым
+// важны только номера.
+pub fn numbered_gift(collection: &str, num: i32) -> UniqueStarGift {
+    tl::types::payments::UniqueStarGift {
+        gift: tl::types::StarGiftUnique {
+            id: num as i64,
+            title: collection.to_string(),
+            slug: format!("{collection}-{num}"),
+            num,
+            owner_id: None,
+            owner_name: None,
+            owner_address: None,
+            attributes: Vec::new(),
+            availability_issued: num,
+            availability_total: num,
+            gift_address: None,
+            resell_stars: None,
+            released_by: None,
+        }
+        .into(),
+        users: Vec::new(),
+    }
+    .into()
+}
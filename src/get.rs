@@ -0,0 +1,174 @@
+use crate::Result;
+use grammers_client::Client;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::types::GiftAttribute;
+use parser_nft::error::GiftContext as _;
+use parser_nft::model::owners::peer_name;
+use parser_nft::model::palette::Palette;
+use parser_nft::model::ton::explorer_url;
+use parser_nft::report::json_string;
+
+// Достаёт slug из «PlushPepe-1234» или ссылки «https://t.me/nft/PlushPepe-1234».
+pub fn parse_slug(input: &str) -> &str {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+    let input = input.strip_prefix("t.me/nft/").unwrap_or(input);
+    let input = input.split(['?', '#']).next().unwrap_or(input);
+    input.trim_end_matches('/')
+}
+
+// Загружает один подарок и печатает его целиком - текстом или в JSON.
+pub async fn get(client: &Client, input: &str, json: bool) -> Result<()> {
+    let slug = parse_slug(input);
+    let gift = client.get_unique_star_gift(slug).await.gift(slug)?;
+    if json {
+        print!("{}", to_json(&gift.raw, &gift.users));
+    } else {
+        print_human(&gift.raw, &gift.users);
+    }
+    Ok(())
+}
+
+fn print_human(info: &tl::types::StarGiftUnique, users: &[tl::enums::User]) {
+    println!("{} #{}", info.title, info.num);
+    println!("Ссылка: https://t.me/nft/{}", info.slug);
+    println!(
+        "Выпущено: {} из {}",
+        info.availability_issued, info.availability_total
+    );
+    match &info.owner_id {
+        Some(peer) => println!("Владелец: {}", peer_name(peer, users)),
+        None => match &info.owner_name {
+            Some(name) => println!("Владелец: {name}"),
+            None => println!("Владелец: скрыт"),
+        },
+    }
+    if let Some(address) = &info.owner_address {
+        println!("TON-адрес владельца: {address} ({})", explorer_url(address));
+    }
+    if let Some(address) = &info.gift_address {
+        println!("TON-адрес подарка: {address} ({})", explorer_url(address));
+    }
+    if let Some(stars) = info.resell_stars {
+        println!("Цена перепродажи: {stars} ⭐");
+    }
+
+    for attribute in info.attributes.iter().map(GiftAttribute::from) {
+        match attribute {
+            GiftAttribute::Model(model) => {
+                println!("Модель: {} ({})", model.name, rarity(model.rarity_permille));
+            }
+            GiftAttribute::Pattern(pattern) => {
+                println!(
+                    "Узор: {} ({})",
+                    pattern.name,
+                    rarity(pattern.rarity_permille)
+                );
+            }
+            GiftAttribute::Backdrop(backdrop) => {
+                let palette = Palette::from_backdrop(&backdrop);
+                println!(
+                    "Фон: {} ({}) центр {}, край {}, узор {}, текст {}",
+                    backdrop.name,
+                    rarity(backdrop.rarity_permille),
+                    palette.center_color,
+                    palette.edge_color,
+                    palette.pattern_color,
+                    palette.text_color
+                );
+            }
+            GiftAttribute::OriginalDetails(details) => {
+                println!("Исходный подарок:");
+                if let Some(sender) = &details.sender {
+                    println!("  от: {}", peer_name(sender, users));
+                }
+                println!("  кому: {}", peer_name(&details.recipient, users));
+                println!("  дата: {}", details.date.to_rfc3339());
+                if let Some(message) = &details.message {
+                    println!("  сообщение: {message}");
+                }
+            }
+        }
+    }
+}
+
+fn to_json(info: &tl::types::StarGiftUnique, users: &[tl::enums::User]) -> String {
+    let mut fields = vec![
+        format!("\"title\": {}", json_string(&info.title)),
+        format!("\"slug\": {}", json_string(&info.slug)),
+        format!("\"num\": {}", info.num),
+        format!("\"availability_issued\": {}", info.availability_issued),
+        format!("\"availability_total\": {}", info.availability_total),
+        format!(
+            "\"owner\": {}",
+            match &info.owner_id {
+                Some(peer) => json_string(&peer_name(peer, users)),
+                None => optional(info.owner_name.as_deref()),
+            }
+        ),
+        format!(
+            "\"owner_address\": {}",
+            optional(info.owner_address.as_deref())
+        ),
+        format!(
+            "\"gift_address\": {}",
+            optional(info.gift_address.as_deref())
+        ),
+        format!(
+            "\"resell_stars\": {}",
+            info.resell_stars
+                .map(|stars| stars.to_string())
+                .unwrap_or("null".to_string())
+        ),
+    ];
+
+    for attribute in info.attributes.iter().map(GiftAttribute::from) {
+        fields.push(match attribute {
+            GiftAttribute::Model(model) => format!(
+                "\"model\": {{\"name\": {}, \"rarity_permille\": {}}}",
+                json_string(&model.name),
+                model.rarity_permille
+            ),
+            GiftAttribute::Pattern(pattern) => format!(
+                "\"pattern\": {{\"name\": {}, \"rarity_permille\": {}}}",
+                json_string(&pattern.name),
+                pattern.rarity_permille
+            ),
+            GiftAttribute::Backdrop(backdrop) => {
+                let palette = Palette::from_backdrop(&backdrop);
+                format!(
+                    "\"backdrop\": {{\"name\": {}, \"rarity_permille\": {}, \"center_color\": \"{}\", \"edge_color\": \"{}\", \"pattern_color\": \"{}\", \"text_color\": \"{}\"}}",
+                    json_string(&backdrop.name),
+                    backdrop.rarity_permille,
+                    palette.center_color,
+                    palette.edge_color,
+                    palette.pattern_color,
+                    palette.text_color
+                )
+            }
+            GiftAttribute::OriginalDetails(details) => format!(
+                "\"original_details\": {{\"sender\": {}, \"recipient\": {}, \"date\": \"{}\", \"message\": {}}}",
+                match &details.sender {
+                    Some(peer) => json_string(&peer_name(peer, users)),
+                    None => "null".to_string(),
+                },
+                json_string(&peer_name(&details.recipient, users)),
+                details.date.to_rfc3339(),
+                optional(details.message.as_deref())
+            ),
+        });
+    }
+
+    format!("{{\n  {}\n}}\n", fields.join(",\n  "))
+}
+
+fn rarity(permille: i32) -> String {
+    format!("{:.1}%", permille as f64 / 10.0)
+}
+
+fn optional(value: Option<&str>) -> String {
+    value.map(json_string).unwrap_or("null".to_string())
+}
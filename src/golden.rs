@@ -0,0 +1,1102 @@
+// Эталонные тесты форматов вывода: небольшая выдуманная коллекция рендерится
+// в каждый формат и сравнивается с файлами из tests/golden. Так переделки
+// рендеров не могут незаметно поменять то, что разбирают потребители отчётов.
+//
+// Если формат меняется намеренно, эталоны перезаписываются командой
+//   UPDATE_GOLDEN=1 cargo test
+// и изменения в tests/golden попадают в коммит вместе с кодом.
+
+use crate::error::ParserError;
+use crate::export::html::{IndexEntry, write_html, write_index};
+use crate::export::lang::Lang;
+use crate::export::markdown::write_markdown;
+use crate::export::prices::write_prices;
+use crate::export::{Enrichment, Memory, Report, SplitBy, Theme, exporter, names, site};
+use crate::fetch::discover::highest_number;
+use crate::fetch::links::{message_slugs, slugs_in};
+use crate::fetch::rate::RateLimiter;
+use crate::fetch::source::MockSource;
+use crate::fetch::{Progress, Stop, checkpoint, fetch_gifts};
+use crate::model::provenance::hide_original_details;
+use crate::model::rarity::{self, RarityFormula};
+use crate::model::{FloorPrices, GiftRecord, Owner, Transfer};
+use crate::notify::Event;
+use crate::notify::rules::AlertRules;
+use crate::notify::telegram;
+use crate::notify::webhook::Webhook;
+use crate::report::RunInfo;
+use crate::report::diff::Changes;
+use crate::report::manifest::{CollectionRun, manifest_json};
+use crate::report::ownership::{OwnerHistory, add_snapshot};
+use crate::report::prices::Prices;
+use crate::report::quality::Quality;
+use crate::report::stats::Stats;
+use crate::report::timing::Performance;
+use crate::store;
+use chrono::DateTime;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn golden_dir(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(name)
+}
+
+// Сравнивает файлы с эталонами в tests/golden/<name>, а с UPDATE_GOLDEN - перезаписывает их.
+fn check(name: &str, files: Vec<(String, String)>) {
+    let dir = golden_dir(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (file, content) in &files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+        return;
+    }
+
+    let mut expected: Vec<String> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("нет эталонов в {}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    expected.sort();
+    let mut actual: Vec<String> = files.iter().map(|(file, _)| file.clone()).collect();
+    actual.sort();
+    assert_eq!(
+        actual, expected,
+        "набор файлов {name} отличается от эталона"
+    );
+
+    for (file, content) in files {
+        let golden = fs::read_to_string(dir.join(&file)).unwrap();
+        assert!(
+            content == golden,
+            "{name}/{file} отличается от эталона, запустите с UPDATE_GOLDEN=1, если так и задумано"
+        );
+    }
+}
+
+fn run_info() -> RunInfo {
+    RunInfo {
+        version: "0.0.0",
+        layer: 0,
+        started_at: DateTime::from_timestamp(1_750_000_000, 0).unwrap(),
+        timestamps: false,
+        params: vec![
+            ("collection", "PlushPepe".to_string()),
+            ("format", "html".to_string()),
+        ],
+    }
+}
+
+fn model(name: &str) -> tl::enums::StarGiftAttribute {
+    tl::types::StarGiftAttributeModel {
+        name: name.to_string(),
+        document: tl::types::DocumentEmpty { id: 1 }.into(),
+        rarity_permille: 15,
+    }
+    .into()
+}
+
+fn backdrop(name: &str, center_color: i32) -> tl::enums::StarGiftAttribute {
+    tl::types::StarGiftAttributeBackdrop {
+        name: name.to_string(),
+        backdrop_id: center_color,
+        center_color,
+        edge_color: 0x101010,
+        pattern_color: 0x202020,
+        text_color: 0xffffff,
+        rarity_permille: 20,
+    }
+    .into()
+}
+
+fn pattern(name: &str, rarity_permille: i32) -> tl::enums::StarGiftAttribute {
+    tl::types::StarGiftAttributePattern {
+        name: name.to_string(),
+        document: tl::types::DocumentEmpty { id: 2 }.into(),
+        rarity_permille,
+    }
+    .into()
+}
+
+// Подарок от анонима пользователю 1001, с сообщением, которое нужно экранировать.
+fn original_details() -> tl::enums::StarGiftAttribute {
+    tl::types::StarGiftAttributeOriginalDetails {
+        sender_id: None,
+        recipient_id: tl::types::PeerUser { user_id: 1001 }.into(),
+        date: 1_700_000_000,
+        message: Some(
+            tl::types::TextWithEntities {
+                text: "С днём рождения! <3 | *".to_string(),
+                entities: Vec::new(),
+            }
+            .into(),
+        ),
+    }
+    .into()
+}
+
+fn user(id: i64, first_name: &str, username: Option<&str>) -> tl::enums::User {
+    tl::types::User {
+        is_self: false,
+        contact: false,
+        mutual_contact: false,
+        deleted: false,
+        bot: false,
+        bot_chat_history: false,
+        bot_nochats: false,
+        verified: false,
+        restricted: false,
+        min: false,
+        bot_inline_geo: false,
+        support: false,
+        scam: false,
+        apply_min_photo: false,
+        fake: false,
+        bot_attach_menu: false,
+        premium: false,
+        attach_menu_enabled: false,
+        bot_can_edit: false,
+        close_friend: false,
+        stories_hidden: false,
+        stories_unavailable: false,
+        contact_require_premium: false,
+        bot_business: false,
+        bot_has_main_app: false,
+        id,
+        access_hash: Some(1),
+        first_name: Some(first_name.to_string()),
+        last_name: None,
+        username: username.map(str::to_string),
+        phone: None,
+        photo: None,
+        status: None,
+        bot_info_version: None,
+        restriction_reason: None,
+        bot_inline_placeholder: None,
+        lang_code: None,
+        emoji_status: None,
+        usernames: None,
+        stories_max_id: None,
+        color: None,
+        profile_color: None,
+        bot_active_users: None,
+        bot_verification_icon: None,
+        send_paid_messages_stars: None,
+    }
+    .into()
+}
+
+fn gift(
+    num: i32,
+    owner_id: Option<tl::enums::Peer>,
+    owner_name: Option<&str>,
+    owner_address: Option<&str>,
+    resell_stars: Option<i64>,
+    attributes: Vec<tl::enums::StarGiftAttribute>,
+    users: Vec<tl::enums::User>,
+) -> UniqueStarGift {
+    tl::types::payments::UniqueStarGift {
+        gift: tl::types::StarGiftUnique {
+            id: num as i64,
+            title: "Plush Pepe".to_string(),
+            slug: format!("PlushPepe-{num}"),
+            num,
+            owner_id,
+            owner_name: owner_name.map(str::to_string),
+            owner_address: owner_address.map(str::to_string),
+            attributes,
+            // Выпущено на один больше, чем в выборке, - чтобы в отчёте был пропуск.
+            availability_issued: 4,
+            availability_total: 5000,
+            // Выведенный в блокчейн подарок - тот, у которого известен кошелёк владельца.
+            gift_address: owner_address.map(|_| format!("EQGift{num}")),
+            resell_stars,
+            released_by: None,
+        }
+        .into(),
+        users,
+    }
+    .into()
+}
+
+// Три подарка: владелец-пользователь с именем, канал и скрытый владелец,
+// а в названии модели - символы, которые нужно экранировать. Первый выведен
+// в блокчейн TON и хранит исходные данные, второй выставлен на продажу.
+// У третьего нет узора, так что оценки редкости у него нет.
+fn fixture() -> Vec<UniqueStarGift> {
+    vec![
+        gift(
+            1,
+            Some(tl::types::PeerUser { user_id: 1001 }.into()),
+            Some("Alice | Bob"),
+            Some("UQAlice"),
+            None,
+            vec![
+                model("Gold"),
+                backdrop("Black", 0x000000),
+                pattern("Stars", 5),
+                original_details(),
+            ],
+            vec![user(1001, "Alice", Some("alice_bob"))],
+        ),
+        gift(
+            2,
+            Some(tl::types::PeerChannel { channel_id: 2002 }.into()),
+            None,
+            None,
+            Some(2500),
+            vec![
+                model("Gold"),
+                backdrop("Onyx", 0x333333),
+                pattern("Hearts", 40),
+            ],
+            Vec::new(),
+        ),
+        gift(
+            3,
+            None,
+            None,
+            None,
+            None,
+            vec![model("Cozy <Pepe> & Co"), backdrop("Black", 0x000000)],
+            Vec::new(),
+        ),
+    ]
+}
+
+fn records(gifts: Vec<UniqueStarGift>) -> Vec<GiftRecord> {
+    gifts.into_iter().map(GiftRecord::from).collect()
+}
+
+fn quality(gifts: &[UniqueStarGift]) -> Quality {
+    let mut quality = Quality::new(gifts);
+    quality
+        .failed_media
+        .push("превью модели Cozy <Pepe> & Co".to_string());
+    quality
+}
+
+#[test]
+fn html() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let report = Report {
+        name: "parsed",
+        enrichment: &Enrichment::default(),
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    write_html(&records(gifts), &report, &mut output).unwrap();
+    check("html", output.into_files().unwrap());
+}
+
+// Оглавление двух коллекций: полный снимок рядом с index.html и неполный
+// в папке коллекции, у которого нет ни одной оценки редкости.
+#[test]
+fn html_index() {
+    let gifts = fixture();
+    let mut partial = records(gifts.clone());
+    partial.truncate(1);
+    partial[0].rarity_score = None;
+    let entries = [
+        IndexEntry::new(
+            "PlushPepe",
+            "parsed.html".to_string(),
+            &records(gifts.clone()),
+            &Quality::default(),
+        ),
+        IndexEntry::new(
+            "Durov's <Cap>",
+            "DurovsCap/parsed.html".to_string(),
+            &partial,
+            &quality(&gifts),
+        ),
+    ];
+    let mut html = Vec::new();
+    write_index(
+        &entries.iter().collect::<Vec<_>>(),
+        Lang::Ru,
+        Theme::Light,
+        None,
+        &run_info(),
+        &mut html,
+    )
+    .unwrap();
+    check(
+        "html_index",
+        vec![("index.html".to_string(), String::from_utf8(html).unwrap())],
+    );
+}
+
+// manifest.json по двум коллекциям: неполный снимок с записанным отчётом
+// и коллекция, которая не спарсилась.
+// Цены перепродажи: у Gold два лота (медиана - среднее двух цен), у модели
+// Cozy <Pepe> & Co лотов нет, так что она в конце таблицы без цен.
+#[test]
+fn prices() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let report = Report {
+        name: "parsed",
+        enrichment: &Enrichment::default(),
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut records = records(gifts);
+    records[0].resale_price = Some(1500);
+    let mut output = Memory::default();
+    write_prices(
+        &Prices::new("PlushPepe", &records),
+        &report,
+        "prices",
+        &mut output,
+    )
+    .unwrap();
+    check("prices", output.into_files().unwrap());
+}
+
+#[test]
+fn manifest() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let mut parsed = CollectionRun::new("PlushPepe", run.started_at, &records(gifts), &quality);
+    parsed.finished_at = DateTime::from_timestamp(1_750_000_042, 500_000_000).unwrap();
+    parsed.files = vec![PathBuf::from("out/parsed.html")];
+    parsed.performance = Some(Performance {
+        requests: 5,
+        p50: Duration::from_millis(120),
+        p95: Duration::from_millis(480),
+        elapsed: Duration::from_millis(2_500),
+        flood_waits: 1,
+    });
+    let mut failed =
+        CollectionRun::failed("DurovsCap", run.started_at, "FLOOD_WAIT (30)".to_string());
+    failed.finished_at = DateTime::from_timestamp(1_750_000_001, 0).unwrap();
+    let json = manifest_json(
+        &run,
+        DateTime::from_timestamp(1_750_000_043, 0).unwrap(),
+        &[parsed, failed],
+    );
+    check("manifest", vec![("manifest.json".to_string(), json)]);
+}
+
+// Сайт из одной коллекции: корень и папка коллекции со страницами подарков.
+#[test]
+fn site() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let records = records(gifts);
+    // История владельцев: в прошлом снимке второй подарок был у другого владельца.
+    let mut before = records.clone();
+    before[1].owner = Owner::Channel {
+        id: 3003,
+        name: Some("Old channel".to_string()),
+        username: None,
+    };
+    let mut history = OwnerHistory::default();
+    add_snapshot(
+        &mut history,
+        DateTime::from_timestamp(1_740_000_000, 0).unwrap(),
+        &before,
+    );
+    add_snapshot(&mut history, run.started_at, &records);
+    let enrichment = Enrichment {
+        owner_history: Some(history),
+        ..Default::default()
+    };
+    let report = Report {
+        name: "parsed",
+        enrichment: &enrichment,
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut collection = Memory::default();
+    site::write_collection(&records, &report, &mut collection).unwrap();
+    check("site_collection", collection.into_files().unwrap());
+
+    let entry = IndexEntry::new(
+        "PlushPepe",
+        "PlushPepe/index.html".to_string(),
+        &records,
+        &quality,
+    );
+    let mut root = Memory::default();
+    site::write_root(&[&entry], Lang::Ru, Theme::Light, None, &run, &mut root).unwrap();
+    check("site", root.into_files().unwrap());
+}
+
+// Подписи по-английски (с разбиением на страницы, чтобы попали и они),
+// автоматическая тема, свои стили из --css и время запуска (--timestamps).
+#[test]
+fn html_english() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let mut run = run_info();
+    run.timestamps = true;
+    let enrichment = Enrichment {
+        floor_prices: Some(floor_prices()),
+        ..Default::default()
+    };
+    let report = Report {
+        name: "parsed",
+        enrichment: &enrichment,
+        page_size: 2,
+        split_by: None,
+        lang: Lang::En,
+        theme: Theme::Auto,
+        css: Some(".gift-item { border: 1px solid gold; }"),
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    write_html(&records(gifts), &report, &mut output).unwrap();
+    check("html_en", output.into_files().unwrap());
+}
+
+fn floor_prices() -> FloorPrices {
+    HashMap::from([(("Gold".to_string(), "Black".to_string()), 1900)])
+}
+
+#[test]
+fn html_paged_split_with_artwork() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let enrichment = Enrichment {
+        artwork: Some(HashMap::from([(
+            "Gold".to_string(),
+            "parsed_art/1.webp".to_string(),
+        )])),
+        floor_prices: Some(floor_prices()),
+        ..Default::default()
+    };
+    let run = run_info();
+    let report = Report {
+        name: "parsed",
+        enrichment: &enrichment,
+        page_size: 1,
+        split_by: Some(SplitBy::Model),
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    write_html(&records(gifts), &report, &mut output).unwrap();
+    check("html_split", output.into_files().unwrap());
+}
+
+fn markdown_files(
+    gifts: Vec<UniqueStarGift>,
+    floor_prices: Option<&FloorPrices>,
+    quality: &Quality,
+) -> Vec<(String, String)> {
+    let mut markdown = Vec::new();
+    write_markdown(
+        &records(gifts),
+        floor_prices,
+        &run_info(),
+        quality,
+        &mut markdown,
+    )
+    .unwrap();
+    vec![(
+        "parsed.md".to_string(),
+        String::from_utf8(markdown).unwrap(),
+    )]
+}
+
+#[test]
+fn markdown() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    check("markdown", markdown_files(gifts, None, &quality));
+}
+
+#[test]
+fn markdown_with_floor_prices() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    check(
+        "markdown_floor",
+        markdown_files(gifts, Some(&floor_prices()), &quality),
+    );
+}
+
+#[test]
+fn markdown_hidden_original_details() {
+    let mut gifts = fixture();
+    hide_original_details(&mut gifts);
+    let quality = quality(&gifts);
+    check("markdown_private", markdown_files(gifts, None, &quality));
+}
+
+// Подарки из профиля канала (`owner @channel`): у публичного канала в отчётах
+// название, username и ссылка на канал, у закрытого - только название.
+#[test]
+fn channel_owner() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let mut records = records(gifts);
+    records[0].owner = Owner::Channel {
+        id: 2002,
+        name: Some("Pepe | News".to_string()),
+        username: Some("pepe_news".to_string()),
+    };
+    records[2].owner = Owner::Channel {
+        id: 3003,
+        name: Some("Private channel".to_string()),
+        username: None,
+    };
+    let report = Report {
+        name: "parsed",
+        enrichment: &Enrichment::default(),
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    write_html(&records, &report, &mut output).unwrap();
+    let mut files = output.into_files().unwrap();
+    let mut markdown = Vec::new();
+    write_markdown(&records, None, &run, &quality, &mut markdown).unwrap();
+    files.push((
+        "parsed.md".to_string(),
+        String::from_utf8(markdown).unwrap(),
+    ));
+    check("channel_owner", files);
+}
+
+// Условия передачи из своего профиля: платная передача, закрытая до даты, и
+// бесплатная; у третьего подарка условия неизвестны.
+#[test]
+fn transfer_terms() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let run = run_info();
+    let mut records = records(gifts);
+    let date = |timestamp| DateTime::from_timestamp(timestamp, 0).unwrap();
+    let locked = Transfer {
+        stars: Some(25),
+        transfer_after: Some(date(1_751_000_000)),
+        export_after: Some(date(1_752_000_000)),
+    };
+    assert!(!locked.is_open(run.started_at));
+    assert!(locked.is_open(date(1_751_000_000)));
+    assert!(Transfer::default().is_open(run.started_at));
+    records[0].transfer = Some(locked);
+    records[1].transfer = Some(Transfer::default());
+
+    let report = Report {
+        name: "parsed",
+        enrichment: &Enrichment::default(),
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    for format in ["html", "markdown", "json", "csv"] {
+        exporter(format, &report)
+            .unwrap()
+            .write_files(&records, &mut output)
+            .unwrap();
+    }
+    check("transfer", output.into_files().unwrap());
+}
+
+// Файлы отчёта в формате `name`, полученные через реестр, как это делает --format.
+fn export(
+    name: &str,
+    gifts: Vec<UniqueStarGift>,
+    enrichment: &Enrichment,
+) -> Vec<(String, String)> {
+    let quality = quality(&gifts);
+    let run = run_info();
+    let report = Report {
+        name: "parsed",
+        enrichment,
+        page_size: 0,
+        split_by: None,
+        lang: Lang::Ru,
+        theme: Theme::Light,
+        css: None,
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let mut output = Memory::default();
+    exporter(name, &report)
+        .unwrap()
+        .write_files(&records(gifts), &mut output)
+        .unwrap();
+    output.into_files().unwrap()
+}
+
+// Без --timestamps отчёт любого формата не зависит от времени запуска.
+#[test]
+fn reproducible() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let earlier = run_info();
+    let mut later = run_info();
+    later.started_at = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+    for name in names() {
+        let files: Vec<_> = [&earlier, &later]
+            .into_iter()
+            .map(|run| {
+                let report = Report {
+                    name: "parsed",
+                    enrichment: &Enrichment::default(),
+                    page_size: 2,
+                    split_by: None,
+                    lang: Lang::Ru,
+                    theme: Theme::Light,
+                    css: None,
+                    detail_pages: false,
+                    run,
+                    quality: &quality,
+                };
+                let mut output = Memory::default();
+                exporter(name, &report)
+                    .unwrap()
+                    .write_files(&records(gifts.clone()), &mut output)
+                    .unwrap();
+                output.into_files().unwrap()
+            })
+            .collect();
+        assert_eq!(files[0], files[1], "{name} зависит от времени запуска");
+    }
+}
+
+fn json_enrichment() -> Enrichment {
+    Enrichment {
+        floor_prices: Some(floor_prices()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn json() {
+    check("json", export("json", fixture(), &json_enrichment()));
+}
+
+#[test]
+fn ndjson() {
+    check("ndjson", export("ndjson", fixture(), &json_enrichment()));
+}
+
+#[test]
+fn csv() {
+    check("csv", export("csv", fixture(), &Enrichment::default()));
+}
+
+// fetch_gifts без ограничения скорости, прерывания и --stream.
+async fn fetch_all(source: &MockSource, progress: &mut Progress) -> Stop {
+    fetch_gifts(
+        source,
+        progress,
+        &mut RateLimiter::new(0.0),
+        false,
+        &mut std::future::pending::<()>(),
+        &mut |_: &UniqueStarGift| {},
+    )
+    .await
+    .unwrap()
+}
+
+// --update: из снимка берутся подарки до окна, остальные загружаются заново.
+// Первый подарок источник отдать не может - значит, его и не запрашивают.
+#[tokio::test]
+async fn update_from_snapshot() {
+    let stored = fixture().into_iter().take(2).collect();
+    let mut progress = Progress::update("PlushPepe", stored, 1);
+    assert_eq!(progress.next, 2);
+    progress.highest = 3;
+    let source = MockSource::new(fixture()).failing("PlushPepe-1", 2);
+    let stop = fetch_all(&source, &mut progress).await;
+    assert!(matches!(stop, Stop::Done));
+    assert!(progress.failed.is_empty());
+    let slugs: Vec<_> = records(progress.gifts)
+        .into_iter()
+        .map(|gift| gift.slug)
+        .collect();
+    assert_eq!(slugs, ["PlushPepe-1", "PlushPepe-2", "PlushPepe-3"]);
+}
+
+// Отбор и порядок по оценке редкости, которые отчёт получает с
+// --rarity-formula, --min-rarity и --sort-by-rarity.
+#[test]
+fn rarity_sort_and_filter() {
+    let slugs = |gifts: &[GiftRecord]| -> Vec<String> {
+        gifts.iter().map(|gift| gift.slug.clone()).collect()
+    };
+    let scores = |gifts: &[GiftRecord]| -> Vec<Option<String>> {
+        gifts
+            .iter()
+            .map(|gift| gift.rarity_score.map(|score| format!("{score:.0}")))
+            .collect()
+    };
+
+    let mut gifts = records(fixture());
+    gifts.reverse();
+    rarity::select(&mut gifts, None, true);
+    assert_eq!(slugs(&gifts), ["PlushPepe-1", "PlushPepe-2", "PlushPepe-3"]);
+    assert_eq!(
+        scores(&gifts),
+        [Some("666667".to_string()), Some("83333".to_string()), None]
+    );
+
+    rarity::rescore(&mut gifts, RarityFormula::Min);
+    assert_eq!(
+        scores(&gifts),
+        [Some("200".to_string()), Some("67".to_string()), None]
+    );
+    rarity::rescore(&mut gifts, RarityFormula::Weighted);
+    assert_eq!(
+        scores(&gifts),
+        [Some("76".to_string()), Some("50".to_string()), None]
+    );
+
+    // Подарок без оценки под фильтр не попадает.
+    rarity::rescore(&mut gifts, RarityFormula::Product);
+    rarity::select(&mut gifts, Some(100_000.0), false);
+    assert_eq!(slugs(&gifts), ["PlushPepe-1"]);
+}
+
+// Ошибка поиска последнего номера называет подарок, на котором она случилась.
+#[tokio::test]
+async fn discover_error_names_gift() {
+    let source = MockSource::new(fixture()).failing("PlushPepe-2", 1);
+    let e = highest_number(&source, "PlushPepe", &mut RateLimiter::new(0.0))
+        .await
+        .unwrap_err();
+    assert!(matches!(&e, ParserError::Gift { gift, .. } if gift == "PlushPepe-2"));
+    assert!(e.to_string().starts_with("PlushPepe-2: "));
+}
+
+// Весь конвейер без Telegram: последний номер и сами подарки берутся из
+// MockSource, и отчёт должен совпасть с тем, что строится из тех же подарков напрямую.
+#[tokio::test]
+async fn json_from_source() {
+    // С несколькими соединениями запросы идут параллельно, но подарки
+    // обрабатываются и сохраняются в том же порядке.
+    for parallel in [1, 3] {
+        let source = MockSource::new(fixture()).parallel(parallel);
+        let mut limiter = RateLimiter::new(0.0);
+        let highest = highest_number(&source, "PlushPepe", &mut limiter)
+            .await
+            .unwrap();
+        let mut progress = Progress::new("PlushPepe", highest as usize);
+        let mut interrupt = std::future::pending::<()>();
+        let mut streamed = Vec::new();
+        let mut on_gift =
+            |gift: &UniqueStarGift| streamed.push(GiftRecord::from(gift.clone()).slug);
+        let stop = fetch_gifts(
+            &source,
+            &mut progress,
+            &mut limiter,
+            false,
+            &mut interrupt,
+            &mut on_gift,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(stop, Stop::Done));
+        // --stream получает каждый подарок по порядку, как только он загружен.
+        let slugs: Vec<_> = progress
+            .gifts
+            .iter()
+            .map(|gift| GiftRecord::from(gift.clone()).slug)
+            .collect();
+        assert_eq!(streamed, slugs);
+        check("json", export("json", progress.gifts, &json_enrichment()));
+    }
+}
+
+// Отозванная сессия останавливает парсинг сразу, а не откладывает подарок на потом:
+// после нового входа он продолжится с того же номера.
+#[tokio::test]
+async fn session_revoked() {
+    let source = MockSource::new(fixture()).revoked_at("PlushPepe-2");
+    let mut progress = Progress::new("PlushPepe", 3);
+    let stop = fetch_all(&source, &mut progress).await;
+    assert!(matches!(stop, Stop::SessionRevoked(_)));
+    assert_eq!(progress.next, 2);
+    assert_eq!(progress.gifts.len(), 1);
+    assert!(progress.retry.is_empty());
+}
+
+// Сбой Telegram на одном подарке не останавливает парсинг: номер пробуется ещё раз
+// в конце, а что не получилось и тогда - попадает в раздел качества.
+#[tokio::test]
+async fn retry_failed_gifts() {
+    let source = MockSource::new(fixture())
+        .failing("PlushPepe-1", 1)
+        .failing("PlushPepe-2", 2);
+    let mut progress = Progress::new("PlushPepe", 3);
+    let stop = fetch_all(&source, &mut progress).await;
+    assert!(matches!(stop, Stop::Done));
+    assert!(progress.retry.is_empty());
+    let slugs: Vec<_> = records(progress.gifts.clone())
+        .into_iter()
+        .map(|gift| gift.slug)
+        .collect();
+    assert_eq!(slugs, ["PlushPepe-1", "PlushPepe-3"]);
+    assert_eq!(
+        progress.failed,
+        ["PlushPepe-2: request error: rpc error 500: INTERNAL"]
+    );
+
+    let mut quality = Quality::new(&progress.gifts);
+    quality.failed_gifts = progress.failed;
+    assert!(!quality.is_complete());
+    // Выпущено четыре: кроме не полученного второго нет и четвёртого.
+    assert_eq!(quality.missing, [2, 4]);
+    let lines = quality.lines();
+    assert!(lines.contains(&"Пропущены номера (2): 2, 4".to_string()));
+    assert!(lines.contains(
+        &"Не получены подарки (1): PlushPepe-2: request error: rpc error 500: INTERNAL".to_string()
+    ));
+}
+
+// Ctrl+C во время второй попытки не теряет отложенные номера: они попадают в
+// файл состояния, и следующий запуск запрашивает их снова, а не считает
+// коллекцию полной.
+#[tokio::test]
+async fn resume_pending_retries() {
+    let source = MockSource::new(fixture())
+        .failing("PlushPepe-2", 1)
+        .stalled("PlushPepe-2");
+    let mut progress = Progress::new("PlushPepe", 3);
+    // Ответы мока приходят сразу, так что к прерыванию парсинг уже ждёт вторую попытку.
+    let mut interrupt = Box::pin(tokio::time::sleep(Duration::from_millis(100)));
+    let stop = fetch_gifts(
+        &source,
+        &mut progress,
+        &mut RateLimiter::new(0.0),
+        false,
+        &mut interrupt,
+        &mut |_: &UniqueStarGift| {},
+    )
+    .await
+    .unwrap();
+    assert!(matches!(stop, Stop::Interrupted(slug) if slug == "PlushPepe-2"));
+    assert_eq!(progress.next, 4);
+    assert_eq!(progress.retry, [2]);
+
+    let dir = std::env::temp_dir().join(format!("rustfind-resume-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    progress.state_dir = dir.clone();
+    checkpoint::save(&progress).unwrap();
+    let mut resumed = Progress::resume(&dir, "PlushPepe");
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(resumed.next, 4);
+    assert_eq!(resumed.retry, [2]);
+
+    resumed.highest = 3;
+    let stop = fetch_all(&MockSource::new(fixture()), &mut resumed).await;
+    assert!(matches!(stop, Stop::Done));
+    assert!(resumed.retry.is_empty() && resumed.failed.is_empty());
+    check("json", export("json", resumed.gifts, &json_enrichment()));
+}
+
+// События вебхуков: прошлый снимок без третьего подарка и с другим владельцем второго.
+#[test]
+fn webhook() {
+    let gifts = fixture();
+    let quality = quality(&gifts);
+    let after = records(gifts);
+    let mut before = after.clone();
+    before.pop();
+    before[1].owner = Owner::Channel {
+        id: 3003,
+        name: Some("Old channel".to_string()),
+        username: None,
+    };
+    before[1].resale_price = None;
+    let changes = Changes::new(&before, &after);
+    let run = run_info();
+    let stats = Stats::new(&after);
+    let mut events = vec![Event::RunCompleted {
+        collection: "PlushPepe",
+        run: &run,
+        quality: &quality,
+        stats: &stats,
+    }];
+    events.extend(Event::from_changes("PlushPepe", &changes));
+
+    let webhook = Webhook::new("http://localhost/hook", Some("secret".to_string()));
+    let mut files = Vec::new();
+    for event in &events {
+        let body = event.to_json();
+        let signature = webhook.signature(&body).unwrap();
+        files.push((format!("{}.json", event.name()), body));
+        files.push((format!("{}.sig", event.name()), signature + "\n"));
+    }
+    check("webhook", files);
+
+    let messages = telegram::alert_messages("PlushPepe", &changes, &AlertRules::default());
+    assert_eq!(messages.len(), 1);
+    check(
+        "telegram_alert",
+        vec![("alert.txt".to_string(), messages.concat() + "\n")],
+    );
+}
+
+// Правила --alert-rules: первый подарок не подходит ни под одно, второй - под
+// условия через and, третий - под название модели в кавычках. В оповещение
+// попадает только новый третий подарок.
+#[test]
+fn alert_rules() {
+    let rules = AlertRules::parse(
+        "# только интересное\n\
+         rarity < 0.0001% or model == 'cozy <pepe> & co'\n\
+         backdrop == Onyx and pattern >= 4%\n",
+    )
+    .unwrap();
+    let after = records(fixture());
+    let matched: Vec<bool> = after.iter().map(|gift| rules.matches(gift)).collect();
+    assert_eq!(matched, [false, true, true]);
+
+    let mut before = after.clone();
+    before.pop();
+    let changes = Changes::new(&before, &after);
+    let messages = telegram::alert_messages("PlushPepe", &changes, &rules);
+    check(
+        "telegram_alert_rules",
+        vec![("alert.txt".to_string(), messages.concat() + "\n")],
+    );
+}
+
+// Ссылки на подарки в анонсе канала: с параметрами и знаками препинания после
+// ссылки, через telegram.me; ссылка на коллекцию без номера и чужие ссылки пропускаются.
+#[test]
+fn feed_links() {
+    let text = "🎉 Улучшены: https://t.me/nft/PlushPepe-12, t.me/nft/DurovsCap-3?startapp=x\n\
+                и https://telegram.me/nft/PlushPepe-7.\n\
+                Коллекция: https://t.me/nft/PlushPepe, канал: https://t.me/gifts";
+    assert_eq!(
+        slugs_in(text),
+        ["PlushPepe-12", "DurovsCap-3", "PlushPepe-7"]
+    );
+}
+
+// Служебное сообщение о переданном подарке в истории чата (scan-chat): slug
+// берётся из самого подарка, без ссылок в тексте.
+#[test]
+fn scan_chat_service_message() {
+    let UniqueStarGift::Gift(gift) = fixture().remove(1);
+    let message: tl::enums::Message = tl::types::MessageService {
+        out: false,
+        mentioned: false,
+        media_unread: false,
+        reactions_are_possible: false,
+        silent: false,
+        post: false,
+        legacy: false,
+        id: 1,
+        from_id: None,
+        peer_id: tl::types::PeerUser { user_id: 1001 }.into(),
+        saved_peer_id: None,
+        reply_to: None,
+        date: 0,
+        action: tl::types::MessageActionStarGiftUnique {
+            upgrade: false,
+            transferred: true,
+            saved: false,
+            refunded: false,
+            gift: gift.gift,
+            can_export_at: None,
+            transfer_stars: None,
+            from_id: None,
+            peer: None,
+            saved_id: None,
+            resale_stars: None,
+            can_transfer_at: None,
+            can_resell_at: None,
+        }
+        .into(),
+        reactions: None,
+        ttl_period: None,
+    }
+    .into();
+    assert_eq!(message_slugs(&message), ["PlushPepe-2"]);
+}
+
+// Две пересекающиеся части коллекции: в старой у второго подарка другой владелец
+// и нет цены, в новой он актуальный. После слияния - та же коллекция, что целиком.
+#[test]
+fn merge() {
+    let mut old = fixture();
+    old.truncate(2);
+    old[1] = gift(
+        2,
+        Some(tl::types::PeerUser { user_id: 1001 }.into()),
+        None,
+        None,
+        None,
+        vec![model("Gold"), backdrop("Onyx", 0x333333)],
+        Vec::new(),
+    );
+    let new = fixture().split_off(1);
+    let merged = store::merge([new.clone(), old.clone()]);
+    assert_eq!(merged.len(), 3);
+    let merged = store::merge([old, new]);
+    check("json", export("json", merged, &json_enrichment()));
+}
+
+// Чей подарок новее, merge решает по времени снимка из имени файла, а не по
+// времени записи: файлы копируют и скачивают из CI.
+#[test]
+fn snapshot_time() {
+    let taken_at = store::snapshot_time(Path::new("store/PlushPepe/20260101T120000Z.snapshot"));
+    assert_eq!(taken_at, DateTime::from_timestamp(1767268800, 0));
+    assert_eq!(store::snapshot_time(Path::new("PlushPepe.snapshot")), None);
+    assert_eq!(
+        store::snapshot_time(Path::new("parsed_PlushPepe.state")),
+        None
+    );
+}
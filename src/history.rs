@@ -0,0 +1,123 @@
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::types::{StarsAmount, StarsTransaction};
+use grammers_client::{Client, InvocationError};
+use parser_nft::report::json_string;
+
+// Одна операция с подарком.
+struct Entry {
+    date: String,
+    kind: &'static str,
+    // Со знаком: минус - потрачено, плюс - получено.
+    amount: StarsAmount,
+    gift: String,
+    slug: Option<String>,
+    peer: Option<String>,
+}
+
+// Проходит по всем транзакциям звёзд аккаунта и печатает те, что касаются подарков:
+// покупки, продажи, улучшения и возвраты. От новых к старым.
+pub async fn history(client: &Client, json: bool) -> Result<(), InvocationError> {
+    let me = client.get_me().await?;
+    let mut transactions = client.iter_star_transactions(&me);
+    let mut entries = Vec::new();
+    while let Some(transaction) = transactions.next().await? {
+        if let Some(entry) = entry(&transaction) {
+            entries.push(entry);
+        }
+    }
+
+    if json {
+        print_json(&entries);
+    } else {
+        print_table(&entries);
+    }
+    Ok(())
+}
+
+fn entry(transaction: &StarsTransaction) -> Option<Entry> {
+    let (gift, slug) = match transaction.gift()? {
+        tl::enums::StarGift::Unique(gift) => (
+            format!("{} #{}", gift.title, gift.num),
+            Some(gift.slug.clone()),
+        ),
+        tl::enums::StarGift::Gift(gift) => (
+            gift.title
+                .clone()
+                .unwrap_or_else(|| format!("подарок {}", gift.id)),
+            None,
+        ),
+    };
+
+    let amount = transaction.amount();
+    let spent = match amount {
+        StarsAmount::Stars { amount, .. } => amount < 0,
+        StarsAmount::Ton { nanotons } => nanotons < 0,
+    };
+    let kind = if transaction.is_refund() {
+        "возврат"
+    } else if transaction.is_gift_upgrade() {
+        "улучшение"
+    } else if spent {
+        "покупка"
+    } else {
+        "продажа"
+    };
+
+    Some(Entry {
+        date: transaction.date().format("%Y-%m-%d %H:%M").to_string(),
+        kind,
+        amount,
+        gift,
+        slug,
+        peer: transaction.peer().map(|peer| match peer.username() {
+            Some(username) => format!("@{username}"),
+            None => peer.name().unwrap_or_default().to_string(),
+        }),
+    })
+}
+
+fn print_table(entries: &[Entry]) {
+    println!(
+        "{:<16} {:<10} {:>12} {:<28} С кем",
+        "Дата", "Операция", "Сумма", "Подарок"
+    );
+    for entry in entries {
+        println!(
+            "{:<16} {:<10} {:>12} {:<28} {}",
+            entry.date,
+            entry.kind,
+            match entry.amount {
+                StarsAmount::Stars { amount, .. } => format!("{amount} ⭐"),
+                StarsAmount::Ton { nanotons } => format!("{} TON", ton(nanotons)),
+            },
+            entry.gift,
+            entry.peer.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+fn print_json(entries: &[Entry]) {
+    let optional = |value: Option<&str>| value.map_or("null".to_string(), json_string);
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let (amount, currency) = match entry.amount {
+                StarsAmount::Stars { amount, .. } => (amount.to_string(), "stars"),
+                StarsAmount::Ton { nanotons } => (ton(nanotons).to_string(), "ton"),
+            };
+            format!(
+                "  {{\"date\": {}, \"kind\": {}, \"amount\": {amount}, \"currency\": \"{currency}\", \"gift\": {}, \"slug\": {}, \"peer\": {}}}",
+                json_string(&entry.date),
+                json_string(entry.kind),
+                json_string(&entry.gift),
+                optional(entry.slug.as_deref()),
+                optional(entry.peer.as_deref())
+            )
+        })
+        .collect();
+    println!("[\n{}\n]", lines.join(",\n"));
+}
+
+fn ton(nanotons: i64) -> f64 {
+    nanotons as f64 / 1e9
+}
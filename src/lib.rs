@@ -0,0 +1,26 @@
+// Парсер уникальных подарков Telegram как библиотека: rustfind - лишь её CLI.
+//   console - куда писать сообщения о ходе парсинга;
+//   db      - подарки и снимки в PostgreSQL (feature postgres);
+//   error   - ParserError, общий тип ошибок парсера;
+//   export  - отчёты в HTML, Markdown, JSON, NDJSON и CSV и их публикация;
+//   fetch   - загрузка подарков и данных для обогащения через grammers;
+//   model   - разбор того, что вернул Telegram (владельцы, палитры, TON);
+//   notify  - вебхуки о событиях запуска;
+//   redact  - вычистка секретов из логов, сообщений и паник;
+//   report  - сведения о запуске, качестве данных, сводки и изменения коллекций;
+//   store   - снимки коллекций на диске и слияние результатов запусков.
+
+pub mod console;
+#[cfg(feature = "postgres")]
+pub mod db;
+pub mod error;
+pub mod export;
+pub mod fetch;
+pub mod model;
+pub mod notify;
+pub mod redact;
+pub mod report;
+pub mod store;
+
+#[cfg(test)]
+mod golden;
@@ -1,25 +1,68 @@
-
-
-use grammers_client::session::Session;
-use grammers_client::{Client, Config, SignInError};
-use simple_logger::SimpleLogger;
-use std::io::{self, BufRead as _, Write as _, Result as Res};
-use tokio::runtime;
+use chrono::{Local, Utc};
+use clap::Parser as _;
+use futures_util::future::join_all;
+use grammers_client::Client;
 use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
-use grammers_client::grammers_tl_types as tl;
+use parser_nft::export;
+use parser_nft::export::Output as _;
+use parser_nft::export::json::gift_json;
+use parser_nft::export::{html, publish};
+use parser_nft::fetch::catalog::Catalog;
+use parser_nft::fetch::rate::RateLimiter;
+use parser_nft::fetch::{self, artwork, checkpoint, discover, floor, media, owners, palette};
+use parser_nft::model::provenance::hide_original_details;
+use parser_nft::model::rarity;
+use parser_nft::model::{GiftRecord, gift_title};
+use parser_nft::notify::Event;
+use parser_nft::notify::rules::AlertRules;
+use parser_nft::notify::telegram;
+use parser_nft::notify::webhook::{self, Webhook};
+use parser_nft::report::RunInfo;
+use parser_nft::report::diff::Changes;
+use parser_nft::report::manifest::{self, CollectionRun};
+use parser_nft::report::ownership;
+use parser_nft::report::prices::Prices;
+use parser_nft::report::quality::Quality;
+use parser_nft::report::stats::Stats;
+use parser_nft::store::Store;
+use parser_nft::{console, redact, status};
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{self, File};
-use std::fs::remove_file;
-use std::path::Path;
+use std::io::{self, BufRead as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tokio::runtime;
 
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+mod aliases;
+mod catalog;
+mod cli;
+mod compare;
+mod dry_run;
+mod exit;
+mod feed;
+mod get;
+mod history;
+mod merge;
+mod my_gifts;
+mod owner;
+mod pin;
+mod preview;
+mod serve;
+mod session;
+mod upgrade;
+mod watchlist;
 
-const SESSION_FILE: &str = "parser.session";
+use parser_nft::error::{ParserError, Result};
 
 fn prompt(message: &str) -> Result<String> {
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-    stdout.write_all(message.as_bytes())?;
-    stdout.flush()?;
+    // С --stream stdout занят подарками, вопрос задаём в stderr.
+    let mut out: Box<dyn io::Write> = if console::is_stderr() {
+        Box::new(io::stderr().lock())
+    } else {
+        Box::new(io::stdout().lock())
+    };
+    out.write_all(message.as_bytes())?;
+    out.flush()?;
 
     let stdin = io::stdin();
     let mut stdin = stdin.lock();
@@ -30,218 +73,900 @@ fn prompt(message: &str) -> Result<String> {
 }
 
 async fn async_main() -> Result<()> {
+    let args = cli::Args::parse();
+    redact::init(!args.no_redact);
+    console::status_to_stderr(args.stream);
+    let session_file = session::file_name(&args.session);
+    redact::add_secret(&session_file);
+    for url in &args.webhooks {
+        redact::add_secret(url);
+    }
+    if let Some(secret) = &args.webhook_secret {
+        redact::add_secret(secret);
+    }
+    #[cfg(feature = "postgres")]
+    if let Some(url) = &args.db_url {
+        // В адресе базы бывает пароль.
+        redact::add_secret(url);
+    }
 
-    let api_id = 27221966;
-    let api_hash = "7a547b8a6425910bc9181ecde48e1bcc".to_string();
+    // Для публикации Telegram не нужен - работаем только с готовыми файлами.
+    if let Some(cli::Command::Publish {
+        out,
+        base_url,
+        media,
+    }) = &args.command
+    {
+        export::publish::publish(out, base_url.as_deref(), media.as_deref())?;
+        return Ok(());
+    }
+    // Слияние работает с файлами прошлых запусков, Telegram тоже не нужен.
+    if let Some(cli::Command::Merge { files }) = &args.command {
+        merge::merge(files, &args)?;
+        return Ok(());
+    }
+    // API отвечает по снимкам из хранилища, тоже без Telegram.
+    if let Some(cli::Command::Serve { addr }) = &args.command {
+        let Some(store) = &args.store else {
+            return Err(ParserError::Config(
+                "для serve нужна папка со снимками: --store DIR".to_string(),
+            ));
+        };
+        serve::serve(store.clone(), *addr).await?;
+        return Ok(());
+    }
 
-    println!("Connecting to Telegram...");
-    let client = Client::connect(Config {
-        session: Session::load_file_or_create(SESSION_FILE)?,
-        api_id,
-        api_hash: api_hash.clone(),
-        params: Default::default(),
-    })
-    .await?;
-    println!("Connected!");
+    if let Some(cli::Command::Session { action }) = &args.command {
+        session::session(action, &args).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Watchlist { action }) = &args.command {
+        match action {
+            cli::WatchlistAction::Add { gifts } => watchlist::add(&args.watchlist, gifts)?,
+            cli::WatchlistAction::Remove { gifts } => watchlist::remove(&args.watchlist, gifts)?,
+            cli::WatchlistAction::List => watchlist::list(&args.watchlist)?,
+            cli::WatchlistAction::Check { .. } => {}
+        }
+        if !matches!(action, cli::WatchlistAction::Check { .. }) {
+            return Ok(());
+        }
+    }
+
+    status!("Connecting to Telegram...");
+    let mut client = session::connect(&session_file, &args).await?;
+    status!("Connected!");
 
     //  Если есть уже сессия - входим.
-    let mut sign_out = false;
-
-    if !client.is_authorized().await? {
-        println!("Signing in...");
-        let phone = prompt("Enter your phone number (international format): ")?;
-        let token = client.request_login_code(&phone).await?;
-        let code = prompt("Enter the code you received: ")?;
-        let signed_in = client.sign_in(&token, &code).await;
-        match signed_in {
-            Err(SignInError::PasswordRequired(password_token)) => {
-                // Просии ввести номер телефона, код , пароль.
-                let hint = password_token.hint().unwrap_or("None");
-                let prompt_message = format!("Enter the password (hint {}): ", &hint);
-                let password = prompt(prompt_message.as_str())?;
-
-                client
-                    .check_password(password_token, password.trim())
-                    .await?;
-            }
-            Ok(_) => (),
-            Err(e) => panic!("{}", e),
+    let mut session_saved = session::sign_in(&client, &session_file, &args).await?;
+    if let Some(cli::Command::Get { gift, json }) = &args.command {
+        get::get(&client, gift, *json).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Compare {
+        first,
+        second,
+        json,
+    }) = &args.command
+    {
+        compare::compare(&client, first, second, *json).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Owner { owner }) = &args.command {
+        owner::owner(&client, owner, &args).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Feed { channel, limit }) = &args.command {
+        feed::scan(&client, channel, Some(*limit), &args).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::ScanChat { chat, limit }) = &args.command {
+        feed::scan(&client, chat, *limit, &args).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Watchlist {
+        action: cli::WatchlistAction::Check { every },
+    }) = &args.command
+    {
+        loop {
+            watchlist::check(&client, &args.watchlist, &args).await?;
+            let Some(minutes) = every else {
+                break;
+            };
+            status!("Следующая проверка через {minutes} мин");
+            tokio::select! {
+                _ = session::idle(&client, std::time::Duration::from_secs(minutes * 60)) => {}
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+        return Ok(());
+    }
+    if let Some(cli::Command::Catalog { upgraded, json }) = &args.command {
+        let mut limiter = RateLimiter::new(args.rps);
+        let cache = Catalog::new(fetch::catalog::CACHE_FILE);
+        catalog::catalog(&client, &cache, *upgraded, *json, &mut limiter).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Preview { collection, json }) = &args.command {
+        let catalog = Catalog::new(fetch::catalog::CACHE_FILE);
+        preview::preview(&client, &catalog, collection, *json).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::History { json }) = &args.command {
+        history::history(&client, *json).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Upgrade {
+        id,
+        keep_original_details,
+        yes,
+    }) = &args.command
+    {
+        upgrade::upgrade(&client, *id, *keep_original_details, *yes).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::MyGifts { json }) = &args.command {
+        my_gifts::my_gifts(&client, *json).await?;
+        return Ok(());
+    }
+    if let Some(cli::Command::Pin { gifts } | cli::Command::Unpin { gifts }) = &args.command {
+        let pinned = matches!(args.command, Some(cli::Command::Pin { .. }));
+        let mut limiter = RateLimiter::new(args.rps);
+        pin::pin(&client, gifts, pinned, &mut limiter).await?;
+        return Ok(());
+    }
+
+    // Коллекции из --collection, а если их нет - спрашиваем одну.
+    let aliases = aliases::Aliases::load(&args.aliases)?;
+    // Правила читаются сразу, чтобы ошибка в них не всплыла только после парсинга.
+    let alert_rules = match &args.alert_rules {
+        Some(path) => AlertRules::load(path)?,
+        None => AlertRules::default(),
+    };
+    let collections: Vec<String> = if args.collections.is_empty() {
+        let gift = prompt("Выберите Slug подарка для парсинга в формате «PlushPepe» ---> ")?;
+        vec![aliases.canonical(gift.trim())]
+    } else {
+        args.collections
+            .iter()
+            .map(|collection| aliases.canonical(collection))
+            .collect()
+    };
+
+    if args.dry_run {
+        for gift in &collections {
+            dry_run::dry_run(&client, gift, &args).await?;
+        }
+        return Ok(());
+    }
+
+    // Коллекции парсятся одновременно через одно подключение, но с общим
+    // ограничителем: вместе они не превышают --rps и вместе замедляются по flood-wait.
+    let limiter = if args.adaptive {
+        RateLimiter::adaptive(args.rps)
+    } else {
+        RateLimiter::new(args.rps)
+    };
+    // Каталог подарков нужен для палитр и цен каждой коллекции, а меняется редко:
+    // держим его в кэше и перезагружаем, только если он изменился.
+    let catalog = Catalog::new(fetch::catalog::CACHE_FILE);
+
+    // Строки index.html по коллекциям. Если коллекция в этот раз не спарсилась,
+    // в оглавлении остаётся её прошлый отчёт.
+    let mut index: BTreeMap<String, html::IndexEntry> = BTreeMap::new();
+    let mut site: BTreeMap<String, html::IndexEntry> = BTreeMap::new();
+    // Ошибки последнего прохода: из-за них отчёты неполные.
+    let mut partial;
+
+    // С --schedule работаем демоном: парсим все коллекции, ждём следующего
+    // срабатывания расписания и повторяем, пока не остановят Ctrl+C.
+    loop {
+        let runs = collections.iter().map(|gift| {
+            let mut client = client.clone();
+            let limiter = limiter.clone();
+            let args = &args;
+            let catalog = &catalog;
+            let alert_rules = &alert_rules;
+            async move {
+                let parsed = parse(&mut client, args, catalog, alert_rules, gift, limiter).await;
+                (gift, parsed)
+            }
+        });
+        let run = args.run_info(&collections.join(","));
+        let mut interrupted = false;
+        let mut failed = None;
+        let mut revoked = false;
+        let mut manifest: Vec<CollectionRun> = Vec::new();
+        for (gift, result) in join_all(runs).await {
+            match result {
+                Ok(parsed) => {
+                    interrupted |= parsed.interrupted;
+                    manifest.push(parsed.manifest);
+                    if let Some(entry) = parsed.index {
+                        index.insert(gift.clone(), entry);
+                    }
+                    if let Some(entry) = parsed.site {
+                        site.insert(gift.clone(), entry);
+                    }
+                }
+                // Без авторизации не получится ни одна коллекция - входим заново ниже.
+                Err(e) if session::is_revoked(&e) => {
+                    revoked = true;
+                    manifest.push(CollectionRun::failed(gift, run.started_at, e.to_string()));
+                }
+                // Одна неудачная коллекция не останавливает остальные и демон.
+                Err(e) if collections.len() > 1 || args.schedule.is_some() => {
+                    status!("Парсинг {gift} не удался: {e}");
+                    manifest.push(CollectionRun::failed(gift, run.started_at, e.to_string()));
+                }
+                Err(e) => {
+                    manifest.push(CollectionRun::failed(gift, run.started_at, e.to_string()));
+                    failed = Some(e);
+                }
+            }
+        }
+        write_manifest(&args, &run, &manifest)?;
+        partial = manifest.iter().map(CollectionRun::errors).sum();
+        if revoked {
+            (client, session_saved) = session::relogin(&session_file, &args).await?;
+            continue;
+        }
+        if let Some(e) = failed {
+            return Err(e);
+        }
+        if collections.len() > 1 && !index.is_empty() {
+            let entries: Vec<&html::IndexEntry> = index.values().collect();
+            write_index(&args, &collections, &entries)?;
+        }
+        if let Some(dir) = args.site.as_ref().filter(|_| !site.is_empty()) {
+            let entries: Vec<&html::IndexEntry> = site.values().collect();
+            export::site::write_root(
+                &entries,
+                args.lang(),
+                args.theme.into(),
+                args.css.as_ref().map(|(_, css)| css.as_str()),
+                &run,
+                &mut export::Directory::new(dir)?,
+            )?;
+            status!("Сайт собран в {}", dir.display());
+        }
+        if interrupted {
+            break;
+        }
+        let Some(schedule) = &args.schedule else {
+            break;
         };
-        println!("Signed in!"); // Вход
-        match client.session().save_to_file(SESSION_FILE) {
-            Ok(_) => {}
+        let next = schedule
+            .find_next_occurrence(&Local::now(), false)
+            .map_err(|e| ParserError::Config(format!("--schedule: {e}")))?;
+        status!(
+            "Следующий запуск по расписанию: {}",
+            next.format("%Y-%m-%d %H:%M")
+        );
+        let wait = (next - Local::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = session::idle(&client, wait) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    if !session_saved {
+        session::logout(&client, &session_file).await?;
+    }
+
+    if partial > 0 {
+        return Err(ParserError::Partial { errors: partial });
+    }
+    Ok(())
+}
+
+// Чем закончился парсинг одной коллекции.
+struct Parsed {
+    // Парсинг прервали Ctrl+C.
+    interrupted: bool,
+    // Строка коллекции для index.html, если записан HTML-отчёт.
+    index: Option<html::IndexEntry>,
+    // Строка коллекции для index.html сайта, если он собирается (--site).
+    site: Option<html::IndexEntry>,
+    // Итог коллекции для manifest.json.
+    manifest: CollectionRun,
+}
+
+// Парсит коллекцию `gift` и пишет отчёт.
+async fn parse(
+    client: &mut Client,
+    args: &cli::Args,
+    catalog: &Catalog,
+    alert_rules: &AlertRules,
+    gift: &str,
+    mut limiter: RateLimiter,
+) -> Result<Parsed> {
+    let run = args.run_info(gift);
+
+    // Продолжаем с места, где остановился прошлый незаконченный запуск.
+    let mut progress = fetch::Progress::resume(Path::new("."), gift);
+    if !progress.gifts.is_empty() {
+        status!("{gift}: продолжаем парсинг с подарка {}", progress.next);
+        if !progress.retry.is_empty() {
+            status!(
+                "{gift}: отложенных номеров для второй попытки {}",
+                progress.retry.len()
+            );
+        }
+    } else if let Some(dir) = args.store.as_ref().filter(|_| args.update) {
+        match Store::new(dir).latest(gift) {
+            Ok(Some(snapshot)) => {
+                progress = fetch::Progress::update(gift, snapshot.gifts, args.update_window);
+                status!(
+                    "{gift}: обновляем снимок от {}, загружаем с подарка {}",
+                    snapshot.taken_at.format("%Y-%m-%d %H:%M UTC"),
+                    progress.next
+                );
+            }
+            Ok(None) => status!("{gift}: снимков ещё нет, загружаем коллекцию целиком"),
             Err(e) => {
-                println!("NOTE: failed to save the session, will sign out when done: {e}");
-                sign_out = true;
+                status!("{gift}: не удалось прочитать прошлый снимок, загружаем целиком: {e}")
             }
         }
     }
-    let mut gifts = Vec::new();
-    let mut gift = prompt("Выберите Slug подарка для парсинга в формате «PlushPepe» ---> ")?;
-    let gift = gift.trim();
-    let mut i = 1;
-    loop {
-        let slug = format!("{}-{}", gift, i);
-        let get_gift = client.get_unique_star_gift(slug.clone())
-        .await;
-        match get_gift {
-            Ok(UniqueStarGift::Gift(gift)) => {
-                println!("Парсинг подарка с номером {}", i);
-                gifts.push(UniqueStarGift::Gift(gift));
-                i += 1;
-            },
-            Ok(_) => {
-                println!("{}", slug);
-                break;
-            },
+
+    // Ctrl+C не теряет уже полученное: останавливаемся, сохраняем состояние и пишем отчёт.
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    // Последний номер ищем двоичным поиском: так хвост коллекции, которую ещё
+    // улучшают, находится за десяток запросов, а пропуски в нумерации не обрывают парсинг.
+    progress.highest = discover::highest_number(client, gift, &mut limiter).await? as usize;
+    if progress.highest == 0 && progress.gifts.is_empty() {
+        return Err(ParserError::UnknownCollection(gift.to_string()));
+    }
+    status!(
+        "{gift}: последний выпущенный подарок {gift}-{}",
+        progress.highest
+    );
+
+    // С --stream каждый подарок сразу печатается строкой NDJSON. Владельцы, палитры
+    // и цены дописываются позже, поэтому в потоке их нет - они будут в отчёте.
+    // Если читатель stdout закрылся, поток выключается, а парсинг продолжается.
+    let mut stream = args.stream;
+    // С --format ndjson отчёт пишется так же, по подарку, чтобы файл можно было
+    // читать через `tail -f` и чтобы полученное пережило обрыв. В конце он
+    // перезаписывается целиком - уже с владельцами и ценами.
+    let mut ndjson = if args.format == "ndjson" {
+        Some(ndjson_file(args, gift, &run, &progress.gifts)?)
+    } else {
+        None
+    };
+    let mut on_gift = |gift: &UniqueStarGift| {
+        if !stream && ndjson.is_none() {
+            return;
+        }
+        let line = gift_json(&GiftRecord::from(gift.clone()), None);
+        if stream {
+            let mut stdout = io::stdout().lock();
+            if let Err(e) = writeln!(stdout, "{line}").and_then(|()| stdout.flush()) {
+                status!("Поток подарков остановлен: {e}");
+                stream = false;
+            }
+        }
+        if let Some(file) = &mut ndjson
+            && let Err(e) = writeln!(file, "{line}").and_then(|()| file.flush())
+        {
+            status!("Не удалось дописать подарок в NDJSON: {e}");
+            ndjson = None;
+        }
+    };
+
+    let mut reconnects = 0;
+    let mut interrupted = false;
+    // Ошибка, на которой остановился парсинг, - для кода выхода.
+    let mut failure = None;
+    let stop_error = loop {
+        let fetched = progress.gifts.len();
+        let stop = fetch::fetch_gifts(
+            client,
+            &mut progress,
+            &mut limiter,
+            args.adaptive,
+            &mut ctrl_c,
+            &mut on_gift,
+        )
+        .await?;
+        // Считаем только переподключения подряд, без полученных между ними подарков.
+        if progress.gifts.len() > fetched {
+            reconnects = 0;
+        }
+        match stop {
+            fetch::Stop::Done => break None,
+            // Клиент сам переподключается по --reconnect-attempts, но если и это не
+            // помогло - сохраняем состояние и подключаемся заново, продолжая с того же подарка.
+            fetch::Stop::ConnectionLost(e) if reconnects < args.reconnect_attempts => {
+                reconnects += 1;
+                status!(
+                    "Связь с Telegram потеряна ({e}), переподключаемся ({reconnects} из {})",
+                    args.reconnect_attempts
+                );
+                checkpoint::save(&progress)?;
+                tokio::time::sleep(session::RECONNECT_DELAY).await;
+                match session::reconnect(client, args).await {
+                    Ok(new_client) => *client = new_client,
+                    Err(e) => status!("Не удалось переподключиться: {e}"),
+                }
+            }
+            fetch::Stop::ConnectionLost(source) => {
+                let e = ParserError::Gift {
+                    gift: format!("{gift}-{}", progress.next),
+                    source,
+                };
+                break Some(failure.insert(e).to_string());
+            }
+            // Полученное сохраняем: после нового входа парсинг продолжится с того же подарка.
+            fetch::Stop::SessionRevoked(e) => {
+                checkpoint::save(&progress)?;
+                return Err(e.into());
+            }
+            fetch::Stop::Interrupted(slug) => {
+                interrupted = true;
+                break Some(format!("{slug}: прервано пользователем"));
+            }
+            fetch::Stop::Failed(e) => break Some(failure.insert(e).to_string()),
+        }
+    };
+
+    if stop_error.is_some() {
+        checkpoint::save(&progress)?;
+        status!(
+            "Состояние сохранено, следующий запуск продолжит с подарка {}",
+            progress.next
+        );
+        // Без единого подарка отчёт писать не из чего: код выхода скажет, почему
+        // (flood-wait или нет связи), а не «отчёты неполные».
+        if progress.gifts.is_empty()
+            && let Some(e) = failure
+        {
+            return Err(e);
+        }
+    } else {
+        checkpoint::clear(&progress)?;
+    }
+    // Сводка по скорости - чтобы было по чему подбирать --rps и --flood-sleep.
+    let performance = progress.timings.performance();
+    if performance.requests > 0 {
+        status!("{gift}: {performance}");
+    }
+    let mut gifts = progress.gifts;
+    let failed_gifts = progress.failed;
+
+    // Дописываем к подаркам их владельцев, чтобы в отчёте были username, а не голые id.
+    match owners::resolve_owners(client, &mut gifts, &mut limiter).await {
+        Ok(resolved) => status!("Найдены профили владельцев {resolved} подарков"),
+        Err(e) => status!("Не удалось загрузить профили владельцев: {e}"),
+    }
+
+    if args.hide_original_details {
+        hide_original_details(&mut gifts);
+    }
+
+    let mut quality = Quality::new(&gifts);
+    quality.stop_error = stop_error;
+    quality.failed_gifts = failed_gifts;
+    if !quality.missing.is_empty() {
+        status!(
+            "Внимание: получено {} подарков из {} выпущенных, нет номеров: {}",
+            quality.fetched,
+            quality.expected.unwrap_or_default(),
+            quality.missing_ranges()
+        );
+    }
+
+    // Прошлый снимок берём до того, как сохранится новый: вебхукам и оповещениям
+    // нужно, что изменилось.
+    let notify = !args.webhooks.is_empty() || args.alert_chat.is_some();
+    let previous = match &args.store {
+        Some(dir) if notify && quality.stop_error.is_none() => {
+            Store::new(dir).latest(gift).unwrap_or_else(|e| {
+                status!("Не удалось прочитать прошлый снимок: {e}");
+                None
+            })
+        }
+        _ => None,
+    };
+
+    // Записанные файлы для manifest.json.
+    let mut files = Vec::new();
+
+    // Неполный снимок в хранилище не кладём: по нему нельзя сравнивать запуски.
+    if let Some(dir) = &args.store {
+        if quality.stop_error.is_some() || !quality.failed_gifts.is_empty() || gifts.is_empty() {
+            status!(
+                "Парсинг не завершён, снимок в {} не сохраняется",
+                dir.display()
+            );
+        } else {
+            let path = Store::new(dir).save(gift, &gifts, run.started_at)?;
+            status!("Снимок коллекции сохранён в {}", path.display());
+            files.push(path);
+        }
+    }
+
+    // Палитры фонов загружаем один раз на всю коллекцию.
+    let palettes = match gifts.first().and_then(gift_title) {
+        Some(title) => match palette::fetch_palettes(client, catalog, &title).await {
+            Ok(palettes) => palettes,
             Err(e) => {
-                println!("{}", slug);
-                break;
+                status!("Не удалось загрузить палитры фонов: {e}");
+                HashMap::new()
+            }
+        },
+        None => HashMap::new(),
+    };
+
+    let artwork = if args.artwork {
+        match artwork::download_artwork(client, &gifts, &args.report_dir(gift), &mut quality).await
+        {
+            Ok(artwork) => Some(artwork),
+            Err(e) => {
+                status!("Не удалось сохранить превью моделей: {e}");
+                Some(HashMap::new())
+            }
+        }
+    } else {
+        None
+    };
+
+    let floor_prices = match gifts.first().and_then(gift_title) {
+        Some(title) if args.floor_prices => {
+            match floor::fetch_floor_prices(client, catalog, &title).await {
+                Ok(floor_prices) => Some(floor_prices),
+                Err(e) => {
+                    status!("Не удалось загрузить цены перепродажи: {e}");
+                    None
+                }
             }
+        }
+        _ => None,
+    };
+
+    if let Some(dir) = &args.download_media {
+        if quality.media_unreachable.is_some() {
+            status!("Датацентр с медиа недоступен, медиа подарков не сохраняются");
+        } else if let Err(e) = media::download_media(client, &gifts, dir, &run, &mut quality).await
+        {
+            status!("Не удалось сохранить медиа подарков: {e}");
+        }
     }
-        
-        
+
+    let mut gifts: Vec<GiftRecord> = gifts.into_iter().map(GiftRecord::from).collect();
+    rarity::rescore(&mut gifts, args.rarity_formula.into());
+
+    // В базу, как и в --store, полным снимком попадает только полный парсинг,
+    // но свежие данные о подарках записываются в любом случае.
+    #[cfg(feature = "postgres")]
+    if let Some(url) = args.db_url.as_ref().filter(|_| !gifts.is_empty()) {
+        let complete = quality.stop_error.is_none() && quality.failed_gifts.is_empty();
+        let saved = async {
+            let mut db = parser_nft::db::Database::connect(url).await?;
+            db.save(gift, &gifts, run.started_at, complete).await
+        }
+        .await;
+        match saved {
+            Ok(()) => status!("Подарки {gift} записаны в базу данных"),
+            Err(e) => status!("Не удалось записать подарки в базу данных: {e}"),
+        }
     }
 
-    if !gifts.is_empty() {
-        gen_html(gifts)?;
-        println!("Сгенерирован файл с результатом парсинга parsed.html")
+    let mut index = None;
+    let mut site_entry = None;
+    if gifts.is_empty() {
+        status!("Не найдено подарков")
+    } else {
+        // История владельцев - по снимкам из --store и этому запуску. Без снимков
+        // она нужна только страницам подарков на сайте.
+        let owner_history = (args.store.is_some() || args.site.is_some()).then(|| {
+            let mut history = match &args.store {
+                Some(store) => {
+                    ownership::owner_history(&Store::new(store), gift).unwrap_or_else(|e| {
+                        status!("Не удалось прочитать снимки для истории владельцев: {e}");
+                        Default::default()
+                    })
+                }
+                None => Default::default(),
+            };
+            ownership::add_snapshot(&mut history, run.started_at, &gifts);
+            history
+        });
+        let enrichment = export::Enrichment {
+            palettes,
+            artwork,
+            floor_prices,
+            owner_history,
+        };
+        let name = args.report_name(gift, &run);
+        let report = export::Report {
+            name: &name,
+            enrichment: &enrichment,
+            page_size: args.page_size,
+            split_by: args.split_by.map(Into::into),
+            lang: args.lang(),
+            theme: args.theme.into(),
+            css: args.css.as_ref().map(|(_, css)| css.as_str()),
+            detail_pages: false,
+            run: &run,
+            quality: &quality,
+        };
+        files.extend(write_report(args, &report, &gifts, &args.report_dir(gift))?);
+        if args.prices {
+            files.extend(write_prices(args, &report, gift, &gifts)?);
+        }
+        if args.format == "html" {
+            // Отчёт каждой коллекции лежит либо рядом с index.html, либо в папке коллекции.
+            let link = if args.report_dir(gift) == args.out_dir.clone().unwrap_or_default() {
+                format!("{name}.html")
+            } else {
+                format!("{gift}/{name}.html")
+            };
+            index = Some(html::IndexEntry::new(gift, link, &gifts, &quality));
+        }
+        if let Some(site) = &args.site {
+            let dir = site.join(gift);
+            let mut output = export::Directory::new(&dir)?;
+            export::site::write_collection(&gifts, &report, &mut output)?;
+            files.extend(output.into_files());
+            let artwork_dir = args.report_dir(gift).join(artwork::ARTWORK_DIR);
+            if args.artwork && artwork_dir.is_dir() {
+                publish::copy_dir(&artwork_dir, &dir.join(artwork::ARTWORK_DIR))?;
+            }
+            status!("Страницы {gift} записаны на сайт в {}", dir.display());
+            site_entry = Some(html::IndexEntry::new(
+                gift,
+                format!("{gift}/index.html"),
+                &gifts,
+                &quality,
+            ));
+        }
     }
-    else {
-        println!("Не найдено подарков")
+
+    let mut manifest = CollectionRun::new(gift, run.started_at, &gifts, &quality);
+    manifest.files = files;
+    manifest.performance = Some(performance).filter(|performance| performance.requests > 0);
+
+    let changes = previous.map(|previous| {
+        let before: Vec<GiftRecord> = previous.gifts.into_iter().map(GiftRecord::from).collect();
+        Changes::new(&before, &gifts)
+    });
+
+    if !args.webhooks.is_empty() {
+        let webhooks: Vec<Webhook> = args
+            .webhooks
+            .iter()
+            .map(|url| Webhook::new(url, args.webhook_secret.clone()))
+            .collect();
+        let stats = Stats::new(&gifts);
+        let mut events = vec![Event::RunCompleted {
+            collection: gift,
+            run: &run,
+            quality: &quality,
+            stats: &stats,
+        }];
+        if let Some(changes) = &changes {
+            events.extend(Event::from_changes(gift, changes));
+        }
+        webhook::send_all(&webhooks, &events).await;
     }
-    if sign_out {
-        // TODO revisit examples and get rid of "handle references" (also, this panics)
-        drop(client.sign_out_disconnect().await);
+
+    // Оповещение не должно ронять запуск: отчёт к этому моменту уже записан.
+    if let (Some(chat), Some(changes)) = (&args.alert_chat, &changes) {
+        let messages = telegram::alert_messages(gift, changes, alert_rules);
+        if !messages.is_empty() {
+            let sent: Result<()> = async {
+                let chat = owner::resolve(client, chat).await?;
+                telegram::send_alert(client, chat.pack(), &messages).await?;
+                Ok(())
+            }
+            .await;
+            match sent {
+                Ok(()) => status!("Оповещение об изменениях отправлено в {chat}"),
+                Err(e) => status!("Не удалось отправить оповещение в {chat}: {e}"),
+            }
+        }
     }
 
-    Ok(())
+    Ok(Parsed {
+        interrupted,
+        index,
+        site: site_entry,
+        manifest,
+    })
 }
 
-// Функция для генерации удобного и красивого HTML шаблона
-// Шаблон сделан с помощью ChatGPT - автор не умеет.
-fn gen_html(gifts: Vec<UniqueStarGift>) -> Res<()> {
-    let mut html = "<!DOCTYPE html>
-<html lang=\"ru\">
-<head>
-<meta charset=\"UTF-8\" />
-<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />
-<title>Telegram Gifts</title>
-<style>
-  body {
-    font-family: \"Segoe UI\", Tahoma, Geneva, Verdana, sans-serif;
-    background: #f9fafb;
-    color: #2c3e50;
-    margin: 0;
-    padding: 20px;
-  }
-  .gifts-container {
-    max-width: 900px;
-    margin: 0 auto;
-  }
-  .gift-item {
-    background: white;
-    border-radius: 8px;
-    box-shadow: 0 2px 6px rgb(0 0 0 / 0.1);
-    padding: 15px 20px;
-    margin-bottom: 15px;
-    display: flex;
-    flex-wrap: wrap;
-    gap: 12px;
-    align-items: center;
-  }
-  .gift-item a {
-    color: #2980b9;
-    text-decoration: none;
-    font-weight: 600;
-  }
-  .gift-item a:hover {
-    text-decoration: underline;
-  }
-  .gift-model, .gift-backdrop {
-    background: #ecf0f1;
-    border-radius: 5px;
-    padding: 8px 12px;
-    font-size: 14px;
-    color: #34495e;
-    flex: 1 1 200px;
-  }
-  .gift-username, .gift-name {
-    flex: 0 0 auto;
-  }
-</style>
-</head>
-<body>
-
-<div class=\"gifts-container\">
-  <!-- Один подарок -->
-
-".to_string();
-    let _gifts_info= "Test".to_string();
+// Файл отчёта --format ndjson для записи во время парсинга: уже полученные
+// подарки (из checkpoint или снимка --update) в нём сразу, новые дописываются.
+fn ndjson_file(
+    args: &cli::Args,
+    gift: &str,
+    run: &RunInfo,
+    gifts: &[UniqueStarGift],
+) -> Result<File> {
+    let dir = args.report_dir(gift);
+    if !dir.as_os_str().is_empty() {
+        fs::create_dir_all(&dir)?;
+    }
+    let path = dir.join(format!("{}.ndjson", args.report_name(gift, run)));
+    let mut file = File::create(&path)?;
     for gift in gifts {
-        let mut gift_slug: Option<String> = Some(".".to_string());
-        let mut _gift_link: Option<String> = Some("зн".to_string());
-        let mut gift_model: String = "Test".to_string();
-        let mut gift_backdrop: String = "Test".to_string();
-        match gift {
-            UniqueStarGift::Gift(gift_obj) => {
-                match gift_obj.gift {
-                    tl::enums::StarGift::Unique(info) => {
-                        gift_slug = Some(info.slug.clone());
-                        _gift_link = Some(format!("https://t.me/nft/{}", info.slug.clone()));
-                        let atr = info.attributes;
-                        for elem in atr {
-                            match elem {
-                                tl::enums::StarGiftAttribute::Backdrop(backdrop) => {
-                                    gift_backdrop = backdrop.name;
-                                },
-                                tl::enums::StarGiftAttribute::Model(model) => {
-                                    gift_model = model.name;
-                                }
-                                _ => {}
-                            }
-                        }
-    
-                    },
-
-                    _ => {}
-                }
-                //match atr  {
-                    //tl::enums::StarGiftAttribute::Backdrop(backdrop) => {
-                       // gift_backdrop = backdrop.name.clone()
-                   // },
-                   // _ => {}
-                    
-             //   }
-            },
-            _ => {}
-            }
-
-        //_gifts_info += _gift_info;
-        html.push_str(&format!(
-            r#"<div class="gift-item">
-    <div class="gift-model">Модель: {}</div>
-    <div class="gift-backdrop">Фон: {}</div>
-    <a href="{}" class="gift-name" target="_blank" rel="noopener noreferrer">{}</a>
-</div>
-"#,
-            gift_model, gift_backdrop, _gift_link.as_deref().unwrap_or("значение по умолчанию"), gift_slug.as_deref().unwrap_or("значение по умолчанию")
-        ));
-    }
-    html.push_str("</div>\n</body>\n</html>");
-    if Path::new("parsed.html").exists() {
-        fs::remove_file("parsed.html")?;
-    }
-    let mut file = File::create("parsed.html")?;
-    file.write_all(html.as_bytes())?;
+        writeln!(file, "{}", gift_json(&GiftRecord::from(gift.clone()), None))?;
+    }
+    status!(
+        "Подарки дописываются в {} по мере получения",
+        path.display()
+    );
+    Ok(file)
+}
+
+// Пишет manifest.json с параметрами и итогами запуска в --out-dir.
+fn write_manifest(args: &cli::Args, run: &RunInfo, collections: &[CollectionRun]) -> Result<()> {
+    let dir = args.out_dir.clone().unwrap_or_default();
+    let mut output = export::Directory::new(&dir)?;
+    let mut writer = output.create("manifest.json")?;
+    writer.write_all(manifest::manifest_json(run, Utc::now(), collections).as_bytes())?;
+    writer.flush()?;
+    status!(
+        "Итоги запуска записаны в {}",
+        dir.join("manifest.json").display()
+    );
+    Ok(())
+}
+
+// Пишет index.html со ссылками на отчёты всех коллекций в --out-dir.
+fn write_index(
+    args: &cli::Args,
+    collections: &[String],
+    entries: &[&html::IndexEntry],
+) -> Result<()> {
+    let run = args.run_info(&collections.join(","));
+    let dir = args.out_dir.clone().unwrap_or_default();
+    let mut output = export::Directory::new(&dir)?;
+    let mut writer = output.create("index.html")?;
+    html::write_index(
+        entries,
+        args.lang(),
+        args.theme.into(),
+        args.css.as_ref().map(|(_, css)| css.as_str()),
+        &run,
+        &mut writer,
+    )?;
+    status!(
+        "Оглавление коллекций записано в {}",
+        dir.join("index.html").display()
+    );
+    show_report(&dir.join("index.html"), args.open)
+}
+
+// Пишет отчёт о ценах перепродажи (--prices) в папку отчёта коллекции
+// и возвращает пути записанных файлов.
+fn write_prices(
+    args: &cli::Args,
+    report: &export::Report,
+    gift: &str,
+    gifts: &[GiftRecord],
+) -> Result<Vec<PathBuf>> {
+    let dir = args.report_dir(gift);
+    let export_error = |source| ParserError::Export {
+        path: dir.clone(),
+        source,
+    };
+    let mut output = export::Directory::new(&dir).map_err(export_error)?;
+    let name = args.prices_name(gift, report.run);
+    export::prices::write_prices(&Prices::new(gift, gifts), report, &name, &mut output)
+        .map_err(export_error)?;
+    status!(
+        "Цены перепродажи записаны в {}",
+        dir.join(format!("{name}.html")).display()
+    );
+    Ok(output.into_files())
+}
+
+// Пишет отчёт в формате --format в папку `dir` (пустой путь - текущая папка)
+// и возвращает пути записанных файлов.
+// Оценка редкости у подарков уже посчитана по --rarity-formula (rarity::rescore),
+// а отбор и порядок берутся из --min-rarity, --transferable и --sort-by-rarity.
+fn write_report(
+    args: &cli::Args,
+    report: &export::Report,
+    gifts: &[GiftRecord],
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let total = gifts.len();
+    let mut gifts = gifts.to_vec();
+    // Порядок подарков не зависит от того, в каком порядке они пришли.
+    gifts.sort_by(|a, b| (&a.title, a.num).cmp(&(&b.title, b.num)));
+    rarity::select(&mut gifts, args.min_rarity, args.sort_by_rarity);
+    if args.min_rarity.is_some() {
+        status!(
+            "В отчёт попало подарков по --min-rarity: {} из {}",
+            gifts.len(),
+            total
+        );
+    }
+    if args.transferable {
+        // Подарок без известных условий передачи не попадает: передать его нельзя
+        // или это не наш подарок.
+        let now = Utc::now();
+        gifts.retain(|gift| {
+            gift.transfer
+                .as_ref()
+                .is_some_and(|transfer| transfer.is_open(now))
+        });
+        status!(
+            "В отчёт попало подарков по --transferable: {} из {}",
+            gifts.len(),
+            total
+        );
+    }
+    // --format принимает только имена из реестра, так что формат всегда находится.
+    let mut output = export::Directory::new(dir).map_err(|source| ParserError::Export {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    if let Some(exporter) = export::exporter(&args.format, report) {
+        let path = dir.join(exporter.file_name());
+        exporter
+            .write_files(&gifts, &mut output)
+            .map_err(|source| ParserError::Export {
+                path: path.clone(),
+                source,
+            })?;
+        status!(
+            "Сгенерирован файл с результатом парсинга {}",
+            path.display()
+        );
+        // Отчёты нескольких коллекций открываются одним index.html.
+        show_report(&path, args.open && args.collections.len() <= 1)?;
+    }
+    Ok(output.into_files())
+}
+
+// Печатает ссылку file:// на отчёт, а с `open` (--open) ещё и открывает его в браузере.
+fn show_report(path: &Path, open: bool) -> Result<()> {
+    let url = file_url(&std::path::absolute(path)?);
+    status!("Отчёт: {url}");
+    if open && let Err(e) = open::that_detached(path) {
+        status!("Не удалось открыть отчёт в браузере: {e}");
+    }
     Ok(())
-    
 }
-fn main() -> Result<()> {
-    
-    runtime::Builder::new_current_thread()
+
+// Ссылка file:// на абсолютный путь `path`: пробелы, кириллица и прочее - через %XX.
+fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut url = String::from("file://");
+    // В Windows путь начинается с диска: file:///C:/...
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    url
+}
+
+// Код выхода говорит, чем закончился запуск (см. exit.rs и --help).
+fn main() -> ExitCode {
+    let result = runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .unwrap()
-        .block_on(async_main())
+        .block_on(async_main());
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // В ошибке бывают путь к сессии и другие секреты, печатаем её без них.
+            eprintln!("Error: {}", redact::redact(&e.to_string()));
+            ExitCode::from(exit::code(&e))
+        }
+    }
 }
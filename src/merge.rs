@@ -0,0 +1,114 @@
+use crate::cli::Args;
+use crate::{Result, write_report};
+use parser_nft::error::ParserError;
+use parser_nft::export::{Enrichment, Report};
+use parser_nft::fetch::checkpoint;
+use parser_nft::model::GiftRecord;
+use parser_nft::model::provenance::hide_original_details;
+use parser_nft::model::rarity;
+use parser_nft::report::quality::Quality;
+use parser_nft::store::{self, Store};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Сводит результаты прерванных или разбитых по диапазонам запусков в один отчёт.
+pub fn merge(files: &[PathBuf], args: &Args) -> Result<()> {
+    let mut parts = Vec::new();
+    for file in files {
+        // Из JSON-отчёта не восстановить подарок целиком, как он пришёл из Telegram.
+        if file
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            return Err(ParserError::Config(format!(
+                "{}: merge пока сводит только снимки *.snapshot и состояния parsed_*.state",
+                file.display()
+            )));
+        }
+        // Чьи данные новее, решает время снимка или сохранения состояния.
+        let part = match store::snapshot_time(file) {
+            Some(taken_at) => (taken_at, store::read_gifts(file)?),
+            None => {
+                let saved = checkpoint::read(file)?;
+                let taken_at = match saved.taken_at {
+                    Some(taken_at) => taken_at,
+                    // В состояниях прошлых версий времени нет - остаётся время записи файла.
+                    None => fs::metadata(file)
+                        .and_then(|metadata| metadata.modified())
+                        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", file.display())))?
+                        .into(),
+                };
+                (taken_at, saved.gifts)
+            }
+        };
+        parts.push(part);
+    }
+    // Более новые файлы перекрывают старые.
+    parts.sort_by_key(|(taken_at, _)| *taken_at);
+    let Some(&(newest, _)) = parts.last() else {
+        return Ok(());
+    };
+    let total: usize = parts.iter().map(|(_, gifts)| gifts.len()).sum();
+    let mut gifts = store::merge(parts.into_iter().map(|(_, gifts)| gifts));
+    println!(
+        "Из {total} подарков в {} файлах осталось {} без повторов",
+        files.len(),
+        gifts.len()
+    );
+    if gifts.is_empty() {
+        return Err(ParserError::NoGifts {
+            from: "файлов".to_string(),
+            source: None,
+        });
+    }
+
+    let mut collections = BTreeMap::new();
+    for gift in &gifts {
+        if let Some(name) = store::collection(gift) {
+            collections
+                .entry(name.to_string())
+                .or_insert_with(Vec::new)
+                .push(gift.clone());
+        }
+    }
+    // Объединённый снимок датируется самым свежим файлом: данные не новее него.
+    if let Some(dir) = &args.store {
+        let store = Store::new(dir);
+        for (name, gifts) in &collections {
+            let path = store.save(name, gifts, newest)?;
+            println!("Снимок {name} сохранён в {}", path.display());
+        }
+    }
+
+    if args.hide_original_details {
+        hide_original_details(&mut gifts);
+    }
+    let names: Vec<&str> = collections.keys().map(String::as_str).collect();
+    let run = args.run_info(&names.join(","));
+    let mut quality = Quality::new(&gifts);
+    // Номера разных коллекций пересекаются, сверять их с одним выпуском нельзя.
+    if collections.len() > 1 {
+        quality.without_supply();
+    }
+    let mut gifts: Vec<GiftRecord> = gifts.into_iter().map(GiftRecord::from).collect();
+    rarity::rescore(&mut gifts, args.rarity_formula.into());
+    let enrichment = Enrichment::default();
+    let report_name = args.report_name(&names.join(","), &run);
+    let report = Report {
+        name: &report_name,
+        enrichment: &enrichment,
+        page_size: args.page_size,
+        split_by: args.split_by.map(Into::into),
+        lang: args.lang(),
+        theme: args.theme.into(),
+        css: args.css.as_ref().map(|(_, css)| css.as_str()),
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let dir = args.out_dir.clone().unwrap_or_default();
+    write_report(args, &report, &gifts, &dir)?;
+    Ok(())
+}
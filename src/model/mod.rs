@@ -0,0 +1,25 @@
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use std::collections::HashMap;
+
+pub mod owners;
+pub mod palette;
+pub mod provenance;
+pub mod rarity;
+pub mod record;
+pub mod ton;
+
+pub use record::{Attribute, GiftAttributeSet, GiftRecord, Owner, Transfer};
+
+// Минимальная цена на перепродаже в звёздах по паре «модель, фон».
+pub type FloorPrices = HashMap<(String, String), i64>;
+
+// Название коллекции, например «Plush Pepe».
+pub fn gift_title(gift: &UniqueStarGift) -> Option<String> {
+    match gift {
+        UniqueStarGift::Gift(gift_obj) => match &gift_obj.gift {
+            tl::enums::StarGift::Unique(info) => Some(info.title.clone()),
+            _ => None,
+        },
+    }
+}
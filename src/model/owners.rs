@@ -0,0 +1,47 @@
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::types::{GiftOwner, User};
+
+// Владелец-пользователь подарка, если Telegram (или resolve_owners) прислал его вместе с подарком.
+pub fn owner_user(owner: &GiftOwner, users: &[tl::enums::User]) -> Option<User> {
+    match owner {
+        GiftOwner::Peer {
+            peer: tl::enums::Peer::User(user),
+            ..
+        } => find_user(users, user.user_id),
+        _ => None,
+    }
+}
+
+// Имя пира с @username, если Telegram прислал пользователя вместе с подарком.
+pub fn peer_name(peer: &tl::enums::Peer, users: &[tl::enums::User]) -> String {
+    let user_id = match peer {
+        tl::enums::Peer::User(user) => user.user_id,
+        tl::enums::Peer::Chat(chat) => return format!("chat {}", chat.chat_id),
+        tl::enums::Peer::Channel(channel) => return format!("channel {}", channel.channel_id),
+    };
+    let user = users.iter().find_map(|user| match user {
+        tl::enums::User::User(user) if user.id == user_id => Some(user),
+        _ => None,
+    });
+    let Some(user) = user else {
+        return format!("user {user_id}");
+    };
+
+    let name = [user.first_name.as_deref(), user.last_name.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    match &user.username {
+        Some(username) => format!("{name} (@{username}, id {user_id})"),
+        None => format!("{name} (id {user_id})"),
+    }
+}
+
+pub(crate) fn find_user(users: &[tl::enums::User], id: i64) -> Option<User> {
+    users
+        .iter()
+        .find(|user| matches!(user, tl::enums::User::User(user) if user.id == id))
+        .cloned()
+        .map(User::from_raw)
+}
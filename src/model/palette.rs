@@ -0,0 +1,26 @@
+use grammers_client::types::GiftBackdrop;
+
+// Палитра фона подарка в виде готовых для CSS hex-строк.
+#[derive(Clone, Debug)]
+pub struct Palette {
+    pub center_color: String,
+    pub edge_color: String,
+    pub pattern_color: String,
+    pub text_color: String,
+}
+
+impl Palette {
+    pub fn from_backdrop(backdrop: &GiftBackdrop) -> Self {
+        Self {
+            center_color: hex(backdrop.center_color),
+            edge_color: hex(backdrop.edge_color),
+            pattern_color: hex(backdrop.pattern_color),
+            text_color: hex(backdrop.text_color),
+        }
+    }
+}
+
+// Цвет как RGB, упакованный в число.
+fn hex(color: u32) -> String {
+    format!("#{color:06x}")
+}
@@ -0,0 +1,41 @@
+use crate::model::owners::peer_name;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::types::UniqueGift;
+
+// Кто, кому и когда подарил подарок до улучшения - одной строкой для отчёта.
+// Сообщение отправителя отдельно: его нужно показывать целиком, а не в скобках.
+#[derive(Clone, Debug)]
+pub struct Provenance {
+    pub summary: String,
+    pub message: Option<String>,
+}
+
+// Исходные данные подарка, если владелец оставил их при улучшении.
+pub fn provenance(gift: &UniqueGift, users: &[tl::enums::User]) -> Option<Provenance> {
+    let details = gift.original_details()?;
+    let sender = match &details.sender {
+        Some(sender) => peer_name(sender, users),
+        None => "анонима".to_string(),
+    };
+    Some(Provenance {
+        summary: format!(
+            "от {sender} для {}, {}",
+            peer_name(&details.recipient, users),
+            details.date.format("%Y-%m-%d")
+        ),
+        message: details.message.filter(|message| !message.is_empty()),
+    })
+}
+
+// Убирает из подарков отправителя, получателя, дату и сообщение - для отчётов,
+// которые публикуются: владелец мог оставить их на подарке, но не для всех.
+pub fn hide_original_details(gifts: &mut [UniqueStarGift]) {
+    for UniqueStarGift::Gift(gift) in gifts {
+        if let tl::enums::StarGift::Unique(gift) = &mut gift.gift {
+            gift.attributes.retain(|attribute| {
+                !matches!(attribute, tl::enums::StarGiftAttribute::OriginalDetails(_))
+            });
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::model::{GiftAttributeSet, GiftRecord};
+use std::cmp::Ordering;
+
+// Как свести редкость модели, фона и узора в одно число. Все формулы дают
+// «один подарок из N»: чем больше, тем реже.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RarityFormula {
+    // Редкость всего сочетания, если атрибуты выпадают независимо.
+    #[default]
+    Product,
+    // Редкость самого редкого из трёх атрибутов.
+    Min,
+    // Взвешенное среднее геометрическое: модель важнее фона, фон важнее узора.
+    Weighted,
+}
+
+// Веса модели, фона и узора для Weighted, в сумме 1.
+const WEIGHTS: [f64; 3] = [0.5, 0.3, 0.2];
+
+impl RarityFormula {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RarityFormula::Product => "product",
+            RarityFormula::Min => "min",
+            RarityFormula::Weighted => "weighted",
+        }
+    }
+}
+
+// Оценка редкости подарка по формуле `formula`.
+// None, если Telegram прислал не все атрибуты.
+pub fn rarity_score(attributes: &GiftAttributeSet, formula: RarityFormula) -> Option<f64> {
+    let mut chances = [0.0; 3];
+    for (chance, attribute) in
+        chances
+            .iter_mut()
+            .zip([&attributes.model, &attributes.backdrop, &attributes.pattern])
+    {
+        // Самые редкие атрибуты Telegram округляет до 0‰ - считаем их за 0.1‰.
+        let permille = attribute.as_ref()?.rarity_permille as f64;
+        *chance = permille.max(0.1) / 1000.0;
+    }
+    let score = match formula {
+        RarityFormula::Product => 1.0 / chances.iter().product::<f64>(),
+        RarityFormula::Min => 1.0 / chances.iter().copied().fold(1.0, f64::min),
+        RarityFormula::Weighted => chances
+            .iter()
+            .zip(WEIGHTS)
+            .map(|(chance, weight)| chance.powf(-weight))
+            .product(),
+    };
+    Some(score)
+}
+
+// Пересчитывает оценки подарков по другой формуле.
+pub fn rescore(gifts: &mut [GiftRecord], formula: RarityFormula) {
+    for gift in gifts {
+        gift.rarity_score = rarity_score(&gift.attributes, formula);
+    }
+}
+
+// Оставляет подарки с оценкой не ниже `min_score` и, если `sort`, ставит
+// самые редкие первыми. Подарки без оценки фильтр не проходят и при сортировке идут в конце.
+pub fn select(gifts: &mut Vec<GiftRecord>, min_score: Option<f64>, sort: bool) {
+    if let Some(min_score) = min_score {
+        gifts.retain(|gift| gift.rarity_score.is_some_and(|score| score >= min_score));
+    }
+    if sort {
+        // sort_by стабильная: при равной оценке порядок по номерам сохраняется.
+        gifts.sort_by(|a, b| match (a.rarity_score, b.rarity_score) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    }
+}
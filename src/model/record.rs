@@ -0,0 +1,305 @@
+use crate::model::owners::owner_user;
+use crate::model::palette::Palette;
+use crate::model::provenance::{Provenance, provenance};
+use crate::model::rarity::{RarityFormula, rarity_score};
+use chrono::{DateTime, Utc};
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::types::{Chat, GiftOwner, SavedStarGift, UniqueGift};
+
+// Подарок в том виде, в каком его показывают отчёты. Форматы вывода работают
+// только с ним, а не с типами TL: новый слой Telegram меняет одну конвертацию здесь.
+#[derive(Clone, Debug, Default)]
+pub struct GiftRecord {
+    // Например «PlushPepe-1»; подарок открывается по https://t.me/nft/{slug}.
+    pub slug: String,
+    pub num: i32,
+    // Название коллекции, например «Plush Pepe».
+    pub title: String,
+    pub attributes: GiftAttributeSet,
+    pub owner: Owner,
+    // Цена перепродажи в звёздах, если подарок выставлен на продажу.
+    pub resale_price: Option<i64>,
+    // Адреса в блокчейне TON, если подарок туда выведен.
+    pub gift_address: Option<String>,
+    pub owner_address: Option<String>,
+    // Кто кому подарил подарок до улучшения, если владелец это оставил.
+    pub provenance: Option<Provenance>,
+    // Оценка редкости сочетания атрибутов, см. rarity::rarity_score.
+    pub rarity_score: Option<f64>,
+    // Условия передачи; известны, только если подарки взяты из своего профиля
+    // или профиля своего канала.
+    pub transfer: Option<Transfer>,
+}
+
+// Когда и за сколько владелец может передать подарок. Telegram сообщает это
+// только самому владельцу.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transfer {
+    // Стоимость передачи в звёздах; None - передача бесплатна.
+    pub stars: Option<i64>,
+    // Раньше этого времени подарок нельзя передать или вывести в блокчейн TON.
+    pub transfer_after: Option<DateTime<Utc>>,
+    pub export_after: Option<DateTime<Utc>>,
+}
+
+// Модель, фон и узор подарка. У улучшенного подарка они есть всегда, но
+// Telegram может прислать не все - тогда поле пустое.
+#[derive(Clone, Debug, Default)]
+pub struct GiftAttributeSet {
+    pub model: Option<Attribute>,
+    pub backdrop: Option<Attribute>,
+    pub pattern: Option<Attribute>,
+    // Цвета фона, если они пришли вместе с подарком.
+    pub palette: Option<Palette>,
+}
+
+// Один атрибут и то, у скольких подарков на тысячу он встречается.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub rarity_permille: i32,
+}
+
+// Владелец подарка. Имя и username пользователя известны, если Telegram
+// (или resolve_owners) прислал его профиль вместе с подарком; название и
+// username канала - если подарки получены из профиля самого канала.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Owner {
+    User {
+        id: i64,
+        name: Option<String>,
+        username: Option<String>,
+    },
+    Chat {
+        id: i64,
+        name: Option<String>,
+    },
+    Channel {
+        id: i64,
+        name: Option<String>,
+        username: Option<String>,
+    },
+    // Аккаунта нет, только имя, которое владелец решил показать.
+    Name(String),
+    // Подарок выведен в блокчейн TON, известен только кошелёк.
+    Address(String),
+    #[default]
+    Hidden,
+}
+
+impl GiftRecord {
+    pub fn new(gift: &UniqueGift, users: &[tl::enums::User]) -> Self {
+        let backdrop = gift.backdrop();
+        let mut record = Self {
+            slug: gift.slug().to_string(),
+            num: gift.num(),
+            title: gift.title().to_string(),
+            attributes: GiftAttributeSet {
+                model: gift.model().map(|model| Attribute {
+                    name: model.name,
+                    rarity_permille: model.rarity_permille,
+                }),
+                pattern: gift.pattern().map(|pattern| Attribute {
+                    name: pattern.name,
+                    rarity_permille: pattern.rarity_permille,
+                }),
+                palette: backdrop.as_ref().map(Palette::from_backdrop),
+                backdrop: backdrop.map(|backdrop| Attribute {
+                    name: backdrop.name,
+                    rarity_permille: backdrop.rarity_permille,
+                }),
+            },
+            owner: Owner::new(gift, users),
+            resale_price: gift.resale_price(),
+            gift_address: gift.gift_address().map(str::to_string),
+            owner_address: gift.owner_address().map(str::to_string),
+            provenance: provenance(gift, users),
+            rarity_score: None,
+            transfer: None,
+        };
+        record.rarity_score = rarity_score(&record.attributes, RarityFormula::default());
+        record
+    }
+
+    pub fn link(&self) -> String {
+        format!("https://t.me/nft/{}", self.slug)
+    }
+}
+
+impl From<UniqueStarGift> for GiftRecord {
+    fn from(gift: UniqueStarGift) -> Self {
+        let UniqueStarGift::Gift(gift) = gift;
+        match UniqueGift::from_star_gift(gift.gift) {
+            Some(unique) => Self::new(&unique, &gift.users),
+            // Не улучшенный подарок: показать в отчёте нечего.
+            None => Self::default(),
+        }
+    }
+}
+
+impl Transfer {
+    // Можно ли передать подарок в момент `now`.
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.transfer_after.is_none_or(|after| after <= now)
+    }
+}
+
+impl From<&SavedStarGift> for Transfer {
+    fn from(gift: &SavedStarGift) -> Self {
+        Self {
+            stars: gift.transfer_stars().filter(|stars| *stars > 0),
+            transfer_after: gift.can_transfer_at(),
+            export_after: gift.can_export_at(),
+        }
+    }
+}
+
+impl GiftAttributeSet {
+    // Название модели, или пустая строка, если Telegram её не прислал.
+    pub fn model_name(&self) -> &str {
+        self.model.as_ref().map_or("", |model| &model.name)
+    }
+
+    pub fn backdrop_name(&self) -> &str {
+        self.backdrop.as_ref().map_or("", |backdrop| &backdrop.name)
+    }
+}
+
+impl Owner {
+    fn new(gift: &UniqueGift, users: &[tl::enums::User]) -> Self {
+        let owner = gift.owner();
+        if let Some(user) = owner_user(&owner, users) {
+            let name = match owner {
+                GiftOwner::Peer {
+                    name: Some(name), ..
+                } => name,
+                _ => user.full_name(),
+            };
+            return Owner::User {
+                id: user.id(),
+                name: Some(name),
+                username: user.username().map(str::to_string),
+            };
+        }
+
+        match owner {
+            GiftOwner::Peer { peer, name } => match peer {
+                tl::enums::Peer::User(user) => Owner::User {
+                    id: user.user_id,
+                    name,
+                    username: None,
+                },
+                tl::enums::Peer::Chat(chat) => Owner::Chat {
+                    id: chat.chat_id,
+                    name,
+                },
+                tl::enums::Peer::Channel(channel) => Owner::Channel {
+                    id: channel.channel_id,
+                    name,
+                    username: None,
+                },
+            },
+            GiftOwner::Name(name) => Owner::Name(name),
+            GiftOwner::Address(address) => Owner::Address(address),
+            GiftOwner::Hidden => Owner::Hidden,
+        }
+    }
+
+    // Имя владельца с @username, а если имени нет - id пира.
+    // None, если владелец скрыт или известен только кошелёк.
+    pub fn display_name(&self) -> Option<String> {
+        let with_username = |name: &Option<String>, username: &Option<String>, fallback| {
+            let name = name.as_deref().unwrap_or_default();
+            match username {
+                Some(username) if name.is_empty() => format!("@{username}"),
+                Some(username) => format!("{name} (@{username})"),
+                None if name.is_empty() => fallback,
+                None => name.to_string(),
+            }
+        };
+        match self {
+            Owner::User { id, name, username } => {
+                Some(with_username(name, username, format!("user {id}")))
+            }
+            Owner::Channel { id, name, username } => {
+                Some(with_username(name, username, format!("channel {id}")))
+            }
+            Owner::Chat {
+                name: Some(name), ..
+            }
+            | Owner::Name(name) => Some(name.clone()),
+            Owner::Chat { id, name: None } => Some(format!("chat {id}")),
+            Owner::Address(_) | Owner::Hidden => None,
+        }
+    }
+
+    // Ссылка на профиль: t.me по username, у пользователя без username - tg://user.
+    // У канала без username публичной ссылки нет.
+    pub fn link(&self) -> Option<String> {
+        match self {
+            Owner::User {
+                username: Some(username),
+                ..
+            }
+            | Owner::Channel {
+                username: Some(username),
+                ..
+            } => Some(format!("https://t.me/{username}")),
+            Owner::User { id, .. } => Some(format!("tg://user?id={id}")),
+            _ => None,
+        }
+    }
+
+    // Вид владельца одним словом - для машиночитаемых форматов.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Owner::User { .. } => "user",
+            Owner::Chat { .. } => "chat",
+            Owner::Channel { .. } => "channel",
+            Owner::Name(_) => "name",
+            Owner::Address(_) => "address",
+            Owner::Hidden => "hidden",
+        }
+    }
+
+    // ID пользователя, чата или канала.
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            Owner::User { id, .. } | Owner::Chat { id, .. } | Owner::Channel { id, .. } => {
+                Some(*id)
+            }
+            Owner::Name(_) | Owner::Address(_) | Owner::Hidden => None,
+        }
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        match self {
+            Owner::User { username, .. } | Owner::Channel { username, .. } => username.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+// Владелец по чату, который известен целиком, - например, профилю из `owner`.
+impl From<&Chat> for Owner {
+    fn from(chat: &Chat) -> Self {
+        let name = chat.name().map(str::to_string);
+        match chat {
+            Chat::User(user) => Owner::User {
+                id: user.id(),
+                name: Some(user.full_name()),
+                username: user.username().map(str::to_string),
+            },
+            Chat::Group(group) => Owner::Chat {
+                id: group.id(),
+                name,
+            },
+            Chat::Channel(channel) => Owner::Channel {
+                id: channel.id(),
+                name,
+                username: channel.username().map(str::to_string),
+            },
+        }
+    }
+}
@@ -0,0 +1,8 @@
+// Обозреватель блокчейна TON, в котором открываются адреса подарков и кошельков.
+const EXPLORER: &str = "https://tonviewer.com";
+
+// Ссылка на адрес в обозревателе. Адреса TON бывают в base64url
+// («EQ...», «UQ...») или в сыром виде («0:abc...») - оба годятся в URL как есть.
+pub fn explorer_url(address: &str) -> String {
+    format!("{EXPLORER}/{}", address.trim())
+}
@@ -0,0 +1,160 @@
+use crate::Result;
+use crate::owner::chat_name;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use grammers_client::Client;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::types::SavedStarGift;
+use parser_nft::report::json_string;
+
+// Сколько обычный подарок можно обменять на звёзды после получения
+// (stargifts_convert_period_max в настройках Telegram).
+const CONVERT_PERIOD: Duration = Duration::days(90);
+// Срок обмена, который истекает раньше, чем через столько, выделяется.
+const CONVERT_SOON: Duration = Duration::days(7);
+
+// Все подарки в своём профиле, включая скрытые: уникальные (NFT) и обычные.
+// У обычных видно, до какого числа их можно обменять на звёзды; скоро
+// истекающие сроки отмечены «⚠».
+pub async fn my_gifts(client: &Client, json: bool) -> Result<()> {
+    let me = client.get_me().await?;
+    let mut saved = client.iter_saved_star_gifts(&me);
+    let mut gifts = Vec::new();
+    while let Some(gift) = saved.next().await? {
+        gifts.push(gift);
+    }
+    if json {
+        let gifts: Vec<String> = gifts
+            .iter()
+            .map(|gift| format!("  {}", to_json(gift)))
+            .collect();
+        if gifts.is_empty() {
+            println!("[]");
+        } else {
+            println!("[\n{}\n]", gifts.join(",\n"));
+        }
+        return Ok(());
+    }
+
+    if gifts.is_empty() {
+        println!("В профиле нет подарков");
+        return Ok(());
+    }
+    let unique = gifts.iter().filter(|gift| gift.unique().is_some()).count();
+    println!(
+        "Подарков в профиле: {} (уникальных {unique}, обычных {})",
+        gifts.len(),
+        gifts.len() - unique
+    );
+    let now = Utc::now();
+    let expiring = gifts
+        .iter()
+        .filter_map(conversion)
+        .filter(|(_, until)| *until > now && *until - now < CONVERT_SOON)
+        .count();
+    if expiring > 0 {
+        println!("⚠ Срок обмена на звёзды истекает в ближайшую неделю: {expiring}");
+    }
+    for gift in &gifts {
+        print_gift(gift, now);
+    }
+    Ok(())
+}
+
+fn print_gift(gift: &SavedStarGift, now: DateTime<Utc>) {
+    let mut marks = Vec::new();
+    if gift.is_pinned() {
+        marks.push("закреплён");
+    }
+    if !gift.is_saved() {
+        marks.push("скрыт из профиля");
+    }
+    let marks = if marks.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", marks.join(", "))
+    };
+    match gift.gift() {
+        tl::enums::StarGift::Unique(unique) => println!(
+            "NFT {} #{}{marks} - https://t.me/nft/{}",
+            unique.title, unique.num, unique.slug
+        ),
+        tl::enums::StarGift::Gift(regular) => println!(
+            "Обычный подарок «{}» за {} ⭐{marks}",
+            title(regular),
+            regular.stars
+        ),
+    }
+
+    let from = gift
+        .sender()
+        .map(chat_name)
+        .unwrap_or_else(|| "скрытый отправитель".to_string());
+    let id = gift
+        .msg_id()
+        .map(|id| format!(", id {id}"))
+        .unwrap_or_default();
+    println!("  от {from}, {}{id}", gift.date().format("%Y-%m-%d"));
+
+    if let Some((stars, until)) = conversion(gift) {
+        let left = until - now;
+        if left <= Duration::zero() {
+            println!(
+                "  срок обмена на {stars} ⭐ истёк {}",
+                until.format("%Y-%m-%d")
+            );
+        } else {
+            let warning = if left < CONVERT_SOON { "⚠ " } else { "" };
+            println!(
+                "  {warning}можно обменять на {stars} ⭐ до {} (осталось дней: {})",
+                until.format("%Y-%m-%d"),
+                left.num_days()
+            );
+        }
+    }
+    if gift.can_upgrade() {
+        match gift.prepaid_upgrade_stars() {
+            Some(_) => println!("  можно улучшить бесплатно: улучшение оплачено отправителем"),
+            None => println!("  можно улучшить до уникального"),
+        }
+    }
+}
+
+// Сколько звёзд дадут за обычный подарок и до какого времени его можно обменять.
+fn conversion(gift: &SavedStarGift) -> Option<(i64, DateTime<Utc>)> {
+    let stars = gift.convert_stars().filter(|_| gift.unique().is_none())?;
+    Some((stars, gift.date() + CONVERT_PERIOD))
+}
+
+fn title(gift: &tl::types::StarGift) -> String {
+    gift.title
+        .clone()
+        .unwrap_or_else(|| format!("id {}", gift.id))
+}
+
+fn to_json(gift: &SavedStarGift) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    let time = |time: DateTime<Utc>| json_string(&time.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let (kind, title, slug) = match gift.gift() {
+        tl::enums::StarGift::Unique(unique) => (
+            "nft",
+            format!("{} #{}", unique.title, unique.num),
+            Some(json_string(&unique.slug)),
+        ),
+        tl::enums::StarGift::Gift(regular) => ("gift", title(regular), None),
+    };
+    let conversion = conversion(gift);
+    format!(
+        "{{\"kind\": \"{kind}\", \"title\": {}, \"slug\": {}, \"pinned\": {}, \"hidden\": {}, \"from\": {}, \"date\": {}, \"msg_id\": {}, \"convert_stars\": {}, \"convert_until\": {}, \"can_upgrade\": {}, \"prepaid_upgrade_stars\": {}}}",
+        json_string(&title),
+        optional(slug),
+        gift.is_pinned(),
+        !gift.is_saved(),
+        optional(gift.sender().map(|chat| json_string(&chat_name(chat)))),
+        time(gift.date()),
+        optional(gift.msg_id().map(|id| id.to_string())),
+        optional(conversion.map(|(stars, _)| stars.to_string())),
+        optional(conversion.map(|(_, until)| time(until))),
+        gift.can_upgrade(),
+        optional(gift.prepaid_upgrade_stars().map(|stars| stars.to_string()))
+    )
+}
@@ -0,0 +1,128 @@
+use crate::export::json::{gift_json, owner_json};
+use crate::model::GiftRecord;
+use crate::report::RunInfo;
+use crate::report::diff::{Changes, OwnerChange};
+use crate::report::json_string;
+use crate::report::quality::Quality;
+use crate::report::stats::Stats;
+
+pub mod rules;
+pub mod telegram;
+pub mod webhook;
+
+// Событие запуска, о котором сообщается наружу.
+pub enum Event<'a> {
+    // Парсинг коллекции закончен, отчёт записан.
+    RunCompleted {
+        collection: &'a str,
+        run: &'a RunInfo,
+        quality: &'a Quality,
+        stats: &'a Stats,
+    },
+    // С прошлого снимка появились новые подарки.
+    NewGifts {
+        collection: &'a str,
+        gifts: &'a [GiftRecord],
+    },
+    // С прошлого снимка у подарков сменился владелец.
+    OwnerChanged {
+        collection: &'a str,
+        changes: &'a [OwnerChange],
+    },
+    // С прошлого снимка подарки выставили на продажу.
+    ResaleListed {
+        collection: &'a str,
+        gifts: &'a [GiftRecord],
+    },
+}
+
+impl<'a> Event<'a> {
+    // События по разнице со снимком прошлого запуска; пустых не бывает.
+    pub fn from_changes(collection: &'a str, changes: &'a Changes) -> Vec<Self> {
+        let mut events = Vec::new();
+        if !changes.new_gifts.is_empty() {
+            events.push(Event::NewGifts {
+                collection,
+                gifts: &changes.new_gifts,
+            });
+        }
+        if !changes.owner_changes.is_empty() {
+            events.push(Event::OwnerChanged {
+                collection,
+                changes: &changes.owner_changes,
+            });
+        }
+        if !changes.listed.is_empty() {
+            events.push(Event::ResaleListed {
+                collection,
+                gifts: &changes.listed,
+            });
+        }
+        events
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::RunCompleted { .. } => "run_completed",
+            Event::NewGifts { .. } => "new_gifts",
+            Event::OwnerChanged { .. } => "owner_changed",
+            Event::ResaleListed { .. } => "resale_listed",
+        }
+    }
+
+    // Тело события в JSON. Подарки в нём - те же объекты, что в parsed.json.
+    pub fn to_json(&self) -> String {
+        let (collection, body) = match self {
+            Event::RunCompleted {
+                collection,
+                run,
+                quality,
+                stats,
+            } => (
+                collection,
+                format!(
+                    "  \"run\": {},\n  \"quality\": {},\n  \"stats\": {}",
+                    run.to_json().trim_end().replace('\n', "\n  "),
+                    quality.to_json(),
+                    stats.to_json()
+                ),
+            ),
+            Event::NewGifts { collection, gifts } | Event::ResaleListed { collection, gifts } => (
+                collection,
+                format!(
+                    "  \"gifts\": {}",
+                    list(gifts.iter().map(|gift| gift_json(gift, None)))
+                ),
+            ),
+            Event::OwnerChanged {
+                collection,
+                changes,
+            } => (
+                collection,
+                format!(
+                    "  \"changes\": {}",
+                    list(changes.iter().map(|change| format!(
+                        "{{\"gift\": {}, \"previous_owner\": {}}}",
+                        gift_json(&change.gift, None),
+                        owner_json(&change.previous)
+                    )))
+                ),
+            ),
+        };
+        format!(
+            "{{\n  \"event\": {},\n  \"collection\": {},\n{body}\n}}\n",
+            json_string(self.name()),
+            json_string(collection)
+        )
+    }
+}
+
+// Массив JSON, по элементу на строке.
+fn list(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.map(|item| format!("    {item}")).collect();
+    if items.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", items.join(",\n"))
+    }
+}
@@ -0,0 +1,330 @@
+use crate::error::{ParserError, Result};
+use crate::model::rarity::{RarityFormula, rarity_score};
+use crate::model::{Attribute, GiftRecord};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+// Правила оповещения о новых подарках (--alert-rules): в оповещение попадают
+// только подарки, подошедшие хотя бы под одно правило. Без правил новых
+// подарков в оповещении нет.
+//
+// Формат файла - по правилу на строку, `#` - комментарий. Условия в строке
+// соединяются через and и or (and связывает сильнее), строки - как через or:
+//   rarity < 0.5% or model == 'Golden'
+//   backdrop == Black and pattern < 1%
+//   num <= 100
+// Поля: rarity - доля всего сочетания модели, фона и узора в процентах;
+// model, backdrop, pattern - название (== и !=, без учёта регистра) или
+// доля атрибута в процентах (<, <=, >, >=); num - номер подарка.
+#[derive(Debug, Default)]
+pub struct AlertRules {
+    // Варианты всех строк вместе (то, что между or): подарок подходит, если
+    // выполнены все условия хотя бы одного варианта.
+    alternatives: Vec<Vec<Condition>>,
+}
+
+#[derive(Debug)]
+enum Condition {
+    // Доля сочетания в процентах.
+    Rarity(Comparison, f64),
+    // Название атрибута равно `name` (в нижнем регистре) или, если не `equal`, не равно.
+    Name {
+        kind: AttributeKind,
+        equal: bool,
+        name: String,
+    },
+    // Доля атрибута в процентах.
+    Share(AttributeKind, Comparison, f64),
+    Number(Comparison, i32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AttributeKind {
+    Model,
+    Backdrop,
+    Pattern,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+// Сначала двухсимвольные, чтобы «<=» не разобрался как «<».
+const OPERATORS: [(&str, Comparison); 6] = [
+    ("<=", Comparison::LessOrEqual),
+    (">=", Comparison::GreaterOrEqual),
+    ("==", Comparison::Equal),
+    ("!=", Comparison::NotEqual),
+    ("<", Comparison::Less),
+    (">", Comparison::Greater),
+];
+
+impl AlertRules {
+    // Загружает правила из файла; ошибка в правиле - ошибка настроек с номером строки.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| ParserError::Config(format!("{}:{e}", path.display())))
+    }
+
+    // Разбирает текст файла правил. Ошибка - «<строка>: <что не так>».
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut alternatives = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            // `#` в кавычках - часть названия, а не начало комментария.
+            let line = match unquoted(line).find(|&(_, c)| c == '#') {
+                Some((at, _)) => &line[..at],
+                None => line,
+            }
+            .trim();
+            if line.is_empty() {
+                continue;
+            }
+            for alternative in split(line, "or") {
+                let conditions = split(alternative, "and")
+                    .into_iter()
+                    .map(condition)
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(|e| format!("{}: {e}", n + 1))?;
+                alternatives.push(conditions);
+            }
+        }
+        Ok(Self { alternatives })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.is_empty()
+    }
+
+    // Подходит ли подарок хотя бы под одно правило.
+    pub fn matches(&self, gift: &GiftRecord) -> bool {
+        self.alternatives
+            .iter()
+            .any(|conditions| conditions.iter().all(|condition| condition.matches(gift)))
+    }
+
+    // Подарки из `gifts`, которые подошли под правила.
+    pub fn select(&self, gifts: &[GiftRecord]) -> Vec<GiftRecord> {
+        gifts
+            .iter()
+            .filter(|gift| self.matches(gift))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Condition {
+    fn matches(&self, gift: &GiftRecord) -> bool {
+        let attributes = &gift.attributes;
+        match self {
+            // Доля сочетания не зависит от --rarity-formula: это вероятность
+            // выпадения всех трёх атрибутов сразу.
+            Condition::Rarity(comparison, percent) => {
+                rarity_score(attributes, RarityFormula::Product)
+                    .is_some_and(|score| comparison.holds(100.0 / score, *percent))
+            }
+            Condition::Name { kind, equal, name } => {
+                let actual = kind.of(gift).map_or("", |attribute| &attribute.name);
+                (actual.to_lowercase() == *name) == *equal
+            }
+            Condition::Share(kind, comparison, percent) => kind.of(gift).is_some_and(|attribute| {
+                comparison.holds(attribute.rarity_permille as f64 / 10.0, *percent)
+            }),
+            Condition::Number(comparison, num) => comparison.holds(gift.num, *num),
+        }
+    }
+}
+
+impl AttributeKind {
+    fn of(self, gift: &GiftRecord) -> Option<&Attribute> {
+        let attributes = &gift.attributes;
+        match self {
+            AttributeKind::Model => attributes.model.as_ref(),
+            AttributeKind::Backdrop => attributes.backdrop.as_ref(),
+            AttributeKind::Pattern => attributes.pattern.as_ref(),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds<T: PartialOrd>(self, actual: T, expected: T) -> bool {
+        let Some(ordering) = actual.partial_cmp(&expected) else {
+            return false;
+        };
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::NotEqual => ordering != Ordering::Equal,
+        }
+    }
+}
+
+// Символы строки вне кавычек вместе с их позициями; сами кавычки пропускаются.
+fn unquoted(line: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quote = None;
+    line.char_indices().filter(move |&(_, c)| match quote {
+        Some(q) => {
+            if c == q {
+                quote = None;
+            }
+            false
+        }
+        None if c == '\'' || c == '"' => {
+            quote = Some(c);
+            false
+        }
+        None => true,
+    })
+}
+
+// Режет строку по слову `word` (and или or) вне кавычек.
+fn split<'a>(line: &'a str, word: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c) in unquoted(line).filter(|(_, c)| c.is_whitespace()) {
+        let rest = &line[i + c.len_utf8()..];
+        let is_word = rest
+            .get(..word.len())
+            .is_some_and(|next| next.eq_ignore_ascii_case(word))
+            && rest[word.len()..].starts_with(char::is_whitespace);
+        if is_word {
+            parts.push(line[start..i].trim());
+            start = i + c.len_utf8() + word.len();
+        }
+    }
+    parts.push(line[start..].trim());
+    parts
+}
+
+// Одно условие: «поле оператор значение».
+fn condition(text: &str) -> std::result::Result<Condition, String> {
+    let Some(at) = text.find(['<', '>', '=', '!']) else {
+        return Err(format!("«{text}»: нет оператора сравнения"));
+    };
+    let field = text[..at].trim().to_lowercase();
+    let rest = &text[at..];
+    let Some((operator, comparison)) = OPERATORS
+        .into_iter()
+        .find(|(operator, _)| rest.starts_with(operator))
+    else {
+        return Err(format!("«{text}»: неизвестный оператор"));
+    };
+    let value = rest[operator.len()..].trim();
+    let is_name = matches!(comparison, Comparison::Equal | Comparison::NotEqual);
+
+    let kind = match field.as_str() {
+        "model" => Some(AttributeKind::Model),
+        "backdrop" => Some(AttributeKind::Backdrop),
+        "pattern" => Some(AttributeKind::Pattern),
+        "rarity" | "num" => None,
+        _ => {
+            return Err(format!(
+                "«{field}»: ожидается rarity, model, backdrop, pattern или num"
+            ));
+        }
+    };
+    match (kind, field.as_str()) {
+        (Some(kind), _) if is_name => {
+            let name = unquote(value);
+            if name.is_empty() {
+                return Err(format!("«{text}»: пустое название"));
+            }
+            Ok(Condition::Name {
+                kind,
+                equal: comparison == Comparison::Equal,
+                name: name.to_lowercase(),
+            })
+        }
+        (Some(kind), _) => Ok(Condition::Share(kind, comparison, percent(value)?)),
+        (None, "rarity") => Ok(Condition::Rarity(comparison, percent(value)?)),
+        _ => value
+            .parse()
+            .map(|num| Condition::Number(comparison, num))
+            .map_err(|_| format!("«{value}»: ожидается номер подарка")),
+    }
+}
+
+// «0.5%» или «0.5» - проценты.
+fn percent(value: &str) -> std::result::Result<f64, String> {
+    value
+        .trim_end_matches('%')
+        .trim()
+        .parse()
+        .map_err(|_| format!("«{value}»: ожидается доля в процентах, например 0.5%"))
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::GiftAttributeSet;
+
+    fn gift(num: i32, model: &str) -> GiftRecord {
+        GiftRecord {
+            num,
+            attributes: GiftAttributeSet {
+                model: Some(Attribute {
+                    name: model.to_string(),
+                    rarity_permille: 10,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    // and связывает сильнее or: «a or b and c» - это «a or (b and c)».
+    #[test]
+    fn precedence() {
+        let rules = AlertRules::parse("num < 10 or num > 100 and model == Gold").unwrap();
+        assert!(rules.matches(&gift(5, "Onyx")));
+        assert!(rules.matches(&gift(200, "Gold")));
+        assert!(!rules.matches(&gift(200, "Onyx")));
+        assert!(!rules.matches(&gift(50, "Gold")));
+    }
+
+    // `#`, and и or в кавычках - часть названия.
+    #[test]
+    fn quoted() {
+        let rules = AlertRules::parse("model == 'Gold #1' # редкая модель").unwrap();
+        assert!(rules.matches(&gift(1, "gold #1")));
+        assert!(!rules.matches(&gift(1, "Gold")));
+        let rules = AlertRules::parse("model == \"Salt and Pepper\" or num == 7").unwrap();
+        assert!(rules.matches(&gift(1, "Salt and Pepper")));
+        assert!(rules.matches(&gift(7, "Onyx")));
+        assert!(!rules.matches(&gift(1, "Salt")));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            AlertRules::parse("num < 10\nprice > 5").unwrap_err(),
+            "2: «price»: ожидается rarity, model, backdrop, pattern или num"
+        );
+        assert_eq!(
+            AlertRules::parse("model ~ Gold").unwrap_err(),
+            "1: «model ~ Gold»: нет оператора сравнения"
+        );
+        assert!(AlertRules::parse("# пусто\n\n").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::model::{GiftRecord, Owner};
+use crate::notify::rules::AlertRules;
+use crate::report::diff::Changes;
+use grammers_client::types::PackedChat;
+use grammers_client::{Client, InvocationError};
+
+// Telegram не принимает сообщения длиннее 4096 символов.
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+// Оповещение о новых подарках, сменах владельцев и новых лотах на перепродаже
+// одним или несколькими сообщениями. Пусто, если ничего из этого не было.
+// Новые подарки попадают сюда, только если подошли под правила `rules`
+// (--alert-rules): без них это была бы вся коллекция при первом парсинге.
+pub fn alert_messages(collection: &str, changes: &Changes, rules: &AlertRules) -> Vec<String> {
+    let mut lines = Vec::new();
+    let minted = rules.select(&changes.new_gifts);
+    if !minted.is_empty() {
+        lines.push(format!("Новые подарки по правилам ({}):", minted.len()));
+        for gift in &minted {
+            lines.push(format!("• {}\n  {}", gift_name(gift), gift.link()));
+        }
+    }
+    if !changes.owner_changes.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!(
+            "Сменили владельца ({}):",
+            changes.owner_changes.len()
+        ));
+        for change in &changes.owner_changes {
+            lines.push(format!(
+                "• {}: {} → {}\n  {}",
+                gift_name(&change.gift),
+                owner_name(&change.previous),
+                owner_name(&change.gift.owner),
+                change.gift.link()
+            ));
+        }
+    }
+    if !changes.listed.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("Выставлены на продажу ({}):", changes.listed.len()));
+        for gift in &changes.listed {
+            lines.push(format!(
+                "• {}: {} ⭐\n  {}",
+                gift_name(gift),
+                gift.resale_price.unwrap_or_default(),
+                gift.link()
+            ));
+        }
+    }
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    // Длинный список режется по строкам, чтобы подарок не разрывался между сообщениями.
+    let mut messages = vec![format!("🔔 {collection}: изменения с прошлого снимка\n")];
+    for line in lines {
+        let last = messages.last_mut().expect("хотя бы одно сообщение есть");
+        if last.chars().count() + line.chars().count() + 1 > MAX_MESSAGE_CHARS {
+            messages.push(line);
+        } else {
+            last.push('\n');
+            last.push_str(&line);
+        }
+    }
+    messages
+}
+
+// Отправляет сообщения оповещения в чат по порядку.
+pub async fn send_alert(
+    client: &Client,
+    chat: PackedChat,
+    messages: &[String],
+) -> Result<(), InvocationError> {
+    for message in messages {
+        client.send_message(chat, message.as_str()).await?;
+    }
+    Ok(())
+}
+
+// «PlushPepe-2 (Gold, Onyx)».
+fn gift_name(gift: &GiftRecord) -> String {
+    let attributes = &gift.attributes;
+    format!(
+        "{} ({}, {})",
+        gift.slug,
+        attributes.model_name(),
+        attributes.backdrop_name()
+    )
+}
+
+fn owner_name(owner: &Owner) -> String {
+    owner
+        .display_name()
+        .unwrap_or_else(|| "скрытый владелец".to_string())
+}
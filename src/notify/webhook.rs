@@ -0,0 +1,73 @@
+use crate::notify::Event;
+use crate::status;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+// Подписанное тело в заголовке «sha256=<hex>», как у вебхуков GitHub.
+pub const SIGNATURE_HEADER: &str = "X-Rustfind-Signature";
+pub const EVENT_HEADER: &str = "X-Rustfind-Event";
+
+// Сколько ждать ответа получателя, чтобы медленный вебхук не держал запуск.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Адрес, на который события уходят POST-запросом с JSON. С секретом тело
+// подписывается HMAC-SHA256, и получатель может проверить, что это мы.
+pub struct Webhook {
+    pub url: String,
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    pub fn new(url: impl Into<String>, secret: Option<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret,
+        }
+    }
+
+    // Значение SIGNATURE_HEADER для тела `body`; None без секрета.
+    pub fn signature(&self, body: &str) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC принимает ключ любой длины");
+        mac.update(body.as_bytes());
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Some(format!("sha256={hex}"))
+    }
+
+    pub async fn send(&self, client: &reqwest::Client, event: &Event<'_>) -> reqwest::Result<()> {
+        let body = event.to_json();
+        let mut request = client
+            .post(&self.url)
+            .timeout(TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event.name());
+        if let Some(signature) = self.signature(&body) {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        request.body(body).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+// Отправляет все события на все вебхуки. Недоступный получатель не ломает запуск:
+// ошибка только печатается, отчёт к этому моменту уже записан.
+pub async fn send_all(webhooks: &[Webhook], events: &[Event<'_>]) {
+    let client = reqwest::Client::new();
+    for webhook in webhooks {
+        for event in events {
+            if let Err(e) = webhook.send(&client, event).await {
+                status!(
+                    "Не удалось отправить событие {} на вебхук: {e}",
+                    event.name()
+                );
+            }
+        }
+    }
+}
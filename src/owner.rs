@@ -0,0 +1,169 @@
+use crate::cli::Args;
+use crate::{Result, write_report};
+use grammers_client::Client;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::types::Chat;
+use parser_nft::error::ParserError;
+use parser_nft::export::{Enrichment, Report};
+use parser_nft::model::provenance::hide_original_details;
+use parser_nft::model::rarity;
+use parser_nft::model::{GiftRecord, Owner, Transfer};
+use parser_nft::report::quality::Quality;
+use parser_nft::status;
+use std::collections::HashMap;
+
+// То, что известно о подарках из профиля владельца, но не приходит в самих
+// подарках: владелец целиком и, в своём профиле, условия передачи по slug.
+pub struct Profile {
+    pub owner: Owner,
+    pub transfers: HashMap<String, Transfer>,
+}
+
+// Все уникальные подарки в профиле пользователя или канала - тот же отчёт,
+// что и по коллекции, только подарки берутся у одного владельца.
+// Скрытые из профиля подарки Telegram показывает только самому владельцу.
+pub async fn owner(client: &Client, input: &str, args: &Args) -> Result<()> {
+    let chat = resolve(client, input).await?;
+    let name = chat_name(&chat);
+    // Владелец-пользователь идёт вместе с подарками, чтобы в отчёте были его имя и username.
+    let users = match &chat {
+        Chat::User(user) => vec![user.raw.clone()],
+        _ => Vec::new(),
+    };
+
+    let mut profile = Profile {
+        // Название и username канала в самих подарках не приходят - берём их у чата.
+        owner: Owner::from(&chat),
+        transfers: HashMap::new(),
+    };
+    let own = is_own(&chat);
+
+    let mut saved = client.iter_saved_star_gifts(&chat).unique_only();
+    let mut gifts = Vec::new();
+    let mut stop_error = None;
+    loop {
+        match saved.next().await {
+            Ok(Some(gift)) => {
+                if own && let Some(unique) = gift.unique() {
+                    profile
+                        .transfers
+                        .insert(unique.slug.clone(), Transfer::from(&gift));
+                }
+                gifts.push(UniqueStarGift::Gift(tl::types::payments::UniqueStarGift {
+                    gift: gift.raw.gift,
+                    users: users.clone(),
+                }));
+                status!("{name}: получено подарков {}", gifts.len());
+            }
+            Ok(None) => break,
+            // Уже полученные подарки не выбрасываем: ошибка попадёт в раздел качества.
+            Err(e) if !gifts.is_empty() => {
+                status!("{name}: не удалось получить остальные подарки: {e}");
+                stop_error = Some(e.to_string());
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if gifts.is_empty() {
+        status!("У {name} нет уникальных подарков в профиле");
+        return Ok(());
+    }
+    write_gifts(args, &name, Some(&profile), gifts, stop_error, Vec::new())
+}
+
+// Свой профиль или канал, где мы администратор: только тогда Telegram
+// сообщает условия передачи подарков.
+fn is_own(chat: &Chat) -> bool {
+    match chat {
+        Chat::User(user) => user.is_self(),
+        Chat::Channel(channel) => channel.raw.creator || channel.admin_rights().is_some(),
+        Chat::Group(_) => false,
+    }
+}
+
+// Отчёт по подаркам не из одной коллекции - владельца или ленты канала - под
+// именем `name`. `profile` - то, что известно из профиля владельца: владелец
+// заменяет то, что о нём сказано в самих подарках. `stop_error` - ошибка, на
+// которой получение подарков оборвалось, `failed_gifts` - подарки, которые не
+// удалось получить («slug: ошибка»).
+pub fn write_gifts(
+    args: &Args,
+    name: &str,
+    profile: Option<&Profile>,
+    mut gifts: Vec<UniqueStarGift>,
+    stop_error: Option<String>,
+    failed_gifts: Vec<String>,
+) -> Result<()> {
+    if args.hide_original_details {
+        hide_original_details(&mut gifts);
+    }
+    let run = args.run_info(name);
+    let mut quality = Quality::new(&gifts);
+    // Выпуск коллекций здесь ни при чём: подарки идут не подряд по номерам.
+    quality.without_supply();
+    quality.stop_error = stop_error;
+    quality.failed_gifts = failed_gifts;
+    let mut gifts: Vec<GiftRecord> = gifts.into_iter().map(GiftRecord::from).collect();
+    rarity::rescore(&mut gifts, args.rarity_formula.into());
+    if let Some(profile) = profile {
+        for gift in &mut gifts {
+            if gift.owner.id() == profile.owner.id() {
+                gift.owner = profile.owner.clone();
+            }
+            gift.transfer = profile.transfers.get(&gift.slug).cloned();
+        }
+    }
+    let enrichment = Enrichment::default();
+    let report_name = args.report_name(name, &run);
+    let report = Report {
+        name: &report_name,
+        enrichment: &enrichment,
+        page_size: args.page_size,
+        split_by: args.split_by.map(Into::into),
+        lang: args.lang(),
+        theme: args.theme.into(),
+        css: args.css.as_ref().map(|(_, css)| css.as_str()),
+        detail_pages: false,
+        run: &run,
+        quality: &quality,
+    };
+    let dir = args.out_dir.clone().unwrap_or_default();
+    write_report(args, &report, &gifts, &dir)?;
+    Ok(())
+}
+
+// «@username» чата, а если его нет - имя.
+pub fn chat_name(chat: &Chat) -> String {
+    match chat.username() {
+        Some(username) => format!("@{username}"),
+        None => chat.name().unwrap_or_default().to_string(),
+    }
+}
+
+// Владелец по «@username», ссылке «https://t.me/username», «me» для своего
+// аккаунта или числовому id. По id находятся только уже встречавшиеся пользователи.
+pub async fn resolve(client: &Client, input: &str) -> Result<Chat> {
+    let input = input.trim();
+    if input == "me" {
+        return Ok(Chat::User(client.get_me().await?));
+    }
+    if let Ok(id) = input.parse::<i64>() {
+        return match client.get_users(&[id]).await?.pop() {
+            Some(user) => Ok(Chat::User(user)),
+            None => Err(ParserError::UnknownPeer(id.to_string())),
+        };
+    }
+
+    let username = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+    let username = username.strip_prefix("t.me/").unwrap_or(username);
+    let username = username.trim_start_matches('@').trim_end_matches('/');
+    client
+        .resolve_username(username)
+        .await?
+        .ok_or_else(|| ParserError::UnknownPeer(format!("@{username}")))
+}
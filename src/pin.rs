@@ -0,0 +1,45 @@
+use crate::Result;
+use crate::get::parse_slug;
+use grammers_client::{Client, InvocationError};
+use parser_nft::error::GiftContext as _;
+use parser_nft::fetch::rate::RateLimiter;
+
+// Показывает (`pinned`) или скрывает подарки в профиле владельца.
+// Ошибка по одному подарку не останавливает остальные: в конце печатаем,
+// сколько не получилось. Прерываемся только на ошибках соединения.
+pub async fn pin(
+    client: &Client,
+    gifts: &[String],
+    pinned: bool,
+    limiter: &mut RateLimiter,
+) -> Result<()> {
+    let mut failed = 0;
+    for input in gifts {
+        let slug = parse_slug(input);
+        limiter.acquire().await;
+        let result = if pinned {
+            client.save_star_gift(slug).await
+        } else {
+            client.unsave_star_gift(slug).await
+        };
+
+        match result {
+            Ok(()) if pinned => println!("{slug}: показан в профиле"),
+            Ok(()) => println!("{slug}: скрыт из профиля"),
+            Err(e) if e.is_slug_invalid() => {
+                println!("{slug}: такого подарка нет");
+                failed += 1;
+            }
+            Err(InvocationError::Rpc(e)) => {
+                println!("{slug}: не получилось ({e})");
+                failed += 1;
+            }
+            Err(e) => return Err(e).gift(slug),
+        }
+    }
+
+    if failed > 0 {
+        println!("Не получилось для {failed} из {} подарков", gifts.len());
+    }
+    Ok(())
+}
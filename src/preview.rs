@@ -0,0 +1,108 @@
+use grammers_client::types::GiftAttribute;
+use grammers_client::{Client, InvocationError};
+use parser_nft::fetch::catalog::{Catalog, find_collection};
+use parser_nft::report::json_string;
+
+// Одна строка таблицы: вид атрибута, название и редкость.
+struct Row {
+    kind: &'static str,
+    name: String,
+    rarity_permille: i32,
+}
+
+// Печатает все атрибуты, которые может получить подарок коллекции при улучшении.
+// Telegram отдаёт их сразу после анонса коллекции, так что редкость известна
+// ещё до того, как улучшены все подарки.
+pub async fn preview(
+    client: &Client,
+    catalog: &Catalog,
+    collection: &str,
+    json: bool,
+) -> Result<(), InvocationError> {
+    let Some(catalog) = catalog.get(client).await? else {
+        return Ok(());
+    };
+    let Some(gift) = find_collection(&catalog, collection) else {
+        println!("Коллекция {collection} не найдена в каталоге подарков");
+        return Ok(());
+    };
+    if gift.upgrade_stars.is_none() {
+        println!("Подарки коллекции {collection} нельзя улучшить");
+        return Ok(());
+    }
+
+    let preview = client.get_star_gift_upgrade_preview(gift.id).await?;
+    let mut rows: Vec<Row> = preview
+        .attributes()
+        .filter_map(|attribute| {
+            let kind = match attribute {
+                GiftAttribute::Model(_) => "model",
+                GiftAttribute::Backdrop(_) => "backdrop",
+                GiftAttribute::Pattern(_) => "pattern",
+                GiftAttribute::OriginalDetails(_) => return None,
+            };
+            Some(Row {
+                kind,
+                name: attribute.name()?.to_string(),
+                rarity_permille: attribute.rarity_permille()?,
+            })
+        })
+        .collect();
+    // Внутри каждого вида - от самых редких к самым частым.
+    rows.sort_by(|a, b| {
+        kind_order(a.kind)
+            .cmp(&kind_order(b.kind))
+            .then(a.rarity_permille.cmp(&b.rarity_permille))
+            .then(a.name.cmp(&b.name))
+    });
+
+    if json {
+        print_json(&rows);
+    } else {
+        print_table(&rows);
+    }
+    Ok(())
+}
+
+fn kind_order(kind: &str) -> u8 {
+    match kind {
+        "model" => 0,
+        "backdrop" => 1,
+        _ => 2,
+    }
+}
+
+fn kind_name(kind: &str) -> &'static str {
+    match kind {
+        "model" => "Модель",
+        "backdrop" => "Фон",
+        _ => "Узор",
+    }
+}
+
+fn print_table(rows: &[Row]) {
+    println!("{:<8} {:<28} {:>9}", "Вид", "Название", "Редкость");
+    for row in rows {
+        println!(
+            "{:<8} {:<28} {:>8.1}%",
+            kind_name(row.kind),
+            row.name,
+            row.rarity_permille as f64 / 10.0
+        );
+    }
+}
+
+fn print_json(rows: &[Row]) {
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "  {{\"kind\": \"{}\", \"name\": {}, \"rarity_permille\": {}}}",
+                row.kind,
+                json_string(&row.name),
+                row.rarity_permille
+            )
+        })
+        .collect();
+    println!("[\n{}\n]", lines.join(",\n"));
+}
@@ -0,0 +1,216 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use simple_logger::SimpleLogger;
+use std::backtrace::Backtrace;
+use std::panic;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Чем заменяются скрытые данные.
+const MASK: &str = "***";
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+// Известные секреты этого запуска: api_hash, телефон, путь к сессии, пароль.
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+// Включает логирование и panic hook, которые вычищают секреты из каждого сообщения.
+// Парсер запускают и как общий сервис с централизованными логами, так что
+// ни api_hash, ни телефон, ни токены ботов не должны туда попадать.
+// `enabled == false` (--no-redact) оставляет всё как есть - для локальной отладки.
+// Уровень логов по умолчанию - warn, переопределяется через RUST_LOG.
+pub fn init(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+
+    let logger = SimpleLogger::new().with_level(LevelFilter::Warn).env();
+    log::set_max_level(logger.max_level());
+    if log::set_boxed_logger(Box::new(RedactingLogger(logger))).is_err() {
+        eprintln!("Логгер уже установлен, логи не будут очищаться от секретов");
+    }
+
+    panic::set_hook(Box::new(|info| {
+        let location = info
+            .location()
+            .map(|location| format!(" at {}:{}", location.file(), location.line()))
+            .unwrap_or_default();
+        let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+        eprintln!("panicked{location}:\n{}", redact(&message));
+
+        // Как и стандартный hook, печатаем backtrace только по RUST_BACKTRACE.
+        let backtrace = Backtrace::capture();
+        if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+            eprintln!("{}", redact(&backtrace.to_string()));
+        }
+    }));
+}
+
+// Запоминает секрет, чтобы вычищать его дословно. Пустые строки игнорируются.
+pub fn add_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.is_empty() {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|known| known == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+// Заменяет известные секреты и всё, что похоже на телефон, токен бота или api_hash.
+pub fn redact(text: &str) -> String {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return text.to_string();
+    }
+
+    let mut text = text.to_string();
+    for secret in SECRETS.read().unwrap().iter() {
+        text = text.replace(secret.as_str(), MASK);
+    }
+    scrub_patterns(&text)
+}
+
+// Поиск по шаблонам без регулярных выражений: идём по словам из букв, цифр и
+// `+:_-` и заменяем подходящие целиком.
+fn scrub_patterns(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, '+' | ':' | '_' | '-') {
+            word.push(c);
+        } else {
+            result.push_str(&scrub_word(&word));
+            word.clear();
+            result.push(c);
+        }
+    }
+    result.push_str(&scrub_word(&word));
+    result
+}
+
+fn scrub_word(word: &str) -> String {
+    if is_phone(word) || is_bot_token(word) || is_api_hash(word) {
+        MASK.to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+// +79991234567
+fn is_phone(word: &str) -> bool {
+    word.strip_prefix('+').is_some_and(|digits| {
+        (7..=15).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+// 123456789:AAH1bGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9xyz
+fn is_bot_token(word: &str) -> bool {
+    let Some((id, secret)) = word.split_once(':') else {
+        return false;
+    };
+    (6..=12).contains(&id.len())
+        && id.chars().all(|c| c.is_ascii_digit())
+        && secret.len() >= 30
+        && secret
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+// 32 шестнадцатеричных символа, как у api_hash.
+fn is_api_hash(word: &str) -> bool {
+    word.len() == 32 && word.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+struct RedactingLogger(SimpleLogger);
+
+impl Log for RedactingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = redact(&record.args().to_string());
+        self.0.log(
+            &Record::builder()
+                .args(format_args!("{message}"))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phones() {
+        assert!(is_phone("+79991234567"));
+        assert!(is_phone("+1234567"));
+        assert!(!is_phone("79991234567"));
+        assert!(!is_phone("+123456"));
+        assert!(!is_phone("+7999123456789012"));
+        assert!(!is_phone("+7999-123-45-67"));
+    }
+
+    #[test]
+    fn bot_tokens() {
+        let secret = "AAH1bGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9xyz";
+        assert!(is_bot_token(&format!("123456789:{secret}")));
+        assert!(is_bot_token(
+            "1234567890:AAE-x_yZ0123456789abcdefghijklmnopq"
+        ));
+        // Короткий секрет или не числовой id - не токен.
+        assert!(!is_bot_token("123456789:short"));
+        assert!(!is_bot_token(&format!("bot12345:{secret}")));
+        assert!(!is_bot_token(&format!("12345:{secret}")));
+        assert!(!is_bot_token("PlushPepe-1234"));
+    }
+
+    #[test]
+    fn api_hashes() {
+        assert!(is_api_hash("0123456789abcdef0123456789ABCDEF"));
+        assert!(!is_api_hash("0123456789abcdef0123456789abcde"));
+        assert!(!is_api_hash("0123456789abcdef0123456789abcdeg"));
+    }
+
+    #[test]
+    fn patterns_in_text() {
+        assert_eq!(
+            scrub_patterns(
+                "login +79991234567, bot 123456789:AAH1bGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9xyz; \
+                 hash=0123456789abcdef0123456789abcdef"
+            ),
+            "login ***, bot ***; hash=***"
+        );
+        // Обычные сообщения парсера не меняются.
+        let status = "PlushPepe-1234: получен со второй попытки (500 мс, 12:30)";
+        assert_eq!(scrub_patterns(status), status);
+    }
+
+    #[test]
+    fn registered_secrets() {
+        add_secret("  s3cr3t-session.session  ");
+        add_secret("");
+        assert_eq!(
+            redact("не удалось открыть s3cr3t-session.session"),
+            "не удалось открыть ***"
+        );
+        assert_eq!(redact("пароль p4ss"), "пароль p4ss");
+        add_secret("p4ss");
+        assert_eq!(redact("пароль p4ss"), "пароль ***");
+    }
+}
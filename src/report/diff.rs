@@ -0,0 +1,57 @@
+use crate::model::{GiftRecord, Owner};
+use std::collections::HashMap;
+
+// Что изменилось в коллекции между двумя снимками.
+#[derive(Default)]
+pub struct Changes {
+    // Подарки, которых в прошлом снимке не было, - новые улучшения.
+    pub new_gifts: Vec<GiftRecord>,
+    pub owner_changes: Vec<OwnerChange>,
+    // Подарки, которые с прошлого снимка выставили на продажу.
+    pub listed: Vec<GiftRecord>,
+}
+
+// Подарок сменил владельца: `gift` - каким он стал, `previous` - кто владел раньше.
+pub struct OwnerChange {
+    pub gift: GiftRecord,
+    pub previous: Owner,
+}
+
+impl Changes {
+    pub fn new(before: &[GiftRecord], after: &[GiftRecord]) -> Self {
+        let before: HashMap<&str, &GiftRecord> = before
+            .iter()
+            .map(|gift| (gift.slug.as_str(), gift))
+            .collect();
+        let mut changes = Changes::default();
+        for gift in after {
+            let Some(old) = before.get(gift.slug.as_str()) else {
+                changes.new_gifts.push(gift.clone());
+                continue;
+            };
+            if !same_owner(&old.owner, &gift.owner) {
+                changes.owner_changes.push(OwnerChange {
+                    gift: gift.clone(),
+                    previous: old.owner.clone(),
+                });
+            }
+            if old.resale_price.is_none() && gift.resale_price.is_some() {
+                changes.listed.push(gift.clone());
+            }
+        }
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new_gifts.is_empty() && self.owner_changes.is_empty() && self.listed.is_empty()
+    }
+}
+
+// Владелец тот же, даже если в одном из снимков не удалось загрузить его
+// профиль и известен только id, а имя или username он с тех пор сменил.
+pub fn same_owner(a: &Owner, b: &Owner) -> bool {
+    match (a.id(), b.id()) {
+        (Some(a_id), Some(b_id)) => a_id == b_id && a.kind() == b.kind(),
+        _ => a == b,
+    }
+}
@@ -0,0 +1,171 @@
+use crate::model::GiftRecord;
+use crate::report::quality::Quality;
+use crate::report::timing::Performance;
+use crate::report::{RunInfo, json_string};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::path::PathBuf;
+
+// Итог парсинга одной коллекции для manifest.json.
+pub struct CollectionRun {
+    pub collection: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    // Номера первого и последнего полученного подарка.
+    pub range: Option<(i32, i32)>,
+    pub gifts: usize,
+    pub expected: Option<i32>,
+    pub missing: usize,
+    pub failed_gifts: usize,
+    pub unresolved_owners: usize,
+    pub failed_media: usize,
+    // Скорость загрузки подарков, если их загружали.
+    pub performance: Option<Performance>,
+    // Ошибка, на которой остановился парсинг или из-за которой коллекция не спарсилась.
+    pub error: Option<String>,
+    // Записанные файлы: отчёт, снимок, страницы сайта.
+    pub files: Vec<PathBuf>,
+}
+
+impl CollectionRun {
+    // Коллекция, по которой записан отчёт.
+    pub fn new(
+        collection: &str,
+        started_at: DateTime<Utc>,
+        gifts: &[GiftRecord],
+        quality: &Quality,
+    ) -> Self {
+        let first = gifts.iter().map(|gift| gift.num).min();
+        let last = gifts.iter().map(|gift| gift.num).max();
+        Self {
+            collection: collection.to_string(),
+            started_at,
+            finished_at: Utc::now(),
+            range: first.zip(last),
+            gifts: gifts.len(),
+            expected: quality.expected,
+            missing: quality.missing.len(),
+            failed_gifts: quality.failed_gifts.len(),
+            unresolved_owners: quality.unresolved_owners.len(),
+            failed_media: quality.failed_media.len(),
+            performance: None,
+            error: quality.stop_error.clone(),
+            files: Vec::new(),
+        }
+    }
+
+    // Коллекция, парсинг которой не удался целиком.
+    pub fn failed(collection: &str, started_at: DateTime<Utc>, error: String) -> Self {
+        Self {
+            collection: collection.to_string(),
+            started_at,
+            finished_at: Utc::now(),
+            range: None,
+            gifts: 0,
+            expected: None,
+            missing: 0,
+            failed_gifts: 0,
+            unresolved_owners: 0,
+            failed_media: 0,
+            performance: None,
+            error: Some(error),
+            files: Vec::new(),
+        }
+    }
+
+    // Сколько всего ошибок: остановка парсинга, неполученные подарки и медиа.
+    pub fn errors(&self) -> usize {
+        usize::from(self.error.is_some()) + self.failed_gifts + self.failed_media
+    }
+
+    fn to_json(&self) -> String {
+        let (first, last) = match self.range {
+            Some((first, last)) => (first.to_string(), last.to_string()),
+            None => ("null".to_string(), "null".to_string()),
+        };
+        let files = self
+            .files
+            .iter()
+            .map(|file| json_string(&file.display().to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fields = [
+            ("collection", json_string(&self.collection)),
+            ("started_at", json_string(&timestamp(self.started_at))),
+            ("finished_at", json_string(&timestamp(self.finished_at))),
+            ("duration_secs", duration(self.started_at, self.finished_at)),
+            ("first", first),
+            ("last", last),
+            ("gifts", self.gifts.to_string()),
+            (
+                "expected",
+                self.expected
+                    .map_or_else(|| "null".to_string(), |expected| expected.to_string()),
+            ),
+            ("missing", self.missing.to_string()),
+            ("failed_gifts", self.failed_gifts.to_string()),
+            ("unresolved_owners", self.unresolved_owners.to_string()),
+            ("failed_media", self.failed_media.to_string()),
+            ("errors", self.errors().to_string()),
+            (
+                "performance",
+                self.performance
+                    .map_or_else(|| "null".to_string(), |performance| performance.to_json()),
+            ),
+            (
+                "error",
+                self.error
+                    .as_deref()
+                    .map_or_else(|| "null".to_string(), json_string),
+            ),
+            ("files", format!("[{files}]")),
+        ];
+        let fields = fields
+            .iter()
+            .map(|(name, value)| format!("\"{name}\": {value}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{{{fields}}}")
+    }
+}
+
+// manifest.json: параметры и итоги запуска по всем коллекциям - чтобы скрипты
+// могли проверить и заархивировать результат, не разбирая отчёты.
+pub fn manifest_json(
+    run: &RunInfo,
+    finished_at: DateTime<Utc>,
+    collections: &[CollectionRun],
+) -> String {
+    let run_json = run.to_json().trim_end().replace('\n', "\n  ");
+    let entries = collections
+        .iter()
+        .map(|collection| format!("    {}", collection.to_json()))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let gifts: usize = collections.iter().map(|collection| collection.gifts).sum();
+    let errors: usize = collections.iter().map(CollectionRun::errors).sum();
+    format!(
+        "{{
+  \"run\": {run_json},
+  \"finished_at\": \"{}\",
+  \"duration_secs\": {},
+  \"gifts\": {gifts},
+  \"errors\": {errors},
+  \"collections\": [
+{entries}
+  ]
+}}
+",
+        timestamp(finished_at),
+        duration(run.started_at, finished_at)
+    )
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// Длительность в секундах с точностью до миллисекунд.
+fn duration(started_at: DateTime<Utc>, finished_at: DateTime<Utc>) -> String {
+    let millis = (finished_at - started_at).num_milliseconds().max(0);
+    format!("{:.3}", millis as f64 / 1000.0)
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use grammers_client::grammers_tl_types as tl;
+
+pub mod diff;
+pub mod manifest;
+pub mod ownership;
+pub mod prices;
+pub mod quality;
+pub mod stats;
+pub mod timing;
+
+// Сведения о запуске, которые пишутся в каждый результат, чтобы по старому
+// отчёту было видно, чем и с какими параметрами он получен.
+pub struct RunInfo {
+    pub version: &'static str,
+    pub layer: i32,
+    pub started_at: DateTime<Utc>,
+    // Показывать ли время запуска в отчётах (--timestamps). В событиях
+    // вебхуков и manifest.json оно есть всегда.
+    pub timestamps: bool,
+    // Все действующие параметры запуска, включая значения по умолчанию.
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl RunInfo {
+    // Сведения о запуске, который начинается сейчас, с параметрами `params`.
+    pub fn new(params: Vec<(&'static str, String)>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            layer: tl::LAYER,
+            started_at: Utc::now(),
+            timestamps: false,
+            params,
+        }
+    }
+
+    pub fn timestamp(&self) -> String {
+        self.started_at
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    }
+
+    // Время запуска для отчёта, если его там показывают.
+    pub fn report_timestamp(&self) -> Option<String> {
+        self.timestamps.then(|| self.timestamp())
+    }
+
+    pub fn to_json(&self) -> String {
+        self.json(true)
+    }
+
+    // Сведения о запуске для JSON-отчёта: время в нём только с --timestamps.
+    pub fn report_json(&self) -> String {
+        self.json(self.timestamps)
+    }
+
+    fn json(&self, with_time: bool) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|(name, value)| format!("    \"{name}\": {}", json_string(value)))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let started_at = if with_time {
+            format!("\n  \"started_at\": \"{}\",", self.timestamp())
+        } else {
+            String::new()
+        };
+        format!(
+            "{{
+  \"version\": \"{}\",
+  \"tl_layer\": {},{started_at}
+  \"params\": {{
+{params}
+  }}
+}}
+",
+            self.version, self.layer
+        )
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c < ' ' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,49 @@
+use crate::model::{GiftRecord, Owner};
+use crate::report::diff::same_owner;
+use crate::store::Store;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io;
+
+// Владельцы каждого подарка по снимкам, от старых к новым. Telegram показывает
+// только нынешнего владельца, так что прошлые видны лишь по снимкам из --store.
+pub type OwnerHistory = HashMap<String, Vec<Ownership>>;
+
+// Один владелец подарка: в каком снимке он появился и в каком последнем ещё владел
+// подарком. Снимки, где владелец тот же, только сдвигают `last_seen`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ownership {
+    pub owner: Owner,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+// История владельцев подарков коллекции по всем её снимкам в хранилище.
+pub fn owner_history(store: &Store, collection: &str) -> io::Result<OwnerHistory> {
+    let mut history = OwnerHistory::new();
+    for taken_at in store.snapshots(collection)? {
+        let snapshot = store.load(collection, taken_at)?;
+        let gifts: Vec<GiftRecord> = snapshot.gifts.into_iter().map(GiftRecord::from).collect();
+        add_snapshot(&mut history, taken_at, &gifts);
+    }
+    Ok(history)
+}
+
+// Дописывает к истории снимок `gifts` от `taken_at`; снимки - по порядку времени.
+pub fn add_snapshot(history: &mut OwnerHistory, taken_at: DateTime<Utc>, gifts: &[GiftRecord]) {
+    for gift in gifts {
+        let owners = history.entry(gift.slug.clone()).or_default();
+        match owners.last_mut() {
+            // Тот же владелец, но, может быть, с новым именем - оставляем свежее.
+            Some(last) if same_owner(&last.owner, &gift.owner) => {
+                last.owner = gift.owner.clone();
+                last.last_seen = taken_at;
+            }
+            _ => owners.push(Ownership {
+                owner: gift.owner.clone(),
+                first_seen: taken_at,
+                last_seen: taken_at,
+            }),
+        }
+    }
+}
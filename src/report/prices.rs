@@ -0,0 +1,91 @@
+use crate::model::GiftRecord;
+use crate::report::json_string;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+// Цены перепродажи по коллекции (--prices): почём выставлены подарки каждой
+// модели и каждого фона. Считаются только подарки, выставленные на продажу.
+pub struct Prices {
+    pub collection: String,
+    // Вся коллекция одной строкой.
+    pub total: PriceRow,
+    // Строки моделей и фонов: сначала дорогие по медиане, непродающиеся - в конце.
+    pub models: Vec<PriceRow>,
+    pub backdrops: Vec<PriceRow>,
+}
+
+// Цены в звёздах для одной модели или фона.
+pub struct PriceRow {
+    pub name: String,
+    // Сколько подарков выставлено на продажу; без них цен нет.
+    pub listed: usize,
+    pub min: Option<i64>,
+    // Для чётного числа лотов - среднее двух средних цен, вниз до целой звезды.
+    pub median: Option<i64>,
+    pub max: Option<i64>,
+}
+
+impl PriceRow {
+    fn new(name: &str, mut prices: Vec<i64>) -> Self {
+        prices.sort_unstable();
+        let median = match prices.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(prices[n / 2]),
+            n => Some((prices[n / 2 - 1] + prices[n / 2]) / 2),
+        };
+        Self {
+            name: name.to_string(),
+            listed: prices.len(),
+            min: prices.first().copied(),
+            median,
+            max: prices.last().copied(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let price =
+            |price: Option<i64>| price.map_or_else(|| "null".to_string(), |p| p.to_string());
+        format!(
+            "{{\"name\": {}, \"listed\": {}, \"min_price\": {}, \"median_price\": {}, \"max_price\": {}}}",
+            json_string(&self.name),
+            self.listed,
+            price(self.min),
+            price(self.median),
+            price(self.max)
+        )
+    }
+}
+
+impl Prices {
+    pub fn new(collection: &str, gifts: &[GiftRecord]) -> Self {
+        Self {
+            collection: collection.to_string(),
+            total: PriceRow::new(
+                collection,
+                gifts.iter().filter_map(|gift| gift.resale_price).collect(),
+            ),
+            models: rows(gifts, |gift| gift.attributes.model_name()),
+            backdrops: rows(gifts, |gift| gift.attributes.backdrop_name()),
+        }
+    }
+}
+
+// Строки по значению атрибута `key`: у каждого значения - цены его лотов.
+fn rows(gifts: &[GiftRecord], key: impl Fn(&GiftRecord) -> &str) -> Vec<PriceRow> {
+    let mut prices: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    for gift in gifts {
+        let name = key(gift);
+        if name.is_empty() {
+            continue;
+        }
+        let entry = prices.entry(name).or_default();
+        entry.extend(gift.resale_price);
+    }
+    let mut rows: Vec<PriceRow> = prices
+        .into_iter()
+        .map(|(name, prices)| PriceRow::new(name, prices))
+        .collect();
+    // BTreeMap уже упорядочил по имени, сортировка устойчивая.
+    rows.sort_by_key(|row| Reverse(row.median));
+    rows
+}
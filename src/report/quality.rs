@@ -0,0 +1,156 @@
+use crate::report::json_string;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use std::collections::HashSet;
+
+// Насколько полон снимок: что не удалось получить и почему.
+// Попадает в каждый формат вывода отдельным разделом.
+#[derive(Default)]
+pub struct Quality {
+    // Сколько подарков выпущено по данным Telegram.
+    pub expected: Option<i32>,
+    pub fetched: usize,
+    // Выпущенные номера, которых нет в снимке, по возрастанию.
+    pub missing: Vec<i32>,
+    // Ошибка, на которой остановился парсинг, если это не обычный конец коллекции.
+    pub stop_error: Option<String>,
+    // Подарки, которые не удалось получить и со второй попытки: «slug: причина».
+    pub failed_gifts: Vec<String>,
+    // Подарки, владелец которых скрыт или неизвестен.
+    pub unresolved_owners: Vec<String>,
+    // Медиа, которые не удалось скачать: «slug: что именно».
+    pub failed_media: Vec<String>,
+    // Почему не удалось достучаться до датацентра с медиа, если не удалось.
+    pub media_unreachable: Option<String>,
+}
+
+impl Quality {
+    pub fn new(gifts: &[UniqueStarGift]) -> Self {
+        let mut quality = Quality {
+            fetched: gifts.len(),
+            ..Default::default()
+        };
+        let mut numbers = HashSet::new();
+        for gift in gifts {
+            let UniqueStarGift::Gift(gift_obj) = gift;
+            let tl::enums::StarGift::Unique(info) = &gift_obj.gift else {
+                continue;
+            };
+            numbers.insert(info.num);
+            // Выпуск растёт, пока идёт парсинг: верим самому свежему числу.
+            quality.expected = quality.expected.max(Some(info.availability_issued));
+            if info.owner_id.is_none() && info.owner_name.is_none() {
+                quality.unresolved_owners.push(info.slug.clone());
+            }
+        }
+        if let Some(expected) = quality.expected {
+            quality.missing = (1..=expected)
+                .filter(|num| !numbers.contains(num))
+                .collect();
+        }
+        quality
+    }
+
+    // Сверка с выпуском не имеет смысла (подарки одного владельца,
+    // несколько коллекций сразу).
+    pub fn without_supply(&mut self) {
+        self.expected = None;
+        self.missing.clear();
+    }
+
+    // Пропущенные номера диапазонами: «4, 7–9, 12».
+    pub fn missing_ranges(&self) -> String {
+        let mut ranges: Vec<(i32, i32)> = Vec::new();
+        for &num in &self.missing {
+            match ranges.last_mut() {
+                Some((_, last)) if *last + 1 == num => *last = num,
+                _ => ranges.push((num, num)),
+            }
+        }
+        ranges
+            .iter()
+            .map(|&(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{first}–{last}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+            && self.stop_error.is_none()
+            && self.failed_gifts.is_empty()
+            && self.unresolved_owners.is_empty()
+            && self.failed_media.is_empty()
+            && self.media_unreachable.is_none()
+    }
+
+    // Строки раздела «Качество данных», одинаковые для всех форматов.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.expected {
+            Some(expected) => {
+                lines.push(format!("Получено подарков: {} из {expected}", self.fetched))
+            }
+            None => lines.push(format!("Получено подарков: {}", self.fetched)),
+        }
+        if !self.missing.is_empty() {
+            lines.push(format!(
+                "Пропущены номера ({}): {}",
+                self.missing.len(),
+                self.missing_ranges()
+            ));
+        }
+        if let Some(error) = &self.stop_error {
+            lines.push(format!("Парсинг прерван ошибкой: {error}"));
+        }
+        if !self.failed_gifts.is_empty() {
+            lines.push(format!(
+                "Не получены подарки ({}): {}",
+                self.failed_gifts.len(),
+                self.failed_gifts.join(", ")
+            ));
+        }
+        if !self.unresolved_owners.is_empty() {
+            lines.push(format!(
+                "Владелец неизвестен ({}): {}",
+                self.unresolved_owners.len(),
+                self.unresolved_owners.join(", ")
+            ));
+        }
+        if !self.failed_media.is_empty() {
+            lines.push(format!(
+                "Не скачаны медиа ({}): {}",
+                self.failed_media.len(),
+                self.failed_media.join(", ")
+            ));
+        }
+        if let Some(reason) = &self.media_unreachable {
+            lines.push(format!(
+                "Датацентр с медиа недоступен ({reason}): картинки и медиа скачаны не все"
+            ));
+        }
+        if self.is_complete() {
+            lines.push("Снимок полный".to_string());
+        }
+        lines
+    }
+
+    // Полнота и строки качества одним объектом JSON - так же в parsed.json и вебхуках.
+    pub fn to_json(&self) -> String {
+        let lines = self
+            .lines()
+            .iter()
+            .map(|line| json_string(line))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "{{\"complete\": {}, \"lines\": [{lines}]}}",
+            self.is_complete()
+        )
+    }
+}
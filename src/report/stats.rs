@@ -0,0 +1,75 @@
+use crate::model::{GiftRecord, Owner};
+use crate::report::json_string;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+// Сводка по коллекции: сколько подарков, сколько продаётся и почём, у скольких
+// владельцев и какие модели и фоны встречаются чаще всего.
+pub struct Stats {
+    pub count: usize,
+    // Сколько подарков продаётся и самая низкая и высокая цена в звёздах.
+    pub listed: usize,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    // Разные известные владельцы; скрытые не считаются.
+    pub holders: usize,
+    // Сколько подарков с каждой моделью и фоном, от частых к редким.
+    pub models: Vec<(String, usize)>,
+    pub backdrops: Vec<(String, usize)>,
+}
+
+impl Stats {
+    pub fn new(gifts: &[GiftRecord]) -> Self {
+        let prices: Vec<i64> = gifts.iter().filter_map(|gift| gift.resale_price).collect();
+        let holders: HashSet<&Owner> = gifts
+            .iter()
+            .map(|gift| &gift.owner)
+            .filter(|owner| **owner != Owner::Hidden)
+            .collect();
+        Self {
+            count: gifts.len(),
+            listed: prices.len(),
+            min_price: prices.iter().copied().min(),
+            max_price: prices.iter().copied().max(),
+            holders: holders.len(),
+            models: frequencies(gifts.iter().map(|gift| gift.attributes.model_name())),
+            backdrops: frequencies(gifts.iter().map(|gift| gift.attributes.backdrop_name())),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let counts = |counts: &[(String, usize)]| {
+            counts
+                .iter()
+                .map(|(name, count)| format!("{}: {count}", json_string(name)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let price =
+            |price: Option<i64>| price.map_or_else(|| "null".to_string(), |p| p.to_string());
+        format!(
+            "{{\"count\": {}, \"listed\": {}, \"min_price\": {}, \"max_price\": {}, \"holders\": {}, \"models\": {{{}}}, \"backdrops\": {{{}}}}}",
+            self.count,
+            self.listed,
+            price(self.min_price),
+            price(self.max_price),
+            self.holders,
+            counts(&self.models),
+            counts(&self.backdrops)
+        )
+    }
+}
+
+// Сколько раз встречается каждое значение, от частых к редким, при равенстве - по алфавиту.
+fn frequencies<'a>(values: impl Iterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for value in values.filter(|value| !value.is_empty()) {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+    counts
+}
@@ -0,0 +1,105 @@
+use grammers_client::InvocationError;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Время ответов на запросы подарков за парсинг коллекции. По нему видно, во что
+// упирается парсинг - в --rps, в задержку Telegram или во flood-wait.
+#[derive(Default)]
+pub struct Timings {
+    // Время ответа на каждый запрос, без ожидания очереди --rps.
+    latencies: Vec<Duration>,
+    // Flood-wait, которые дошли до парсера. Те, что клиент переждал сам
+    // (не дольше --flood-sleep), видны только как долгие ответы.
+    flood_waits: usize,
+    // Первый запрос и последний ответ.
+    first: Option<Instant>,
+    last: Option<Instant>,
+}
+
+impl Timings {
+    // На запрос, отправленный в `sent`, только что пришёл ответ `response`.
+    pub fn record<T>(&mut self, sent: Instant, response: &Result<T, InvocationError>) {
+        let now = Instant::now();
+        self.first.get_or_insert(sent);
+        self.last = Some(now);
+        self.latencies.push(now - sent);
+        if let Err(e) = response
+            && e.flood_wait_seconds().is_some()
+        {
+            self.flood_waits += 1;
+        }
+    }
+
+    pub fn performance(&self) -> Performance {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        Performance {
+            requests: latencies.len(),
+            p50: percentile(&latencies, 50),
+            p95: percentile(&latencies, 95),
+            elapsed: match (self.first, self.last) {
+                (Some(first), Some(last)) => last - first,
+                _ => Duration::ZERO,
+            },
+            flood_waits: self.flood_waits,
+        }
+    }
+}
+
+// Время ответа, которое не превысили `percent` процентов запросов
+// (ближайший ранг по отсортированным `latencies`).
+fn percentile(latencies: &[Duration], percent: usize) -> Duration {
+    if latencies.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (latencies.len() * percent).div_ceil(100).max(1);
+    latencies[rank - 1]
+}
+
+// Сводка по Timings для вывода в конце парсинга и manifest.json.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Performance {
+    pub requests: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    // От первого запроса до последнего ответа, вместе с очередью --rps и flood-wait.
+    pub elapsed: Duration,
+    pub flood_waits: usize,
+}
+
+impl Performance {
+    // Сколько запросов в секунду получилось на деле.
+    pub fn rps(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.requests as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"requests\": {}, \"p50_ms\": {}, \"p95_ms\": {}, \"fetch_secs\": {:.3}, \"rps\": {:.2}, \"flood_waits\": {}}}",
+            self.requests,
+            self.p50.as_millis(),
+            self.p95.as_millis(),
+            self.elapsed.as_secs_f64(),
+            self.rps(),
+            self.flood_waits
+        )
+    }
+}
+
+impl fmt::Display for Performance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "запросов {} за {:.1} с ({:.1} в секунду), ответ p50 {} мс, p95 {} мс, flood-wait {}",
+            self.requests,
+            self.elapsed.as_secs_f64(),
+            self.rps(),
+            self.p50.as_millis(),
+            self.p95.as_millis(),
+            self.flood_waits
+        )
+    }
+}
@@ -0,0 +1,135 @@
+use crate::Result;
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use parser_nft::export::json::gift_json;
+use parser_nft::model::GiftRecord;
+use parser_nft::report::json_string;
+use parser_nft::report::stats::Stats;
+use parser_nft::store::Store;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// REST API над снимками из хранилища, чтобы дашборды получали данные, не
+// запуская парсер заново. Снимки читаются на каждый запрос, так что новый
+// парсинг с тем же --store виден сразу:
+//   GET /collections                    - коллекции и время их последнего снимка
+//   GET /collections/{collection}/gifts - подарки из последнего снимка
+//   GET /collections/{collection}/stats - сводка по последнему снимку
+//   GET /gifts/{slug}                   - один подарок, например PlushPepe-1
+pub async fn serve(store: PathBuf, addr: SocketAddr) -> Result<()> {
+    let app = Router::new()
+        .route("/collections", get(collections))
+        .route("/collections/{collection}/gifts", get(gifts))
+        .route("/collections/{collection}/stats", get(stats))
+        .route("/gifts/{slug}", get(gift))
+        .with_state(Arc::new(Store::new(store)));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("API доступно на http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn collections(State(store): State<Arc<Store>>) -> Response {
+    let result = store.collections().and_then(|collections| {
+        collections
+            .iter()
+            .map(|collection| {
+                let snapshots = store.snapshots(collection)?;
+                Ok(format!(
+                    "{{\"collection\": {}, \"snapshots\": {}, \"latest\": {}}}",
+                    json_string(collection),
+                    snapshots.len(),
+                    snapshots.last().map_or_else(
+                        || "null".to_string(),
+                        |time| json_string(&time.to_rfc3339())
+                    )
+                ))
+            })
+            .collect::<io::Result<Vec<_>>>()
+    });
+    match result {
+        Ok(collections) => json(StatusCode::OK, format!("[{}]", collections.join(", "))),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+async fn gifts(State(store): State<Arc<Store>>, Path(collection): Path<String>) -> Response {
+    match latest(&store, &collection) {
+        Ok((_, gifts)) => {
+            let gifts: Vec<String> = gifts.iter().map(|gift| gift_json(gift, None)).collect();
+            json(StatusCode::OK, format!("[{}]", gifts.join(",\n")))
+        }
+        Err((status, message)) => error(status, &message),
+    }
+}
+
+async fn stats(State(store): State<Arc<Store>>, Path(collection): Path<String>) -> Response {
+    match latest(&store, &collection) {
+        Ok((taken_at, gifts)) => json(
+            StatusCode::OK,
+            format!(
+                "{{\"collection\": {}, \"snapshot\": {}, \"stats\": {}}}",
+                json_string(&collection),
+                json_string(&taken_at),
+                Stats::new(&gifts).to_json()
+            ),
+        ),
+        Err((status, message)) => error(status, &message),
+    }
+}
+
+async fn gift(State(store): State<Arc<Store>>, Path(slug): Path<String>) -> Response {
+    let Some((collection, _)) = slug.rsplit_once('-') else {
+        return error(StatusCode::NOT_FOUND, &format!("неверный slug «{slug}»"));
+    };
+    match latest(&store, collection) {
+        Ok((_, gifts)) => match gifts.iter().find(|gift| gift.slug == slug) {
+            Some(gift) => json(StatusCode::OK, gift_json(gift, None)),
+            None => error(
+                StatusCode::NOT_FOUND,
+                &format!("подарка {slug} нет в снимке"),
+            ),
+        },
+        Err((status, message)) => error(status, &message),
+    }
+}
+
+// Время и подарки последнего снимка коллекции, или код и текст ошибки для ответа.
+fn latest(
+    store: &Store,
+    collection: &str,
+) -> std::result::Result<(String, Vec<GiftRecord>), (StatusCode, String)> {
+    match store.latest(collection) {
+        Ok(Some(snapshot)) => Ok((
+            snapshot.taken_at.to_rfc3339(),
+            snapshot.gifts.into_iter().map(GiftRecord::from).collect(),
+        )),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("снимков коллекции {collection} нет"),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            Err((StatusCode::NOT_FOUND, e.to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+fn json(status: StatusCode, body: String) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    json(status, format!("{{\"error\": {}}}", json_string(message)))
+}
@@ -0,0 +1,452 @@
+use crate::cli::{Args, SessionAction};
+use crate::{Result, prompt};
+use grammers_client::client::auth::AuthorizationError;
+use grammers_client::session::Session;
+use grammers_client::types::{FullUser, User};
+use grammers_client::{Client, Config, FixedReconnect, InitParams, SignInError};
+use parser_nft::error::ParserError;
+use parser_nft::fetch;
+use parser_nft::redact;
+use parser_nft::status;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal as _};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+// Профиль, которым парсер пользовался до появления --session.
+pub const DEFAULT_NAME: &str = "parser";
+
+const EXTENSION: &str = "session";
+
+// Пауза между попытками переподключения.
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// Файл сессии профиля в текущей папке: «work» -> «work.session».
+pub fn file_name(name: &str) -> String {
+    format!("{name}.{EXTENSION}")
+}
+
+// Почему не получилось войти без человека у терминала.
+#[derive(Debug)]
+pub enum LoginError {
+    // Нужно ввести `what`, но stdin - не терминал, а флагом `flag` значение не передали.
+    InputRequired {
+        what: &'static str,
+        flag: &'static str,
+    },
+    // --code-command завершилась с ошибкой или ничего не напечатала.
+    CodeCommand(String),
+    // --session-data - не сессия из `session export`.
+    SessionData(String),
+    // Telegram пришлёт код только после покупки подписки в официальном приложении.
+    PaymentRequired(String),
+    // На этот номер ещё нет аккаунта Telegram.
+    SignUpRequired,
+    // Код входа неверный или устарел.
+    InvalidCode,
+    // Неверный пароль двухэтапной проверки.
+    InvalidPassword,
+    // Telegram отозвал сессию посреди работы, а войти заново без терминала нельзя.
+    SessionRevoked,
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoginError::InputRequired { what, flag } => write!(
+                f,
+                "для входа нужен {what}, но терминала нет - передайте его через {flag}"
+            ),
+            LoginError::CodeCommand(e) => write!(f, "не удалось получить код входа: {e}"),
+            LoginError::SessionData(e) => write!(f, "неверная --session-data: {e}"),
+            LoginError::PaymentRequired(product) => write!(
+                f,
+                "Telegram пришлёт код на этот номер только после покупки подписки ({product}) \
+                 в официальном приложении"
+            ),
+            LoginError::SignUpRequired => write!(
+                f,
+                "на этот номер нет аккаунта Telegram - зарегистрируйтесь в официальном приложении"
+            ),
+            LoginError::InvalidCode => {
+                write!(f, "код входа неверный или устарел, попробуйте ещё раз")
+            }
+            LoginError::InvalidPassword => write!(f, "неверный пароль двухэтапной проверки"),
+            LoginError::SessionRevoked => write!(
+                f,
+                "Telegram отозвал сессию (из аккаунта вышли или завершили сеанс) - \
+                 войдите заново, парсинг продолжится с того же места"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoginError {}
+
+impl From<LoginError> for ParserError {
+    fn from(e: LoginError) -> Self {
+        ParserError::Auth(Box::new(e))
+    }
+}
+
+// Подключается к Telegram с сессией из `file`. Если файла нет, берём готовую
+// сессию из --session-data, а без неё - начинаем новую.
+pub async fn connect(file: &str, args: &Args) -> Result<Client> {
+    connect_with(load(file, args)?, args).await
+}
+
+// Сессию отозвали: файл с ней удаляется, и, если войти заново можно (есть терминал
+// или --code-command), подключаемся с новой сессией и входим. Иначе - LoginError::SessionRevoked.
+// --session-data здесь не используется: в ней та же отозванная сессия.
+pub async fn relogin(file: &str, args: &Args) -> Result<(Client, bool)> {
+    if Path::new(file).exists() {
+        fs::remove_file(file)?;
+    }
+    if !io::stdin().is_terminal() && args.code_command.is_none() {
+        return Err(LoginError::SessionRevoked.into());
+    }
+    status!("Telegram отозвал сессию, войдите заново - парсинг продолжится с того же места");
+    let client = connect_with(Session::new(), args).await?;
+    let saved = sign_in(&client, file, args).await?;
+    Ok((client, saved))
+}
+
+// Ошибка из-за отозванной сессии: её вернул Telegram или relogin.
+pub fn is_revoked(e: &ParserError) -> bool {
+    match e {
+        ParserError::Rpc(e) | ParserError::Gift { source: e, .. } => fetch::is_session_revoked(e),
+        ParserError::Auth(e) => matches!(e.downcast_ref(), Some(LoginError::SessionRevoked)),
+        _ => false,
+    }
+}
+
+// Новое подключение вместо оборвавшегося. Сессию берём из памяти старого
+// клиента, а не из файла: файл мог не сохраниться, а авторизация в памяти есть.
+pub async fn reconnect(client: &Client, args: &Args) -> Result<Client> {
+    let session = Session::load(&client.session().save())?;
+    connect_with(session, args).await
+}
+
+// Ждёт `wait`, не давая соединению заснуть: клиент шлёт пинги и читает ответы,
+// только пока его кто-то шагает, а без этого сервер закрывает соединение через
+// --ping-interval, и первый запрос после часового ожидания упирается в обрыв.
+// Если связь всё же пропала, просто ждём дальше: переподключится первый запрос.
+pub async fn idle(client: &Client, wait: Duration) {
+    let keepalive = async {
+        while client.step().await.is_ok() {}
+        std::future::pending::<()>().await
+    };
+    tokio::select! {
+        _ = tokio::time::sleep(wait) => {}
+        _ = keepalive => {}
+    }
+}
+
+async fn connect_with(session: Session, args: &Args) -> Result<Client> {
+    let api_id = 27221966;
+    let api_hash = "7a547b8a6425910bc9181ecde48e1bcc".to_string();
+    redact::add_secret(&api_hash);
+
+    let mut params = InitParams {
+        ping_delay: Duration::from_secs(args.ping_interval),
+        flood_sleep_threshold: args.flood_sleep,
+        request_timeout: (args.request_timeout > 0)
+            .then(|| Duration::from_secs(args.request_timeout)),
+        dc_addresses: args.dc_addresses.iter().copied().collect(),
+        connections: args.connections.into(),
+        ..Default::default()
+    };
+    if args.reconnect_attempts > 0 {
+        // Политика нужна клиенту на всё время работы, а клиентов за запуск - единицы.
+        params.reconnection_policy = Box::leak(Box::new(FixedReconnect {
+            attempts: args.reconnect_attempts,
+            delay: RECONNECT_DELAY,
+        }));
+    }
+    if args.adaptive {
+        // При автоподборе скорости flood-wait нужен нам самим, а не клиенту.
+        params.flood_sleep_threshold = 0;
+    }
+    #[cfg(feature = "proxy")]
+    if let Some(proxy) = &args.proxy {
+        // В адресе прокси бывают логин и пароль.
+        redact::add_secret(proxy);
+        params.proxy_url = Some(proxy.clone());
+    }
+
+    Ok(Client::connect(Config {
+        session,
+        api_id,
+        api_hash,
+        params,
+    })
+    .await?)
+}
+
+// Входит в аккаунт, если сессия ещё не авторизована, и сохраняет её в `file`.
+// Возвращает false, если сохранить сессию не удалось: тогда после работы из
+// аккаунта нужно выйти, чтобы на сервере не осталось потерянной авторизации.
+// Всё, что обычно спрашивается в терминале, можно передать флагами - тогда
+// парсер входит сам, например на сервере. Если чего-то не хватает, а терминала
+// нет, возвращается LoginError вместо вечного ожидания stdin.
+pub async fn sign_in(client: &Client, file: &str, args: &Args) -> Result<bool> {
+    if client.is_authorized().await? {
+        return Ok(true);
+    }
+
+    status!("Signing in...");
+    let phone = ask(
+        args.phone.as_deref(),
+        "Enter your phone number (international format): ",
+        "номер телефона",
+        "--phone",
+    )?;
+    redact::add_secret(&phone);
+    let token = match client.request_login_code(&phone).await {
+        Ok(token) => token,
+        Err(AuthorizationError::PaymentRequired { store_product }) => {
+            return Err(LoginError::PaymentRequired(store_product).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let code = match &args.code_command {
+        Some(command) => run_code_command(command, &phone)?,
+        None => ask(
+            None,
+            "Enter the code you received: ",
+            "код подтверждения",
+            "--code-command",
+        )?,
+    };
+    let signed_in = client.sign_in(&token, &code).await;
+    match signed_in {
+        Err(SignInError::PasswordRequired(password_token)) => {
+            // Просии ввести номер телефона, код , пароль.
+            let hint = password_token.hint().unwrap_or("None");
+            let prompt_message = format!("Enter the password (hint {}): ", &hint);
+            let password = ask_password(args, &prompt_message)?;
+            redact::add_secret(&password);
+
+            match client.check_password(password_token, password.trim()).await {
+                Ok(_) => (),
+                Err(SignInError::InvalidPassword) => return Err(LoginError::InvalidPassword.into()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(_) => (),
+        Err(SignInError::SignUpRequired { .. }) => return Err(LoginError::SignUpRequired.into()),
+        Err(SignInError::InvalidCode) => return Err(LoginError::InvalidCode.into()),
+        Err(e) => return Err(e.into()),
+    };
+    status!("Signed in!"); // Вход
+    match client.session().save_to_file(file) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            status!("NOTE: failed to save the session, will sign out when done: {e}");
+            Ok(false)
+        }
+    }
+}
+
+// Выходит из аккаунта и удаляет файл сессии.
+pub async fn logout(client: &Client, file: &str) -> Result<()> {
+    if client.is_authorized().await? {
+        client.sign_out().await?;
+    }
+    if Path::new(file).exists() {
+        fs::remove_file(file)?;
+    }
+    Ok(())
+}
+
+// Значение из флага, а если его нет - из терминала.
+fn ask(
+    value: Option<&str>,
+    message: &str,
+    what: &'static str,
+    flag: &'static str,
+) -> Result<String> {
+    if let Some(value) = value {
+        return Ok(value.to_string());
+    }
+    if !io::stdin().is_terminal() {
+        return Err(LoginError::InputRequired { what, flag }.into());
+    }
+    prompt(message)
+}
+
+// Пароль двухэтапной проверки из --password или --password-file, а если их нет -
+// из терминала, без эха.
+fn ask_password(args: &Args, message: &str) -> Result<String> {
+    if let Some(file) = &args.password_file {
+        let password = fs::read_to_string(file).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("не удалось прочитать {}: {e}", file.display()),
+            )
+        })?;
+        return Ok(password.lines().next().unwrap_or_default().to_string());
+    }
+    if let Some(password) = &args.password {
+        return Ok(password.clone());
+    }
+    if !io::stdin().is_terminal() {
+        return Err(LoginError::InputRequired {
+            what: "пароль двухэтапной проверки",
+            flag: "--password-file",
+        }
+        .into());
+    }
+    Ok(rpassword::prompt_password(message)?)
+}
+
+// Запускает --code-command через shell и берёт код из первой строки её вывода.
+fn run_code_command(command: &str, phone: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("RUSTFIND_PHONE", phone.trim())
+        .output()
+        .map_err(|e| LoginError::CodeCommand(e.to_string()))?;
+    if !output.status.success() {
+        return Err(
+            LoginError::CodeCommand(format!("команда завершилась с {}", output.status)).into(),
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(code) if !code.is_empty() => Ok(code.to_string()),
+        _ => Err(LoginError::CodeCommand("команда ничего не напечатала".to_string()).into()),
+    }
+}
+
+// Сессия из файла, а если его нет - из --session-data (и тогда она сразу
+// сохраняется в файл, чтобы следующие запуски обходились без флага).
+fn load(file: &str, args: &Args) -> Result<Session> {
+    let data = match &args.session_data {
+        Some(data) if !Path::new(file).exists() => data,
+        _ => return Ok(Session::load_file_or_create(file)?),
+    };
+    redact::add_secret(data);
+    let bytes = from_hex(data.trim())
+        .ok_or_else(|| LoginError::SessionData("ожидается шестнадцатеричная строка".to_string()))?;
+    let session = Session::load(&bytes).map_err(|e| LoginError::SessionData(e.to_string()))?;
+    session.save_to_file(file)?;
+    Ok(session)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+// `session list|info|export|logout`. Без имени info, export и logout работают с профилем из --session.
+pub async fn session(action: &SessionAction, args: &Args) -> Result<()> {
+    match action {
+        SessionAction::List => {
+            let names = saved()?;
+            if names.is_empty() {
+                println!("Сохранённых сессий нет");
+            }
+            for name in names {
+                let file = file_name(&name);
+                redact::add_secret(&file);
+                let client = connect(&file, args).await?;
+                let account = if client.is_authorized().await? {
+                    account(me(&client).await?.user())
+                } else {
+                    "не авторизована".to_string()
+                };
+                let current = if name == args.session { " *" } else { "" };
+                println!("{name}{current}: {account}");
+            }
+        }
+        SessionAction::Info { name } => {
+            let name = name.as_deref().unwrap_or(&args.session);
+            let file = file_name(name);
+            if !Path::new(&file).exists() {
+                println!("Сессии {name} нет");
+                return Ok(());
+            }
+            let client = connect(&file, args).await?;
+            if !client.is_authorized().await? {
+                println!("{name}: не авторизована");
+                return Ok(());
+            }
+            let full = me(&client).await?;
+            println!("Профиль: {name} ({file})");
+            println!("Аккаунт: {}", account(full.user()));
+            if let Some(about) = full.about().filter(|about| !about.is_empty()) {
+                println!("О себе: {about}");
+            }
+            if let Some(count) = full.star_gifts_count() {
+                println!("Подарков в профиле: {count}");
+            }
+        }
+        SessionAction::Export { name } => {
+            let name = name.as_deref().unwrap_or(&args.session);
+            let file = file_name(name);
+            if !Path::new(&file).exists() {
+                println!("Сессии {name} нет");
+                return Ok(());
+            }
+            // Это полный доступ к аккаунту - печатаем только саму строку, без пояснений,
+            // чтобы её было удобно сразу передать в секреты деплоя.
+            println!("{}", to_hex(&fs::read(&file)?));
+        }
+        SessionAction::Logout { name } => {
+            let name = name.as_deref().unwrap_or(&args.session);
+            let file = file_name(name);
+            if !Path::new(&file).exists() {
+                println!("Сессии {name} нет");
+                return Ok(());
+            }
+            let client = connect(&file, args).await?;
+            logout(&client, &file).await?;
+            println!("Вышли из профиля {name}, файл {file} удалён");
+        }
+    }
+    Ok(())
+}
+
+// Имена профилей, для которых в текущей папке есть файл сессии, по алфавиту.
+fn saved() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(".")? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == EXTENSION)
+            && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+// Полные данные аккаунта, в который вошла сессия.
+async fn me(client: &Client) -> Result<FullUser> {
+    let me = client.get_me().await?;
+    Ok(client.get_full_user(&me).await?)
+}
+
+// Чей это аккаунт: «Имя (@username, id 123)».
+fn account(user: &User) -> String {
+    match user.username() {
+        Some(username) => format!("{} (@{username}, id {})", user.full_name(), user.id()),
+        None => format!("{} (id {})", user.full_name(), user.id()),
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use grammers_client::grammers_tl_types::{Deserializable as _, Serializable as _};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const EXTENSION: &str = "snapshot";
+
+// Время снимка в имени файла: без двоеточий, чтобы годилось для любой ФС.
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Хранилище снимков коллекций: после каждого полного парсинга подарки
+// сохраняются в <dir>/<коллекция>/<время>.snapshot в том же TL-формате, что и
+// checkpoint. По снимкам отвечает `serve` и сравниваются запуски между собой.
+pub struct Store {
+    dir: PathBuf,
+}
+
+// Все подарки коллекции на момент `taken_at`.
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub gifts: Vec<UniqueStarGift>,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // Сохраняет снимок коллекции и возвращает путь к его файлу.
+    pub fn save(
+        &self,
+        collection: &str,
+        gifts: &[UniqueStarGift],
+        taken_at: DateTime<Utc>,
+    ) -> io::Result<PathBuf> {
+        let dir = self.collection_dir(collection)?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.{EXTENSION}", taken_at.format(TIME_FORMAT)));
+        write_gifts(&path, gifts)?;
+        Ok(path)
+    }
+
+    // Коллекции, для которых есть хотя бы один снимок, по алфавиту.
+    pub fn collections(&self) -> io::Result<Vec<String>> {
+        let mut collections = Vec::new();
+        for entry in read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+                && is_collection(name)
+                && !self.snapshots(name)?.is_empty()
+            {
+                collections.push(name.to_string());
+            }
+        }
+        collections.sort();
+        Ok(collections)
+    }
+
+    // Когда снимались снимки коллекции, от старых к новым.
+    pub fn snapshots(&self, collection: &str) -> io::Result<Vec<DateTime<Utc>>> {
+        let mut times = Vec::new();
+        for entry in read_dir(&self.collection_dir(collection)?)? {
+            if let Some(time) = snapshot_time(&entry?.path()) {
+                times.push(time);
+            }
+        }
+        times.sort();
+        Ok(times)
+    }
+
+    pub fn load(&self, collection: &str, taken_at: DateTime<Utc>) -> io::Result<Snapshot> {
+        let path = self
+            .collection_dir(collection)?
+            .join(format!("{}.{EXTENSION}", taken_at.format(TIME_FORMAT)));
+        let gifts = read_gifts(&path)?;
+        Ok(Snapshot { taken_at, gifts })
+    }
+
+    // Самый свежий снимок коллекции; None, если её ещё не парсили.
+    pub fn latest(&self, collection: &str) -> io::Result<Option<Snapshot>> {
+        match self.snapshots(collection)?.last() {
+            Some(&taken_at) => self.load(collection, taken_at).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    // Папка снимков коллекции. Название приходит и из запросов к `serve`, так что
+    // выйти с его помощью за пределы хранилища («..») нельзя.
+    fn collection_dir(&self, collection: &str) -> io::Result<PathBuf> {
+        if !is_collection(collection) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("неверное название коллекции «{collection}»"),
+            ));
+        }
+        Ok(self.dir.join(collection))
+    }
+}
+
+// Когда снят снимок в файле `path`, по его имени; None, если это не снимок.
+pub fn snapshot_time(path: &Path) -> Option<DateTime<Utc>> {
+    if path.extension()? != EXTENSION {
+        return None;
+    }
+    let time = path.file_stem()?.to_str()?;
+    let time = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+    Some(time.and_utc())
+}
+
+// Подарки из файла с результатами парсинга в TL-формате: снимка (*.snapshot)
+// или состояния прерванного запуска (parsed_*.state).
+pub fn read_gifts(path: &Path) -> io::Result<Vec<UniqueStarGift>> {
+    Vec::<UniqueStarGift>::from_bytes(&fs::read(path)?).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} повреждён или не содержит подарков: {e}", path.display()),
+        )
+    })
+}
+
+// Записывает подарки в файл в том же TL-формате. Как и checkpoint, пишет через
+// временный файл, чтобы не оставить обрезанный.
+pub fn write_gifts(path: &Path, gifts: &[UniqueStarGift]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, gifts.to_vec().to_bytes())?;
+    fs::rename(tmp, path)
+}
+
+// Сводит подарки из нескольких частей (прерванные запуски, запуски по диапазонам)
+// в одну коллекцию. `parts` - от старых к новым: если подарок есть в нескольких
+// частях, остаётся самая свежая версия. Результат - по коллекциям и номерам.
+pub fn merge(parts: impl IntoIterator<Item = Vec<UniqueStarGift>>) -> Vec<UniqueStarGift> {
+    let mut merged = BTreeMap::new();
+    for gift in parts.into_iter().flatten() {
+        if let Some(name) = collection(&gift) {
+            merged.insert((name.to_string(), number(&gift)), gift);
+        }
+    }
+    merged.into_values().collect()
+}
+
+// Коллекция подарка - slug без номера, «PlushPepe» для «PlushPepe-1».
+// None для не улучшенного подарка.
+pub fn collection(gift: &UniqueStarGift) -> Option<&str> {
+    let UniqueStarGift::Gift(gift) = gift;
+    match &gift.gift {
+        tl::enums::StarGift::Unique(unique) => Some(unique.slug.rsplit_once('-')?.0),
+        tl::enums::StarGift::Gift(_) => None,
+    }
+}
+
+// Номер подарка в коллекции; 0 для не улучшенного подарка.
+pub fn number(gift: &UniqueStarGift) -> i32 {
+    let UniqueStarGift::Gift(gift) = gift;
+    match &gift.gift {
+        tl::enums::StarGift::Unique(unique) => unique.num,
+        tl::enums::StarGift::Gift(_) => 0,
+    }
+}
+
+// Названия коллекций в Telegram состоят из латиницы и цифр, как «PlushPepe».
+fn is_collection(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// Содержимое папки; пустое, если её ещё нет.
+fn read_dir(dir: &Path) -> io::Result<Vec<io::Result<fs::DirEntry>>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Ok(entries.collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Result, prompt};
+use grammers_client::Client;
+use grammers_client::grammers_tl_types as tl;
+use grammers_client::types::{SavedStarGiftId, StarGiftUpgrade};
+
+// Улучшает полученный подарок до уникального. `id` - номер сообщения, с которым
+// пришёл подарок (его видно в ссылке на это сообщение).
+// Если отправитель не оплатил улучшение заранее, звёзды списываются с баланса -
+// поэтому сначала проверяем баланс и спрашиваем подтверждение (кроме --yes).
+pub async fn upgrade(
+    client: &Client,
+    id: i32,
+    keep_original_details: bool,
+    yes: bool,
+) -> Result<()> {
+    let me = client.get_me().await?;
+    let mut gifts = client.iter_saved_star_gifts(&me);
+    let mut found = None;
+    while let Some(gift) = gifts.next().await? {
+        if gift.msg_id() == Some(id) {
+            found = Some(gift);
+            break;
+        }
+    }
+    let Some(gift) = found else {
+        println!("Среди полученных подарков нет подарка с id {id}");
+        return Ok(());
+    };
+
+    let tl::enums::StarGift::Gift(info) = gift.gift() else {
+        println!("Подарок {id} уже уникальный");
+        return Ok(());
+    };
+    if !gift.can_upgrade() {
+        println!("Подарок {id} нельзя улучшить");
+        return Ok(());
+    }
+    let title = info.title.as_deref().unwrap_or("без названия");
+
+    let prepaid = gift.prepaid_upgrade_stars().is_some();
+    if prepaid {
+        println!("Улучшение подарка «{title}» уже оплачено отправителем");
+    } else {
+        let cost = info.upgrade_stars.unwrap_or(0);
+        let balance = client
+            .get_star_balance()
+            .await?
+            .balance()
+            .stars()
+            .unwrap_or(0);
+        println!("Улучшение подарка «{title}» стоит {cost} ⭐, на балансе {balance} ⭐");
+        if keep_original_details {
+            println!("Сохранение исходных данных подарка оплачивается отдельно");
+        }
+        if balance < cost {
+            println!("Не хватает звёзд для улучшения");
+            return Ok(());
+        }
+    }
+
+    if !yes {
+        let answer = prompt("Улучшить подарок? [y/N] ")?;
+        if !matches!(answer.trim(), "y" | "Y" | "д" | "Д") {
+            println!("Отменено");
+            return Ok(());
+        }
+    }
+
+    let unique = client
+        .upgrade_star_gift(
+            SavedStarGiftId::User { msg_id: id },
+            keep_original_details,
+            prepaid,
+        )
+        .await?;
+    match unique {
+        StarGiftUpgrade::Upgraded(Some(unique)) => println!(
+            "Готово: {} #{} - https://t.me/nft/{}",
+            unique.title(),
+            unique.num(),
+            unique.slug()
+        ),
+        StarGiftUpgrade::Upgraded(None) => println!("Готово, подарок улучшен"),
+        // Звёзды ещё не списаны: без подтверждения подарок остаётся прежним.
+        StarGiftUpgrade::VerificationNeeded { url } => {
+            println!("Telegram просит подтвердить оплату: {url}");
+            println!("Подарок будет улучшен после подтверждения");
+        }
+    }
+    Ok(())
+}
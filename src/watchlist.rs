@@ -0,0 +1,218 @@
+use crate::cli::Args;
+use crate::get::parse_slug;
+use crate::{Result, owner};
+use grammers_client::Client;
+use grammers_client::grammers_tl_types::enums::payments::UniqueStarGift;
+use parser_nft::error::ParserError;
+use parser_nft::fetch::rate::RateLimiter;
+use parser_nft::model::GiftRecord;
+use parser_nft::notify::rules::AlertRules;
+use parser_nft::notify::webhook::{self, Webhook};
+use parser_nft::notify::{Event, telegram};
+use parser_nft::report::diff::Changes;
+use parser_nft::status;
+use parser_nft::store;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Список отдельных подарков, за которыми следим вместо целых коллекций:
+// по запросу на подарок вместо парсинга коллекции целиком.
+//
+// Формат файла - по slug на строке, `#` - комментарий:
+//   PlushPepe-123
+//   DurovsCap-7  # свой
+pub fn load(path: &Path) -> io::Result<Vec<String>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+// Добавляет подарки в список; уже добавленные не повторяются.
+pub fn add(path: &Path, inputs: &[String]) -> io::Result<()> {
+    let mut slugs = load(path)?;
+    let mut text = fs::read_to_string(path).unwrap_or_default();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    for input in inputs {
+        let slug = parse_slug(input).to_string();
+        if slugs.contains(&slug) {
+            println!("{slug} уже в списке");
+            continue;
+        }
+        text.push_str(&slug);
+        text.push('\n');
+        println!("{slug} добавлен в список");
+        slugs.push(slug);
+    }
+    fs::write(path, text)
+}
+
+// Убирает подарки из списка; комментарии и остальные строки остаются как были.
+pub fn remove(path: &Path, inputs: &[String]) -> io::Result<()> {
+    let slugs: Vec<&str> = inputs.iter().map(|input| parse_slug(input)).collect();
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut removed = Vec::new();
+    let kept: String = text
+        .lines()
+        .filter(|line| {
+            let slug = line.split('#').next().unwrap_or("").trim();
+            let remove = slugs.contains(&slug);
+            if remove {
+                removed.push(slug.to_string());
+            }
+            !remove
+        })
+        .map(|line| format!("{line}\n"))
+        .collect();
+    for slug in slugs {
+        if removed.iter().any(|removed| removed == slug) {
+            println!("{slug} убран из списка");
+        } else {
+            println!("{slug} не было в списке");
+        }
+    }
+    fs::write(path, kept)
+}
+
+pub fn list(path: &Path) -> io::Result<()> {
+    let slugs = load(path)?;
+    if slugs.is_empty() {
+        println!("Список {} пуст", path.display());
+    }
+    for slug in slugs {
+        println!("{slug}");
+    }
+    Ok(())
+}
+
+// Последнее, что видели у подарков списка, - рядом со списком в TL-формате.
+fn state_file(path: &Path) -> PathBuf {
+    path.with_extension("state")
+}
+
+// Загружает подарки `slugs` не быстрее `rps` запросов в секунду. Возвращает
+// полученные подарки и ошибки неполученных (ParserError::Gift); повторы
+// загружаются один раз.
+pub async fn fetch(
+    client: &Client,
+    slugs: &[String],
+    rps: f64,
+) -> (Vec<UniqueStarGift>, Vec<ParserError>) {
+    let mut limiter = RateLimiter::new(rps);
+    let mut fetched = Vec::new();
+    let mut failed = Vec::new();
+    // Подарки запрашиваются пачками по секунде запросов: пачка уходит по соединению
+    // разом, не дожидаясь ответа на каждый подарок, а в среднем не быстрее --rps.
+    let batch = (rps.ceil() as usize).max(1);
+    for slugs in slugs.chunks(batch) {
+        for _ in slugs {
+            limiter.acquire().await;
+        }
+        let mut gifts = client.get_unique_star_gifts(slugs).await;
+        for slug in slugs {
+            match gifts.remove(slug) {
+                Some(Ok(gift)) => fetched.push(gift.into()),
+                Some(Err(e)) => {
+                    status!("{slug}: {e}");
+                    failed.push(ParserError::Gift {
+                        gift: slug.clone(),
+                        source: e,
+                    });
+                }
+                // Повтор в списке: подарок уже получен.
+                None => {}
+            }
+        }
+    }
+    (fetched, failed)
+}
+
+// Загружает подарки из списка и сообщает, кто из них с прошлой проверки сменил
+// владельца или выставлен на продажу: в консоль, в --alert-chat и на вебхуки.
+pub async fn check(client: &Client, path: &Path, args: &Args) -> Result<()> {
+    let slugs = load(path)?;
+    if slugs.is_empty() {
+        println!(
+            "Список {} пуст, добавьте подарки командой watchlist add",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let state = state_file(path);
+    let previous = if state.exists() {
+        store::read_gifts(&state)?
+    } else {
+        Vec::new()
+    };
+    // Неполученный подарок сравним в следующий раз с тем, что видели раньше.
+    let (fetched, _) = fetch(client, &slugs, args.rps).await;
+
+    let before: Vec<GiftRecord> = previous.iter().cloned().map(GiftRecord::from).collect();
+    let after: Vec<GiftRecord> = fetched.iter().cloned().map(GiftRecord::from).collect();
+    let changes = Changes::new(&before, &after);
+    // Новые подарки здесь - только что добавленные в список, а не новые улучшения,
+    // так что правила --alert-rules к ним не применяются.
+    let messages = telegram::alert_messages("watchlist", &changes, &AlertRules::default());
+    if messages.is_empty() {
+        status!(
+            "Проверено подарков: {} из {}, изменений нет",
+            after.len(),
+            slugs.len()
+        );
+    }
+    for message in &messages {
+        println!("{message}");
+    }
+
+    if let Some(chat) = args.alert_chat.as_ref().filter(|_| !messages.is_empty()) {
+        let sent: Result<()> = async {
+            let chat = owner::resolve(client, chat).await?;
+            telegram::send_alert(client, chat.pack(), &messages).await?;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = sent {
+            status!("Не удалось отправить оповещение в {chat}: {e}");
+        }
+    }
+    if !args.webhooks.is_empty() {
+        let webhooks: Vec<Webhook> = args
+            .webhooks
+            .iter()
+            .map(|url| Webhook::new(url, args.webhook_secret.clone()))
+            .collect();
+        // Подарок, только что добавленный в список, - не новость.
+        let events: Vec<Event> = Event::from_changes("watchlist", &changes)
+            .into_iter()
+            .filter(|event| !matches!(event, Event::NewGifts { .. }))
+            .collect();
+        webhook::send_all(&webhooks, &events).await;
+    }
+
+    // Свежие версии поверх прошлых; убранные из списка подарки забываются.
+    let gifts: Vec<_> = store::merge([previous, fetched])
+        .into_iter()
+        .filter(|gift| {
+            store::collection(gift).is_some_and(|collection| {
+                slugs.contains(&format!("{collection}-{}", store::number(gift)))
+            })
+        })
+        .collect();
+    store::write_gifts(&state, &gifts)?;
+    Ok(())
+}